@@ -1,7 +1,10 @@
 const COMMANDS: &[&str] = &[
     "rpc_call",
     "rpc_call_batch",
+    "rpc_call_batch_stream",
     "rpc_procedures",
+    "rpc_schema",
+    "rpc_cancel",
     "rpc_subscribe",
     "rpc_unsubscribe",
     "rpc_subscription_count",
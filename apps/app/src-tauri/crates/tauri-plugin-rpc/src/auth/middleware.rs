@@ -63,7 +63,7 @@ where
                 "Authentication successful"
             );
 
-            next(ctx, req).await
+            next(ctx.with_auth(auth_result), req).await
         }
     };
     from_fn(middleware)
@@ -118,7 +118,7 @@ where
                         user_id = ?auth_result.user_id,
                         "Authorization granted"
                     );
-                    next(ctx, req).await
+                    next(ctx.with_auth(auth_result), req).await
                 }
                 AuthorizationResult::Unauthorized => {
                     tracing::debug!(
@@ -230,7 +230,7 @@ where
                 "Role check passed"
             );
 
-            next(ctx, req).await
+            next(ctx.with_auth(auth_result), req).await
         }
     };
     from_fn(middleware)
@@ -18,7 +18,7 @@
 //!     fn authenticate(&self, request: &Request) -> Pin<Box<dyn Future<Output = AuthResult> + Send + '_>> {
 //!         Box::pin(async move {
 //!             // Extract token from request and validate
-//!             if let Some(token) = request.input.get("token").and_then(|v| v.as_str()) {
+//!             if let Some(token) = request.envelope.bearer_token() {
 //!                 if self.validate_token(token) {
 //!                     return AuthResult::authenticated("user-123")
 //!                         .with_roles(vec!["user", "admin"]);
@@ -56,7 +56,8 @@ pub trait AuthProvider: Send + Sync {
     ///
     /// # Implementation Notes
     ///
-    /// - Extract credentials from `request.input` (e.g., token, API key)
+    /// - Extract credentials from `request.envelope` (e.g., `request.metadata("authorization")`)
+    ///   or, for legacy clients, from `request.input`
     /// - Validate credentials (check signature, expiration, etc.)
     /// - Return `AuthResult::authenticated()` with user info on success
     /// - Return `AuthResult::unauthenticated()` on failure
@@ -170,6 +171,7 @@ mod tests {
             path: "test".to_string(),
             procedure_type: crate::middleware::ProcedureType::Query,
             input: json!({}),
+            envelope: Default::default(),
         }
     }
 
@@ -1,6 +1,7 @@
 //! Authorization rules and pattern matching
 
 use crate::auth::types::AuthResult;
+use crate::pattern::PathPattern;
 
 // =============================================================================
 // Compiled Pattern
@@ -8,63 +9,14 @@ use crate::auth::types::AuthResult;
 
 /// Compiled pattern for efficient path matching.
 ///
-/// Patterns are compiled once during rule creation to avoid
-/// runtime string operations on every authorization check.
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum CompiledPattern {
-    /// Matches everything (pattern: "*")
-    Wildcard,
-    /// Exact string match (pattern: "user.get")
-    Exact(String),
-    /// Prefix match (pattern: "user.*" -> prefix: "user")
-    Prefix(String),
-}
-
-impl CompiledPattern {
-    /// Compile a pattern string into an efficient representation.
-    ///
-    /// # Supported Patterns
-    ///
-    /// - `"*"` - Matches everything
-    /// - `"user.get"` - Exact match
-    /// - `"user.*"` - Prefix match (matches "user", "user.get", "user.create", etc.)
-    ///
-    /// # Example
-    ///
-    /// ```rust,ignore
-    /// let pattern = CompiledPattern::compile("user.*");
-    /// assert!(pattern.matches("user.get"));
-    /// assert!(pattern.matches("user"));
-    /// assert!(!pattern.matches("admin.get"));
-    /// ```
-    pub fn compile(pattern: &str) -> Self {
-        if pattern == "*" {
-            return Self::Wildcard;
-        }
-
-        if let Some(prefix) = pattern.strip_suffix(".*") {
-            return Self::Prefix(prefix.to_string());
-        }
-
-        Self::Exact(pattern.to_string())
-    }
-
-    /// Check if this pattern matches a path.
-    ///
-    /// # Performance
-    ///
-    /// This method is optimized for hot-path usage:
-    /// - Wildcard: O(1)
-    /// - Exact: O(n) string comparison
-    /// - Prefix: O(n) string comparison, no allocations
-    pub fn matches(&self, path: &str) -> bool {
-        match self {
-            Self::Wildcard => true,
-            Self::Exact(exact) => path == exact,
-            Self::Prefix(prefix) => path == prefix || path.starts_with(&format!("{}.", prefix)),
-        }
-    }
-}
+/// Auth rules use the crate-wide [`PathPattern`], so they accept the same
+/// syntax as cache invalidation, rate limits and logging exclusions:
+/// `user.*`, `user.*.get`, `user.**`, `user.{get,list}` and `!admin.*`.
+///
+/// This used to be an enum with `Wildcard`, `Exact` and `Prefix` variants;
+/// use [`PathPattern::matches`] and [`PathPattern::as_str`] instead of
+/// matching on them.
+pub type CompiledPattern = PathPattern;
 
 // =============================================================================
 // Auth Rule
@@ -93,8 +45,6 @@ impl CompiledPattern {
 pub struct AuthRule {
     /// Compiled pattern for efficient matching
     pattern: CompiledPattern,
-    /// Original pattern string (for display/debugging)
-    path_pattern: String,
     /// Required roles (empty means any authenticated user)
     pub required_roles: Vec<String>,
     /// Whether this path is public (no auth required)
@@ -113,10 +63,8 @@ impl AuthRule {
     /// assert!(rule.is_satisfied_by(&AuthResult::unauthenticated()));
     /// ```
     pub fn public(pattern: impl Into<String>) -> Self {
-        let pattern_str = pattern.into();
         Self {
-            pattern: CompiledPattern::compile(&pattern_str),
-            path_pattern: pattern_str,
+            pattern: CompiledPattern::compile(&pattern.into()),
             required_roles: Vec::new(),
             public: true,
             require_all_roles: false,
@@ -133,10 +81,8 @@ impl AuthRule {
     /// assert!(rule.is_satisfied_by(&AuthResult::authenticated("user-123")));
     /// ```
     pub fn requires_auth(pattern: impl Into<String>) -> Self {
-        let pattern_str = pattern.into();
         Self {
-            pattern: CompiledPattern::compile(&pattern_str),
-            path_pattern: pattern_str,
+            pattern: CompiledPattern::compile(&pattern.into()),
             required_roles: Vec::new(),
             public: false,
             require_all_roles: false,
@@ -159,10 +105,8 @@ impl AuthRule {
         pattern: impl Into<String>,
         roles: impl IntoIterator<Item = impl Into<String>>,
     ) -> Self {
-        let pattern_str = pattern.into();
         Self {
-            pattern: CompiledPattern::compile(&pattern_str),
-            path_pattern: pattern_str,
+            pattern: CompiledPattern::compile(&pattern.into()),
             required_roles: roles.into_iter().map(|r| r.into()).collect(),
             public: false,
             require_all_roles: false,
@@ -189,7 +133,7 @@ impl AuthRule {
 
     /// Get the original pattern string (for display/debugging).
     pub fn pattern_string(&self) -> &str {
-        &self.path_pattern
+        self.pattern.as_str()
     }
 
     /// Check if the auth result satisfies this rule.
@@ -233,7 +177,7 @@ mod tests {
     #[test]
     fn test_compiled_pattern_wildcard() {
         let pattern = CompiledPattern::compile("*");
-        assert_eq!(pattern, CompiledPattern::Wildcard);
+        assert_eq!(pattern.as_str(), "*");
         assert!(pattern.matches("anything"));
         assert!(pattern.matches("user.get"));
     }
@@ -241,7 +185,6 @@ mod tests {
     #[test]
     fn test_compiled_pattern_exact() {
         let pattern = CompiledPattern::compile("user.get");
-        assert_eq!(pattern, CompiledPattern::Exact("user.get".to_string()));
         assert!(pattern.matches("user.get"));
         assert!(!pattern.matches("user.create"));
     }
@@ -249,13 +192,25 @@ mod tests {
     #[test]
     fn test_compiled_pattern_prefix() {
         let pattern = CompiledPattern::compile("user.*");
-        assert_eq!(pattern, CompiledPattern::Prefix("user".to_string()));
         assert!(pattern.matches("user"));
         assert!(pattern.matches("user.get"));
         assert!(pattern.matches("user.create"));
         assert!(!pattern.matches("admin.get"));
     }
 
+    #[test]
+    fn test_auth_rule_extended_patterns() {
+        let rule = AuthRule::requires_auth("user.*.{get,list}");
+        assert!(rule.matches("user.posts.get"));
+        assert!(rule.matches("user.posts.list"));
+        assert!(!rule.matches("user.posts.delete"));
+        assert!(!rule.matches("user.get"));
+
+        let rule = AuthRule::public("!admin.**");
+        assert!(rule.matches("user.get"));
+        assert!(!rule.matches("admin.users.delete"));
+    }
+
     #[test]
     fn test_auth_rule_public() {
         let rule = AuthRule::public("health");
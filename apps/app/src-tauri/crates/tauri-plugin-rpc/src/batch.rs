@@ -9,6 +9,13 @@
 //! - **Fail-fast validation**: Validates batch before executing any requests
 //! - **Metrics tracking**: Collects success/error counts and execution duration
 //! - **Error context preservation**: Maintains full error information in results
+//! - **Dependent requests**: `dependsOn` ids are scheduled as a DAG, and
+//!   `{"$ref": "id#/pointer"}` inputs feed the results of declared
+//!   dependencies into later requests
+//! - **Atomic batches**: stop at the first error and run compensation handlers
+//!   of already-succeeded mutations in reverse order
+//! - **Result streaming**: [`execute_batch_streaming`] reports each result as
+//!   soon as it settles, which the plugin forwards as [`BatchStreamEvent`]s
 //!
 //! # Example
 //!
@@ -17,19 +24,29 @@
 //!
 //! let batch = BatchRequest {
 //!     requests: vec![
-//!         SingleRequest { id: "1".into(), path: "user.get".into(), input: json!({"id": 1}) },
-//!         SingleRequest { id: "2".into(), path: "user.list".into(), input: json!(null) },
+//!         SingleRequest { id: "1".into(), path: "user.get".into(), input: json!({"id": 1}), depends_on: vec![] },
+//!         SingleRequest { id: "2".into(), path: "user.list".into(), input: json!(null), depends_on: vec![] },
 //!     ],
+//!     atomic: false,
+//!     envelope: RequestEnvelope::default(),
 //! };
 //!
 //! let (response, metrics) = execute_batch(batch, router, &config).await?;
 //! println!("Processed {} requests in {}ms", metrics.total_requests, metrics.duration_ms);
 //! ```
 
-use crate::{RpcConfig, RpcError, plugin::DynRouter};
+use crate::{
+    RpcConfig, RpcError,
+    middleware::RequestEnvelope,
+    plugin::{DynRouter, call_with_default_timeout},
+    router::with_timeout,
+};
 use futures::stream::{FuturesUnordered, StreamExt};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::{debug, trace, warn};
 
 // =============================================================================
@@ -43,9 +60,21 @@ pub struct BatchConfig {
     /// Requests exceeding this limit will be rejected.
     pub max_batch_size: usize,
     /// Whether to execute requests in parallel.
-    /// When true, uses `futures::join_all` for concurrent execution.
+    /// When true, runs up to `max_concurrency` requests concurrently.
     /// When false, executes requests sequentially.
     pub parallel_execution: bool,
+    /// Whether every batch runs atomically, as if it set [`BatchRequest::atomic`].
+    #[serde(default)]
+    pub atomic: bool,
+    /// Maximum number of requests of one batch running at the same time.
+    /// `None` runs every ready request at once.
+    #[serde(default)]
+    pub max_concurrency: Option<usize>,
+    /// Timeout in milliseconds applied to each request of a batch, on top of
+    /// any procedure or default timeout. A request exceeding it gets its own
+    /// `TIMEOUT` result; the rest of the batch is unaffected.
+    #[serde(default)]
+    pub item_timeout_ms: Option<u64>,
 }
 
 impl Default for BatchConfig {
@@ -53,6 +82,9 @@ impl Default for BatchConfig {
         Self {
             max_batch_size: 100,
             parallel_execution: true,
+            atomic: false,
+            max_concurrency: None,
+            item_timeout_ms: None,
         }
     }
 }
@@ -83,12 +115,61 @@ impl BatchConfig {
         self
     }
 
+    /// Set whether every batch runs atomically.
+    #[must_use = "This method returns a new BatchConfig and does not modify self"]
+    pub fn with_atomic(mut self, atomic: bool) -> Self {
+        trace!(atomic, "Setting batch atomic execution");
+        self.atomic = atomic;
+        self
+    }
+
+    /// Limit how many requests of one batch run at the same time.
+    #[must_use = "This method returns a new BatchConfig and does not modify self"]
+    pub fn with_max_concurrency(mut self, limit: usize) -> Self {
+        trace!(max_concurrency = limit, "Setting batch max concurrency");
+        self.max_concurrency = Some(limit);
+        self
+    }
+
+    /// Set the timeout applied to each request of a batch.
+    #[must_use = "This method returns a new BatchConfig and does not modify self"]
+    pub fn with_item_timeout(mut self, timeout: Duration) -> Self {
+        trace!(
+            item_timeout_ms = timeout.as_millis() as u64,
+            "Setting batch item timeout"
+        );
+        self.item_timeout_ms = Some(timeout.as_millis() as u64);
+        self
+    }
+
+    /// Get the timeout applied to each request of a batch.
+    pub fn item_timeout(&self) -> Option<Duration> {
+        self.item_timeout_ms.map(Duration::from_millis)
+    }
+
+    /// Number of requests allowed in flight at once.
+    pub(crate) fn concurrency(&self) -> usize {
+        if self.parallel_execution {
+            self.max_concurrency.unwrap_or(usize::MAX)
+        } else {
+            1
+        }
+    }
+
     /// Validate the batch configuration.
     pub fn validate(&self) -> Result<(), String> {
         if self.max_batch_size == 0 {
             warn!("BatchConfig validation failed: max_batch_size must be greater than 0");
             return Err("max_batch_size must be greater than 0".to_string());
         }
+        if self.max_concurrency == Some(0) {
+            warn!("BatchConfig validation failed: max_concurrency must be greater than 0");
+            return Err("max_concurrency must be greater than 0".to_string());
+        }
+        if self.item_timeout_ms == Some(0) {
+            warn!("BatchConfig validation failed: item_timeout_ms must be greater than 0");
+            return Err("item_timeout_ms must be greater than 0".to_string());
+        }
         trace!(
             max_batch_size = self.max_batch_size,
             parallel = self.parallel_execution,
@@ -104,6 +185,7 @@ impl BatchConfig {
 
 /// A single request within a batch.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SingleRequest {
     /// Unique identifier for this request within the batch.
     /// Used to correlate results with requests.
@@ -114,6 +196,23 @@ pub struct SingleRequest {
     /// Defaults to null if not provided.
     #[serde(default = "default_input")]
     pub input: serde_json::Value,
+    /// IDs of requests in the same batch that must succeed before this one runs.
+    /// Only these requests can be referenced from `input` via
+    /// `{"$ref": "id#/pointer"}`; any other `$ref` object is plain data.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+}
+
+impl SingleRequest {
+    /// IDs of the requests this one waits for, from `depends_on`.
+    pub fn dependencies(&self) -> Vec<&str> {
+        let mut seen = HashSet::new();
+        self.depends_on
+            .iter()
+            .map(String::as_str)
+            .filter(|id| seen.insert(*id))
+            .collect()
+    }
 }
 
 /// Default input value for requests without input.
@@ -126,6 +225,17 @@ fn default_input() -> serde_json::Value {
 pub struct BatchRequest {
     /// The list of requests to process.
     pub requests: Vec<SingleRequest>,
+    /// Run the batch all-or-nothing.
+    ///
+    /// Requests run one at a time in dependency order. Execution stops at the
+    /// first error and the compensation handlers of requests that already
+    /// succeeded run in reverse order.
+    #[serde(default)]
+    pub atomic: bool,
+    /// Transport metadata shared by every request in the batch.
+    /// Set by the plugin from the `meta` command argument.
+    #[serde(skip)]
+    pub envelope: RequestEnvelope,
 }
 
 impl BatchRequest {
@@ -134,9 +244,25 @@ impl BatchRequest {
         trace!("Creating new empty BatchRequest");
         Self {
             requests: Vec::new(),
+            atomic: false,
+            envelope: RequestEnvelope::default(),
         }
     }
 
+    /// Set whether the batch runs all-or-nothing.
+    #[must_use = "This method returns a new BatchRequest and does not modify self"]
+    pub fn with_atomic(mut self, atomic: bool) -> Self {
+        self.atomic = atomic;
+        self
+    }
+
+    /// Attach the request envelope passed to every request in the batch.
+    #[must_use = "This method returns a new BatchRequest and does not modify self"]
+    pub fn with_envelope(mut self, envelope: RequestEnvelope) -> Self {
+        self.envelope = envelope;
+        self
+    }
+
     /// Add a request to the batch.
     pub fn add(
         mut self,
@@ -147,10 +273,52 @@ impl BatchRequest {
         let id = id.into();
         let path = path.into();
         trace!(request_id = %id, path = %path, "Adding request to batch");
-        self.requests.push(SingleRequest { id, path, input });
+        self.requests.push(SingleRequest {
+            id,
+            path,
+            input,
+            depends_on: Vec::new(),
+        });
         self
     }
 
+    /// Add a request that runs only after `depends_on` have succeeded.
+    pub fn add_dependent<I, S>(
+        mut self,
+        id: impl Into<String>,
+        path: impl Into<String>,
+        input: serde_json::Value,
+        depends_on: I,
+    ) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let id = id.into();
+        let path = path.into();
+        let depends_on: Vec<String> = depends_on.into_iter().map(Into::into).collect();
+        trace!(
+            request_id = %id,
+            path = %path,
+            depends_on = ?depends_on,
+            "Adding dependent request to batch"
+        );
+        self.requests.push(SingleRequest {
+            id,
+            path,
+            input,
+            depends_on,
+        });
+        self
+    }
+
+    /// Check whether any request depends on another request in the batch.
+    pub fn has_dependencies(&self) -> bool {
+        self.requests
+            .iter()
+            .any(|req| !req.dependencies().is_empty())
+    }
+
     /// Get the number of requests in the batch.
     pub fn len(&self) -> usize {
         self.requests.len()
@@ -278,11 +446,33 @@ impl BatchResult {
     }
 }
 
+/// Outcome of a compensation handler run after an atomic batch failed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompensationResult {
+    /// The ID of the request whose effects were undone.
+    pub id: String,
+    /// The procedure path of that request.
+    pub path: String,
+    /// The error returned by the compensation handler, if it failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl CompensationResult {
+    /// Check if the compensation succeeded.
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
 /// Response containing results for all requests in a batch.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BatchResponse {
     /// Results for each request, in the same order as the input requests.
     pub results: Vec<BatchResult>,
+    /// Compensations run after an atomic batch failed, in execution order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub compensations: Vec<CompensationResult>,
 }
 
 impl BatchResponse {
@@ -296,7 +486,17 @@ impl BatchResponse {
             errors = error_count,
             "Created batch response"
         );
-        Self { results }
+        Self {
+            results,
+            compensations: Vec::new(),
+        }
+    }
+
+    /// Attach the compensations run for a failed atomic batch.
+    #[must_use = "This method returns a new BatchResponse and does not modify self"]
+    pub fn with_compensations(mut self, compensations: Vec<CompensationResult>) -> Self {
+        self.compensations = compensations;
+        self
     }
 
     /// Get the number of results.
@@ -335,6 +535,42 @@ impl BatchResponse {
     }
 }
 
+// =============================================================================
+// Batch Streaming
+// =============================================================================
+
+/// Callback receiving each batch result as soon as it settles.
+pub type ResultSink<'a> = &'a (dyn Fn(&BatchResult) + Send + Sync);
+
+/// Event emitted to the frontend while a streaming batch runs.
+///
+/// Every request produces one `result` event, in completion order, followed
+/// by a single `complete` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum BatchStreamEvent {
+    /// A request in the batch finished.
+    Result {
+        /// The result of the request.
+        result: BatchResult,
+    },
+    /// Every request in the batch finished.
+    Complete {
+        /// Metrics for the whole batch.
+        metrics: BatchMetrics,
+        /// Compensations run after an atomic batch failed.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        compensations: Vec<CompensationResult>,
+    },
+}
+
+/// Build the event name a streaming batch emits on.
+///
+/// Format: `{prefix}batch_{batch_id}`, alongside subscription events.
+pub fn batch_event_name(prefix: &str, batch_id: &str) -> String {
+    format!("{}batch_{}", prefix, batch_id)
+}
+
 // =============================================================================
 // Batch Execution
 // =============================================================================
@@ -396,6 +632,29 @@ pub async fn execute_batch(
     batch: BatchRequest,
     router: Arc<dyn DynRouter>,
     rpc_config: &RpcConfig,
+) -> Result<(BatchResponse, BatchMetrics), RpcError> {
+    execute_batch_streaming(batch, router, rpc_config, &|_| {}).await
+}
+
+/// Execute a batch like [`execute_batch`], passing each result to `on_result`
+/// as soon as it settles.
+///
+/// Results are reported in completion order; the returned response still
+/// lists them in request order.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let (response, metrics) = execute_batch_streaming(batch, router, &config, &|result| {
+///     println!("{} finished", result.id);
+/// })
+/// .await?;
+/// ```
+pub async fn execute_batch_streaming(
+    batch: BatchRequest,
+    router: Arc<dyn DynRouter>,
+    rpc_config: &RpcConfig,
+    on_result: ResultSink<'_>,
 ) -> Result<(BatchResponse, BatchMetrics), RpcError> {
     let start = std::time::Instant::now();
     let batch_config = &rpc_config.batch_config;
@@ -420,10 +679,47 @@ pub async fn execute_batch(
     let mut metrics = BatchMetrics::new(total_requests);
 
     // Execute batch using streaming for better performance
-    let results = if batch_config.parallel_execution {
-        execute_parallel(&batch, router).await
+    let mut compensations = Vec::new();
+    let results = if batch.atomic || batch_config.atomic {
+        let (results, ran) = execute_atomic(
+            &batch,
+            |req, input| {
+                call_item(
+                    router.as_ref(),
+                    &req.path,
+                    input,
+                    batch.envelope.clone(),
+                    rpc_config,
+                )
+            },
+            |req, input, output| {
+                router.compensate(&req.path, input, output, batch.envelope.clone())
+            },
+            on_result,
+        )
+        .await;
+        compensations = ran;
+        results
+    } else if batch.has_dependencies() {
+        execute_with_dependencies(
+            &batch,
+            batch_config.concurrency(),
+            |req, input| {
+                call_item(
+                    router.as_ref(),
+                    &req.path,
+                    input,
+                    batch.envelope.clone(),
+                    rpc_config,
+                )
+            },
+            on_result,
+        )
+        .await
+    } else if batch_config.parallel_execution {
+        execute_parallel(&batch, router, rpc_config, on_result).await
     } else {
-        execute_sequential(&batch, router).await
+        execute_sequential(&batch, router, rpc_config, on_result).await
     };
 
     // Update metrics
@@ -445,42 +741,91 @@ pub async fn execute_batch(
         "Batch execution completed"
     );
 
-    let response = BatchResponse::new(results);
+    let response = BatchResponse::new(results).with_compensations(compensations);
     Ok((response, metrics))
 }
 
+/// Call one batch request, applying the procedure, default and per-item
+/// timeouts.
+async fn call_item(
+    router: &dyn DynRouter,
+    path: &str,
+    input: serde_json::Value,
+    envelope: RequestEnvelope,
+    rpc_config: &RpcConfig,
+) -> Result<serde_json::Value, RpcError> {
+    let request_id = envelope.request_id.clone();
+    with_timeout(
+        path,
+        request_id.as_deref(),
+        rpc_config.batch_config.item_timeout(),
+        call_with_default_timeout(
+            router,
+            path,
+            input,
+            envelope,
+            rpc_config.default_timeout(),
+            Arc::default(),
+        ),
+    )
+    .await
+}
+
 /// Execute batch requests in parallel using FuturesUnordered for streaming.
-async fn execute_parallel(batch: &BatchRequest, router: Arc<dyn DynRouter>) -> Vec<BatchResult> {
+///
+/// At most `max_concurrency` requests run at once; the next one starts as
+/// soon as a running request finishes.
+async fn execute_parallel(
+    batch: &BatchRequest,
+    router: Arc<dyn DynRouter>,
+    rpc_config: &RpcConfig,
+    on_result: ResultSink<'_>,
+) -> Vec<BatchResult> {
+    let limit = rpc_config.batch_config.concurrency();
+    let mut pending = batch.requests.iter();
     let mut futures = FuturesUnordered::new();
+    let mut results = Vec::with_capacity(batch.len());
 
-    for req in &batch.requests {
-        let id = req.id.clone();
-        let path = req.path.clone();
-        let input = req.input.clone();
-        let router = router.clone();
-
-        futures.push(async move {
-            match router.call(&path, input).await {
-                Ok(data) => {
-                    debug!(request_id = %id, path = %path, "Batch request succeeded");
-                    BatchResult::success(id, data)
-                }
-                Err(error) => {
-                    warn!(
-                        request_id = %id,
-                        path = %path,
-                        error_code = %error.code,
-                        error_message = %error.message,
-                        "Batch request failed"
-                    );
-                    BatchResult::error(id, error)
+    loop {
+        while futures.len() < limit {
+            let Some(req) = pending.next() else {
+                break;
+            };
+            let envelope = batch.envelope.clone();
+            let router = router.clone();
+
+            futures.push(async move {
+                match call_item(
+                    router.as_ref(),
+                    &req.path,
+                    req.input.clone(),
+                    envelope,
+                    rpc_config,
+                )
+                .await
+                {
+                    Ok(data) => {
+                        debug!(request_id = %req.id, path = %req.path, "Batch request succeeded");
+                        BatchResult::success(&req.id, data)
+                    }
+                    Err(error) => {
+                        warn!(
+                            request_id = %req.id,
+                            path = %req.path,
+                            error_code = %error.code,
+                            error_message = %error.message,
+                            "Batch request failed"
+                        );
+                        BatchResult::error(&req.id, error)
+                    }
                 }
-            }
-        });
-    }
+            });
+        }
 
-    let mut results = Vec::with_capacity(batch.len());
-    while let Some(result) = futures.next().await {
+        let Some(result) = futures.next().await else {
+            break;
+        };
+        on_result(&result);
         results.push(result);
     }
 
@@ -497,11 +842,24 @@ async fn execute_parallel(batch: &BatchRequest, router: Arc<dyn DynRouter>) -> V
 }
 
 /// Execute batch requests sequentially.
-async fn execute_sequential(batch: &BatchRequest, router: Arc<dyn DynRouter>) -> Vec<BatchResult> {
+async fn execute_sequential(
+    batch: &BatchRequest,
+    router: Arc<dyn DynRouter>,
+    rpc_config: &RpcConfig,
+    on_result: ResultSink<'_>,
+) -> Vec<BatchResult> {
     let mut results = Vec::with_capacity(batch.len());
 
     for req in &batch.requests {
-        let result = match router.call(&req.path, req.input.clone()).await {
+        let result = match call_item(
+            router.as_ref(),
+            &req.path,
+            req.input.clone(),
+            batch.envelope.clone(),
+            rpc_config,
+        )
+        .await
+        {
             Ok(data) => {
                 debug!(request_id = %req.id, path = %req.path, "Batch request succeeded");
                 BatchResult::success(&req.id, data)
@@ -517,12 +875,486 @@ async fn execute_sequential(batch: &BatchRequest, router: Arc<dyn DynRouter>) ->
                 BatchResult::error(&req.id, error)
             }
         };
+        on_result(&result);
         results.push(result);
     }
 
     results
 }
 
+// =============================================================================
+// Dependent Requests
+// =============================================================================
+
+/// Key of a result reference in request input, e.g. `{"$ref": "createUser#/id"}`.
+///
+/// The part before `#` is the ID of a request listed in `depends_on`; the part
+/// after it is a JSON pointer into that request's result (empty for the whole
+/// result). References to requests that are not declared dependencies are
+/// left untouched, so user data shaped like a reference is never rewritten.
+pub const RESULT_REF_KEY: &str = "$ref";
+
+/// Return the target of a `{"$ref": "..."}` object.
+fn result_reference(value: &serde_json::Value) -> Option<&str> {
+    match value {
+        serde_json::Value::Object(map) if map.len() == 1 => map.get(RESULT_REF_KEY)?.as_str(),
+        _ => None,
+    }
+}
+
+/// Split a reference into the request ID and the JSON pointer.
+fn split_reference(reference: &str) -> (&str, &str) {
+    reference.split_once('#').unwrap_or((reference, ""))
+}
+
+/// Schedules the requests of a batch in dependency order.
+///
+/// Requests become ready once all their dependencies have succeeded. Unknown
+/// dependencies, cycles and failed dependencies settle a request with an error
+/// without running it.
+struct DependencyScheduler<'a> {
+    batch: &'a BatchRequest,
+    /// Request index by ID (the first request wins for duplicate IDs)
+    index: HashMap<&'a str, usize>,
+    /// Indices of the requests waiting on each request
+    dependents: Vec<Vec<usize>>,
+    /// Number of unsettled dependencies per request
+    pending: Vec<usize>,
+    results: Vec<Option<BatchResult>>,
+    settled: Vec<usize>,
+    ready: VecDeque<usize>,
+}
+
+impl<'a> DependencyScheduler<'a> {
+    fn new(batch: &'a BatchRequest) -> Self {
+        let count = batch.len();
+        let mut index = HashMap::with_capacity(count);
+        for (i, req) in batch.requests.iter().enumerate() {
+            index.entry(req.id.as_str()).or_insert(i);
+        }
+
+        let mut dependencies = vec![Vec::new(); count];
+        let mut failures = Vec::new();
+        for (i, req) in batch.requests.iter().enumerate() {
+            for id in req.dependencies() {
+                match index.get(id) {
+                    Some(&dependency) => dependencies[i].push(dependency),
+                    None => {
+                        failures.push((
+                            i,
+                            RpcError::bad_request(format!(
+                                "Request '{}' depends on unknown request '{}'",
+                                req.id, id
+                            ))
+                            .with_details(serde_json::json!({ "dependency": id })),
+                        ));
+                        break;
+                    }
+                }
+            }
+        }
+
+        let unknown: HashSet<usize> = failures.iter().map(|(i, _)| *i).collect();
+        for (i, cycle) in find_cycles(&dependencies, &unknown) {
+            let cycle: Vec<&str> = cycle
+                .into_iter()
+                .map(|j| batch.requests[j].id.as_str())
+                .collect();
+            failures.push((
+                i,
+                RpcError::bad_request(format!(
+                    "Request '{}' is part of a dependency cycle",
+                    batch.requests[i].id
+                ))
+                .with_details(serde_json::json!({ "cycle": cycle })),
+            ));
+        }
+
+        let mut dependents = vec![Vec::new(); count];
+        for (i, deps) in dependencies.iter().enumerate() {
+            for &dependency in deps {
+                dependents[dependency].push(i);
+            }
+        }
+
+        let mut scheduler = Self {
+            batch,
+            index,
+            dependents,
+            pending: dependencies.iter().map(Vec::len).collect(),
+            results: vec![None; count],
+            settled: Vec::new(),
+            ready: VecDeque::new(),
+        };
+        // Record every planning failure before propagating, so a cycle member
+        // reports the cycle rather than its failed neighbour.
+        let failed: Vec<usize> = failures.iter().map(|(i, _)| *i).collect();
+        for (i, error) in failures {
+            scheduler.record(i, BatchResult::error(&batch.requests[i].id, error));
+        }
+        for i in failed {
+            scheduler.release(i);
+        }
+        for i in 0..count {
+            if scheduler.results[i].is_none() && scheduler.pending[i] == 0 {
+                scheduler.ready.push_back(i);
+            }
+        }
+        scheduler
+    }
+
+    /// Take the next request whose dependencies have all succeeded.
+    fn next_ready(&mut self) -> Option<usize> {
+        self.ready.pop_front()
+    }
+
+    /// Record the result of a request, releasing or failing its dependents.
+    fn settle(&mut self, index: usize, result: BatchResult) {
+        if self.results[index].is_some() {
+            return;
+        }
+        self.record(index, result);
+        self.release(index);
+    }
+
+    fn record(&mut self, index: usize, result: BatchResult) {
+        self.results[index] = Some(result);
+        self.settled.push(index);
+    }
+
+    /// Pass every result settled since the last call to `on_result`.
+    fn report(&mut self, on_result: ResultSink<'_>) {
+        for index in std::mem::take(&mut self.settled) {
+            if let Some(result) = &self.results[index] {
+                on_result(result);
+            }
+        }
+    }
+
+    /// Propagate a settled result to the requests waiting on it.
+    fn release(&mut self, index: usize) {
+        let mut queue = vec![index];
+        while let Some(index) = queue.pop() {
+            let dependency = &self.batch.requests[index].id;
+            let failure = self.results[index]
+                .as_ref()
+                .and_then(BatchResult::get_error)
+                .map(|error| error.code);
+            for &dependent in &self.dependents[index] {
+                if self.results[dependent].is_some() {
+                    continue;
+                }
+                match failure {
+                    Some(code) => {
+                        let id = &self.batch.requests[dependent].id;
+                        let error = RpcError::bad_request(format!(
+                            "Request '{}' depends on failed request '{}'",
+                            id, dependency
+                        ))
+                        .with_details(serde_json::json!({
+                            "dependency": dependency,
+                            "dependency_error": code,
+                        }));
+                        self.results[dependent] = Some(BatchResult::error(id, error));
+                        self.settled.push(dependent);
+                        queue.push(dependent);
+                    }
+                    None => {
+                        self.pending[dependent] -= 1;
+                        if self.pending[dependent] == 0 {
+                            self.ready.push_back(dependent);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Build the input of a request, replacing references to its declared
+    /// dependencies with their result data.
+    fn resolve_input(&self, index: usize) -> Result<serde_json::Value, RpcError> {
+        let req = &self.batch.requests[index];
+        if req.depends_on.is_empty() {
+            return Ok(req.input.clone());
+        }
+        self.resolve_value(&req.input, req)
+    }
+
+    fn resolve_value(
+        &self,
+        value: &serde_json::Value,
+        req: &SingleRequest,
+    ) -> Result<serde_json::Value, RpcError> {
+        let declared = result_reference(value).filter(|reference| {
+            let id = split_reference(reference).0;
+            req.depends_on.iter().any(|dependency| dependency == id)
+        });
+        if let Some(reference) = declared {
+            let request_id = &req.id;
+            let (id, pointer) = split_reference(reference);
+            return self
+                .index
+                .get(id)
+                .and_then(|&i| self.results[i].as_ref())
+                .and_then(BatchResult::data)
+                .and_then(|data| data.pointer(pointer))
+                .cloned()
+                .ok_or_else(|| {
+                    RpcError::bad_request(format!(
+                        "Reference '{}' in request '{}' does not resolve",
+                        reference, request_id
+                    ))
+                    .with_details(serde_json::json!({ "reference": reference }))
+                });
+        }
+        match value {
+            serde_json::Value::Array(items) => items
+                .iter()
+                .map(|item| self.resolve_value(item, req))
+                .collect::<Result<Vec<_>, _>>()
+                .map(serde_json::Value::Array),
+            serde_json::Value::Object(map) => map
+                .iter()
+                .map(|(key, item)| Ok((key.clone(), self.resolve_value(item, req)?)))
+                .collect::<Result<serde_json::Map<_, _>, RpcError>>()
+                .map(serde_json::Value::Object),
+            other => Ok(other.clone()),
+        }
+    }
+
+    /// Results in the original request order.
+    fn into_results(self) -> Vec<BatchResult> {
+        self.results
+            .into_iter()
+            .zip(&self.batch.requests)
+            .map(|(result, req)| {
+                result.unwrap_or_else(|| {
+                    BatchResult::error(&req.id, RpcError::internal("Request was never scheduled"))
+                })
+            })
+            .collect()
+    }
+}
+
+/// Find the requests that sit on a dependency cycle, with the members of
+/// their cycle.
+///
+/// Requests in `failed` are treated as settled and never reported.
+fn find_cycles(dependencies: &[Vec<usize>], failed: &HashSet<usize>) -> Vec<(usize, Vec<usize>)> {
+    // Peel off everything that can be scheduled; what remains is blocked by a cycle.
+    let mut blocked: HashSet<usize> = (0..dependencies.len())
+        .filter(|i| !failed.contains(i))
+        .collect();
+    loop {
+        let schedulable: Vec<usize> = blocked
+            .iter()
+            .copied()
+            .filter(|&i| dependencies[i].iter().all(|d| !blocked.contains(d)))
+            .collect();
+        if schedulable.is_empty() {
+            break;
+        }
+        for i in schedulable {
+            blocked.remove(&i);
+        }
+    }
+
+    let reachable = |start: usize| {
+        let mut seen = HashSet::new();
+        let mut stack = dependencies[start].clone();
+        while let Some(i) = stack.pop() {
+            if blocked.contains(&i) && seen.insert(i) {
+                stack.extend(&dependencies[i]);
+            }
+        }
+        seen
+    };
+    let reach: HashMap<usize, HashSet<usize>> =
+        blocked.iter().map(|&i| (i, reachable(i))).collect();
+
+    let mut cycles: Vec<(usize, Vec<usize>)> = reach
+        .iter()
+        .filter(|(i, seen)| seen.contains(i))
+        .map(|(&i, seen)| {
+            let mut members: Vec<usize> = seen
+                .iter()
+                .copied()
+                .filter(|j| reach[j].contains(&i))
+                .collect();
+            members.sort_unstable();
+            (i, members)
+        })
+        .collect();
+    cycles.sort_unstable_by_key(|(i, _)| *i);
+    cycles
+}
+
+/// Execute a batch whose requests depend on each other.
+///
+/// Up to `concurrency` ready requests run at the same time. Results are
+/// returned in the original request order.
+pub(crate) async fn execute_with_dependencies<'a, F, Fut>(
+    batch: &'a BatchRequest,
+    concurrency: usize,
+    call: F,
+    on_result: ResultSink<'_>,
+) -> Vec<BatchResult>
+where
+    F: Fn(&'a SingleRequest, serde_json::Value) -> Fut,
+    Fut: Future<Output = Result<serde_json::Value, RpcError>> + 'a,
+{
+    let mut scheduler = DependencyScheduler::new(batch);
+    let mut in_flight = FuturesUnordered::new();
+
+    loop {
+        scheduler.report(on_result);
+        while in_flight.len() < concurrency {
+            let Some(index) = scheduler.next_ready() else {
+                break;
+            };
+            let req = &batch.requests[index];
+            match scheduler.resolve_input(index) {
+                Ok(input) => {
+                    let call = call(req, input);
+                    in_flight.push(async move { (index, call.await) });
+                }
+                Err(error) => scheduler.settle(index, BatchResult::error(&req.id, error)),
+            }
+        }
+
+        let Some((index, result)) = in_flight.next().await else {
+            break;
+        };
+        let req = &batch.requests[index];
+        let result = match result {
+            Ok(data) => {
+                debug!(request_id = %req.id, path = %req.path, "Batch request succeeded");
+                BatchResult::success(&req.id, data)
+            }
+            Err(error) => {
+                warn!(
+                    request_id = %req.id,
+                    path = %req.path,
+                    error_code = %error.code,
+                    error_message = %error.message,
+                    "Batch request failed"
+                );
+                BatchResult::error(&req.id, error)
+            }
+        };
+        scheduler.settle(index, result);
+    }
+
+    scheduler.report(on_result);
+    scheduler.into_results()
+}
+
+// =============================================================================
+// Atomic Batches
+// =============================================================================
+
+/// Execute a batch all-or-nothing.
+///
+/// Requests run one at a time in dependency order. At the first error the
+/// remaining requests are skipped and `compensate` is called for every request
+/// that already succeeded, most recent first. Requests without a registered
+/// compensation resolve to `None` and are left out of the report.
+pub(crate) async fn execute_atomic<'a, F, Fut, C, CFut>(
+    batch: &'a BatchRequest,
+    call: F,
+    compensate: C,
+    on_result: ResultSink<'_>,
+) -> (Vec<BatchResult>, Vec<CompensationResult>)
+where
+    F: Fn(&'a SingleRequest, serde_json::Value) -> Fut,
+    Fut: Future<Output = Result<serde_json::Value, RpcError>> + 'a,
+    C: Fn(&'a SingleRequest, serde_json::Value, serde_json::Value) -> CFut,
+    CFut: Future<Output = Option<Result<(), RpcError>>> + 'a,
+{
+    let mut scheduler = DependencyScheduler::new(batch);
+    // Unknown dependencies and cycles abort the batch before anything runs
+    let mut failed = scheduler.results.iter().position(Option::is_some);
+    let mut succeeded = Vec::new();
+
+    while failed.is_none() {
+        scheduler.report(on_result);
+        let Some(index) = scheduler.next_ready() else {
+            break;
+        };
+        let req = &batch.requests[index];
+        let result = match scheduler.resolve_input(index) {
+            Ok(input) => call(req, input.clone()).await.map(|data| (input, data)),
+            Err(error) => Err(error),
+        };
+        match result {
+            Ok((input, data)) => {
+                debug!(request_id = %req.id, path = %req.path, "Batch request succeeded");
+                succeeded.push((index, input));
+                scheduler.settle(index, BatchResult::success(&req.id, data));
+            }
+            Err(error) => {
+                warn!(
+                    request_id = %req.id,
+                    path = %req.path,
+                    error_code = %error.code,
+                    error_message = %error.message,
+                    "Atomic batch request failed"
+                );
+                scheduler.settle(index, BatchResult::error(&req.id, error));
+                failed = Some(index);
+            }
+        }
+    }
+
+    scheduler.report(on_result);
+    let Some(failed) = failed else {
+        return (scheduler.into_results(), Vec::new());
+    };
+
+    let failed_id = &batch.requests[failed].id;
+    for (i, req) in batch.requests.iter().enumerate() {
+        if scheduler.results[i].is_none() {
+            let error = RpcError::bad_request(format!(
+                "Request '{}' was not run because atomic batch request '{}' failed",
+                req.id, failed_id
+            ))
+            .with_details(serde_json::json!({ "aborted_by": failed_id }));
+            scheduler.record(i, BatchResult::error(&req.id, error));
+        }
+    }
+    scheduler.report(on_result);
+
+    let mut compensations = Vec::new();
+    for (index, input) in succeeded.into_iter().rev() {
+        let req = &batch.requests[index];
+        let output = scheduler.results[index]
+            .as_ref()
+            .and_then(BatchResult::data)
+            .cloned()
+            .unwrap_or_default();
+        let Some(result) = compensate(req, input, output).await else {
+            continue;
+        };
+        match &result {
+            Ok(()) => debug!(request_id = %req.id, path = %req.path, "Batch compensation ran"),
+            Err(error) => warn!(
+                request_id = %req.id,
+                path = %req.path,
+                error_code = %error.code,
+                error_message = %error.message,
+                "Batch compensation failed"
+            ),
+        }
+        compensations.push(CompensationResult {
+            id: req.id.clone(),
+            path: req.path.clone(),
+            error: result.err(),
+        });
+    }
+
+    (scheduler.into_results(), compensations)
+}
+
 // =============================================================================
 // Tests
 // =============================================================================
@@ -754,6 +1586,123 @@ mod execution_tests {
             assert_eq!(response.results[2].id, "3");
         });
     }
+
+    #[tokio::test]
+    async fn test_batch_default_timeout() {
+        async fn slow_handler(
+            _ctx: Context<EmptyContext>,
+            _input: serde_json::Value,
+        ) -> RpcResult<serde_json::Value> {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(json!({"result": "late"}))
+        }
+
+        let router = create_test_router()
+            .query("test.slow", slow_handler)
+            .procedure("test.patient")
+            .timeout(Duration::from_secs(10))
+            .input::<serde_json::Value>()
+            .query(
+                |_ctx: Context<EmptyContext>, _input: serde_json::Value| async move {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    Ok(json!({"result": "patient"}))
+                },
+            )
+            .compile();
+        let router: Arc<dyn DynRouter> = Arc::new(router);
+
+        for parallel in [true, false] {
+            let config = RpcConfig::default()
+                .with_default_timeout(Duration::from_millis(20))
+                .with_batch_config(BatchConfig::default().with_parallel_execution(parallel));
+
+            let batch = BatchRequest::new()
+                .add("1", "test.success", json!({}))
+                .add("2", "test.slow", json!({}))
+                .add("3", "test.patient", json!({}));
+
+            let (response, metrics) = execute_batch(batch, router.clone(), &config).await.unwrap();
+            assert_eq!(metrics.success_count, 2);
+            assert_eq!(metrics.error_count, 1);
+            assert!(response.results[0].is_success());
+            assert_eq!(
+                response.results[1].get_error().unwrap().code,
+                crate::RpcErrorCode::Timeout
+            );
+            assert!(response.results[2].is_success());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_item_timeout() {
+        let router = create_test_router()
+            .query(
+                "test.sleep",
+                |_ctx: Context<EmptyContext>, ms: u64| async move {
+                    tokio::time::sleep(Duration::from_millis(ms)).await;
+                    Ok(ms)
+                },
+            )
+            .compile();
+        let router: Arc<dyn DynRouter> = Arc::new(router);
+
+        for parallel in [true, false] {
+            let config = RpcConfig::default().with_batch_config(
+                BatchConfig::default()
+                    .with_parallel_execution(parallel)
+                    .with_item_timeout(Duration::from_millis(30)),
+            );
+            let batch = BatchRequest::new().add("fast", "test.sleep", json!(0)).add(
+                "slow",
+                "test.sleep",
+                json!(5000),
+            );
+
+            let (response, metrics) = execute_batch(batch, router.clone(), &config).await.unwrap();
+            assert_eq!(metrics.success_count, 1);
+            let error = response.results[1].get_error().unwrap();
+            assert_eq!(error.code, crate::RpcErrorCode::Timeout);
+            assert_eq!(error.details.as_ref().unwrap()["timeout_ms"], 30);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_max_concurrency() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (r, p) = (running.clone(), peak.clone());
+        let router = create_test_router()
+            .query(
+                "test.tracked",
+                move |_ctx: Context<EmptyContext>, _input: serde_json::Value| {
+                    let (running, peak) = (r.clone(), p.clone());
+                    async move {
+                        let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                        running.fetch_sub(1, Ordering::SeqCst);
+                        Ok(json!(null))
+                    }
+                },
+            )
+            .compile();
+        let router: Arc<dyn DynRouter> = Arc::new(router);
+        let config =
+            RpcConfig::default().with_batch_config(BatchConfig::default().with_max_concurrency(3));
+
+        let mut batch = BatchRequest::new();
+        for i in 0..10 {
+            batch = batch.add(i.to_string(), "test.tracked", json!(null));
+        }
+        let (response, _) = execute_batch(batch, router, &config).await.unwrap();
+
+        assert!(response.all_success());
+        assert_eq!(peak.load(Ordering::SeqCst), 3);
+        let ids: Vec<_> = response.results.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"]);
+    }
 }
 
 #[cfg(test)]
@@ -772,9 +1721,26 @@ mod tests {
     fn test_batch_config_builder() {
         let config = BatchConfig::new()
             .with_max_batch_size(50)
-            .with_parallel_execution(false);
+            .with_parallel_execution(false)
+            .with_max_concurrency(4)
+            .with_item_timeout(Duration::from_secs(1));
         assert_eq!(config.max_batch_size, 50);
         assert!(!config.parallel_execution);
+        assert_eq!(config.max_concurrency, Some(4));
+        assert_eq!(config.item_timeout_ms, Some(1000));
+        assert_eq!(config.item_timeout(), Some(Duration::from_secs(1)));
+        // Sequential execution ignores the concurrency limit
+        assert_eq!(config.concurrency(), 1);
+        assert_eq!(BatchConfig::new().with_max_concurrency(4).concurrency(), 4);
+    }
+
+    #[test]
+    fn test_batch_config_item_timeout_serializes_as_millis() {
+        let config = BatchConfig::new().with_item_timeout(Duration::from_millis(250));
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(json["item_timeout_ms"], 250);
+        let parsed: BatchConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.item_timeout(), Some(Duration::from_millis(250)));
     }
 
     #[test]
@@ -782,6 +1748,12 @@ mod tests {
         let config = BatchConfig::new().with_max_batch_size(0);
         assert!(config.validate().is_err());
 
+        let config = BatchConfig::new().with_max_concurrency(0);
+        assert!(config.validate().unwrap_err().contains("max_concurrency"));
+
+        let config = BatchConfig::new().with_item_timeout(Duration::ZERO);
+        assert!(config.validate().unwrap_err().contains("item_timeout_ms"));
+
         let config = BatchConfig::default();
         assert!(config.validate().is_ok());
     }
@@ -0,0 +1,169 @@
+//! Storage backends for the cache
+
+use lru::LruCache;
+use std::num::NonZeroUsize;
+
+use super::entry::CacheEntry;
+
+/// Storage behind a [`Cache`](super::Cache)
+///
+/// The cache takes care of locking, TTL checks and metrics; a store only
+/// keeps entries by key and decides which entry to evict when full. Stores
+/// must never hold more than [`capacity`](Self::capacity) entries.
+pub trait CacheStore: Send + Sync {
+    /// Get an entry, marking it as recently used
+    ///
+    /// The cache only updates access statistics such as
+    /// [`CacheEntry::hits`] through the returned reference; stored data
+    /// always changes through [`put`](Self::put).
+    fn get(&mut self, key: &str) -> Option<&mut CacheEntry>;
+
+    /// Get an entry without marking it as recently used
+    fn peek(&self, key: &str) -> Option<&CacheEntry>;
+
+    /// Insert or replace an entry
+    ///
+    /// Returns the entry evicted to make room for it, if any. Replacing the
+    /// value of an existing key is not an eviction.
+    fn put(&mut self, key: String, entry: CacheEntry) -> Option<(String, CacheEntry)>;
+
+    /// Remove an entry
+    fn remove(&mut self, key: &str) -> Option<CacheEntry>;
+
+    /// Remove the least recently used entry
+    ///
+    /// Used by the cache to enforce its byte budget. The default scans
+    /// [`iter`](Self::iter) for the last entry.
+    fn pop_lru(&mut self) -> Option<(String, CacheEntry)> {
+        let key = self.iter().last()?.0.clone();
+        let entry = self.remove(&key)?;
+        Some((key, entry))
+    }
+
+    /// Remove every entry
+    fn clear(&mut self);
+
+    /// Iterate over all entries, most recently used first
+    fn iter(&self) -> Box<dyn Iterator<Item = (&String, &CacheEntry)> + '_>;
+
+    /// Number of entries currently stored
+    fn len(&self) -> usize;
+
+    /// Whether the store holds no entries
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Maximum number of entries the store holds
+    fn capacity(&self) -> usize;
+}
+
+/// In-memory LRU store, the default backend
+#[derive(Debug)]
+pub struct LruStore {
+    entries: LruCache<String, CacheEntry>,
+}
+
+impl LruStore {
+    /// Create a store holding at most `max_entries` entries (at least one)
+    pub fn new(max_entries: usize) -> Self {
+        let capacity = NonZeroUsize::new(max_entries.max(1)).unwrap();
+        Self {
+            entries: LruCache::new(capacity),
+        }
+    }
+}
+
+impl CacheStore for LruStore {
+    fn get(&mut self, key: &str) -> Option<&mut CacheEntry> {
+        self.entries.get_mut(key)
+    }
+
+    fn peek(&self, key: &str) -> Option<&CacheEntry> {
+        self.entries.peek(key)
+    }
+
+    fn put(&mut self, key: String, entry: CacheEntry) -> Option<(String, CacheEntry)> {
+        // `push` also hands back the old value of a replaced key
+        self.entries
+            .push(key.clone(), entry)
+            .filter(|(evicted, _)| *evicted != key)
+    }
+
+    fn remove(&mut self, key: &str) -> Option<CacheEntry> {
+        self.entries.pop(key)
+    }
+
+    fn pop_lru(&mut self) -> Option<(String, CacheEntry)> {
+        self.entries.pop_lru()
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (&String, &CacheEntry)> + '_> {
+        Box::new(self.entries.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.entries.cap().get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::time::Duration;
+
+    fn entry(value: i64) -> CacheEntry {
+        CacheEntry::new(json!(value), Duration::from_secs(60))
+    }
+
+    #[test]
+    fn test_lru_store_put_reports_evictions_only() {
+        let mut store = LruStore::new(2);
+        assert!(store.put("a".into(), entry(1)).is_none());
+        assert!(store.put("b".into(), entry(2)).is_none());
+
+        // Replacing a key is not an eviction
+        assert!(store.put("a".into(), entry(3)).is_none());
+        assert_eq!(store.peek("a").unwrap().value, json!(3));
+
+        let (evicted, _) = store.put("c".into(), entry(4)).unwrap();
+        assert_eq!(evicted, "b");
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.capacity(), 2);
+    }
+
+    #[test]
+    fn test_lru_store_get_updates_recency() {
+        let mut store = LruStore::new(2);
+        store.put("a".into(), entry(1));
+        store.put("b".into(), entry(2));
+
+        store.get("a");
+        let (evicted, _) = store.put("c".into(), entry(3)).unwrap();
+        assert_eq!(evicted, "b");
+
+        let keys: Vec<_> = store.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, ["c", "a"]);
+
+        assert_eq!(store.pop_lru().unwrap().0, "a");
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn test_lru_store_zero_capacity_holds_one() {
+        let mut store = LruStore::new(0);
+        store.put("a".into(), entry(1));
+        assert_eq!(store.capacity(), 1);
+        assert_eq!(store.remove("a").unwrap().value, json!(1));
+        assert!(store.is_empty());
+    }
+}
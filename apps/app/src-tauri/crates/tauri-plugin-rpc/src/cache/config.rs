@@ -1,9 +1,17 @@
 //! Cache configuration
 
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
 
-use super::pattern::pattern_matches;
+use crate::middleware::ProcedureType;
+use crate::pattern::PatternSet;
+use crate::plugin::DynRouter;
+
+use super::error::{CacheError, CacheResult};
+use super::key::CacheKeySelector;
 
 /// Default TTL for cache entries (5 minutes)
 ///
@@ -19,6 +27,48 @@ pub const DEFAULT_TTL_SECS: u64 = 300;
 /// recently used entries are retained when this limit is reached.
 pub const DEFAULT_MAX_ENTRIES: usize = 1000;
 
+/// Whether the cache may store a procedure's results
+///
+/// Declared per procedure with `.cacheable()` or `.no_store()` on the
+/// procedure builders, stored in the procedure's
+/// [`ProcedureMeta`](crate::schema::ProcedureMeta) and exposed to middleware
+/// through [`Context::cache_policy`](crate::Context::cache_policy).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CachePolicy {
+    /// Cache queries only
+    #[default]
+    Auto,
+    /// Cache results whatever the procedure type
+    Cacheable,
+    /// Never store results
+    NoStore,
+}
+
+impl CachePolicy {
+    /// Check if results of a procedure of the given type may be cached
+    pub fn allows(self, procedure_type: ProcedureType) -> bool {
+        match self {
+            Self::Auto => procedure_type == ProcedureType::Query,
+            Self::Cacheable => true,
+            Self::NoStore => false,
+        }
+    }
+}
+
+/// Proactive refresh of frequently read entries
+///
+/// A hit on an entry that was served at least `min_hits` times and has less
+/// than `window` of its TTL left triggers a background refresh, so hot keys
+/// are renewed before they ever expire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RefreshAhead {
+    /// Remaining TTL below which a hot entry is refreshed
+    pub window: Duration,
+    /// Hits after which an entry counts as hot
+    pub min_hits: u64,
+}
+
 /// Configuration for the cache layer
 #[derive(Debug, Clone)]
 pub struct CacheConfig {
@@ -26,12 +76,27 @@ pub struct CacheConfig {
     pub default_ttl: Duration,
     /// Per-procedure TTL overrides
     pub procedure_ttl: HashMap<String, Duration>,
+    /// Per-procedure stale-while-revalidate windows
+    pub procedure_stale_window: HashMap<String, Duration>,
+    /// Proactive refresh of hot entries, disabled by default
+    pub refresh_ahead: Option<RefreshAhead>,
     /// Maximum number of entries in the cache
     pub max_entries: usize,
+    /// Maximum total serialized size of the cached values, unbounded by default
+    pub max_bytes: Option<usize>,
+    /// Maximum serialized size of a single value, unbounded by default
+    pub max_entry_bytes: Option<usize>,
     /// Whether caching is enabled
     pub enabled: bool,
     /// Patterns for procedures that should not be cached
-    pub excluded_patterns: Vec<String>,
+    ///
+    /// Formerly a `Vec<String>`; `collect()` strings into a [`PatternSet`]
+    /// to build one directly.
+    pub excluded_patterns: PatternSet,
+    /// Per-procedure selection of the keyed part of the input
+    pub procedure_keys: HashMap<String, CacheKeySelector>,
+    /// Patterns for procedures whose entries are kept per authenticated user
+    pub vary_by_user: PatternSet,
 }
 
 impl CacheConfig {
@@ -40,9 +105,15 @@ impl CacheConfig {
         Self {
             default_ttl: Duration::from_secs(DEFAULT_TTL_SECS),
             procedure_ttl: HashMap::new(),
+            procedure_stale_window: HashMap::new(),
+            refresh_ahead: None,
             max_entries: DEFAULT_MAX_ENTRIES,
+            max_bytes: None,
+            max_entry_bytes: None,
             enabled: true,
-            excluded_patterns: Vec::new(),
+            excluded_patterns: PatternSet::new(),
+            procedure_keys: HashMap::new(),
+            vary_by_user: PatternSet::new(),
         }
     }
 
@@ -60,6 +131,31 @@ impl CacheConfig {
         self
     }
 
+    /// Serve a procedure's entries stale for up to `window` past their TTL
+    ///
+    /// Under [`cache_middleware`](super::cache_middleware) an entry in this
+    /// window is returned immediately while the handler refreshes it in the
+    /// background. Past the window the entry is a plain miss.
+    #[must_use = "This method returns a new CacheConfig and does not modify self"]
+    pub fn with_stale_while_revalidate(
+        mut self,
+        path: impl Into<String>,
+        window: Duration,
+    ) -> Self {
+        self.procedure_stale_window.insert(path.into(), window);
+        self
+    }
+
+    /// Refresh hot entries in the background before they expire
+    ///
+    /// See [`RefreshAhead`]; only applies under
+    /// [`cache_middleware`](super::cache_middleware).
+    #[must_use = "This method returns a new CacheConfig and does not modify self"]
+    pub fn with_refresh_ahead(mut self, window: Duration, min_hits: u64) -> Self {
+        self.refresh_ahead = Some(RefreshAhead { window, min_hits });
+        self
+    }
+
     /// Set the maximum number of entries
     #[must_use = "This method returns a new CacheConfig and does not modify self"]
     pub fn with_max_entries(mut self, max: usize) -> Self {
@@ -67,6 +163,25 @@ impl CacheConfig {
         self
     }
 
+    /// Bound the total serialized size of the cached values
+    ///
+    /// Sizes are measured from each value's JSON serialization. When a new
+    /// value takes the total past `max`, least recently used entries are
+    /// evicted until it fits. Applies on top of
+    /// [`with_max_entries`](Self::with_max_entries).
+    #[must_use = "This method returns a new CacheConfig and does not modify self"]
+    pub fn with_max_bytes(mut self, max: usize) -> Self {
+        self.max_bytes = Some(max);
+        self
+    }
+
+    /// Never cache values whose serialized size exceeds `max` bytes
+    #[must_use = "This method returns a new CacheConfig and does not modify self"]
+    pub fn with_max_entry_bytes(mut self, max: usize) -> Self {
+        self.max_entry_bytes = Some(max);
+        self
+    }
+
     /// Enable or disable caching
     #[must_use = "This method returns a new CacheConfig and does not modify self"]
     pub fn with_enabled(mut self, enabled: bool) -> Self {
@@ -75,12 +190,86 @@ impl CacheConfig {
     }
 
     /// Add a pattern for procedures that should not be cached
+    ///
+    /// Uses the shared [pattern syntax](crate::pattern); a negated pattern
+    /// such as `!user.settings` re-includes paths excluded before it.
     #[must_use = "This method returns a new CacheConfig and does not modify self"]
     pub fn exclude_pattern(mut self, pattern: impl Into<String>) -> Self {
-        self.excluded_patterns.push(pattern.into());
+        self.excluded_patterns.insert(pattern);
+        self
+    }
+
+    /// Key a procedure's entries on some input fields only
+    ///
+    /// `pointers` are JSON pointers such as `/id` or `/filter/name`. Inputs
+    /// that agree on these fields share an entry.
+    #[must_use = "This method returns a new CacheConfig and does not modify self"]
+    pub fn with_key_include(
+        mut self,
+        path: impl Into<String>,
+        pointers: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        let pointers = pointers.into_iter().map(Into::into).collect();
+        self.procedure_keys
+            .insert(path.into(), CacheKeySelector::Include(pointers));
+        self
+    }
+
+    /// Leave some input fields, such as nonces or timestamps, out of a procedure's keys
+    ///
+    /// `pointers` are JSON pointers such as `/nonce` or `/meta/sent_at`.
+    #[must_use = "This method returns a new CacheConfig and does not modify self"]
+    pub fn with_key_exclude(
+        mut self,
+        path: impl Into<String>,
+        pointers: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        let pointers = pointers.into_iter().map(Into::into).collect();
+        self.procedure_keys
+            .insert(path.into(), CacheKeySelector::Exclude(pointers));
+        self
+    }
+
+    /// Key a procedure's entries on a value derived from the input
+    #[must_use = "This method returns a new CacheConfig and does not modify self"]
+    pub fn with_key_fn<F>(mut self, path: impl Into<String>, key_fn: F) -> Self
+    where
+        F: Fn(&serde_json::Value) -> serde_json::Value + Send + Sync + 'static,
+    {
+        self.procedure_keys
+            .insert(path.into(), CacheKeySelector::Custom(Arc::new(key_fn)));
+        self
+    }
+
+    /// Keep separate entries per authenticated user for procedures matching a pattern
+    ///
+    /// The user comes from the [`AuthResult`](crate::auth::AuthResult) the
+    /// auth middleware attaches to the context, so register the auth
+    /// middleware before [`cache_middleware`](super::cache_middleware).
+    /// Anonymous callers share one entry.
+    #[must_use = "This method returns a new CacheConfig and does not modify self"]
+    pub fn with_vary_by_user(mut self, pattern: impl Into<String>) -> Self {
+        self.vary_by_user.insert(pattern);
         self
     }
 
+    /// Get the part of an input that identifies a procedure's cache entry
+    pub fn key_input<'a>(
+        &self,
+        path: &str,
+        input: &'a serde_json::Value,
+    ) -> Cow<'a, serde_json::Value> {
+        match self.procedure_keys.get(path) {
+            Some(selector) => Cow::Owned(selector.select(input)),
+            None => Cow::Borrowed(input),
+        }
+    }
+
+    /// Check if a procedure's entries are kept per user
+    pub fn varies_by_user(&self, path: &str) -> bool {
+        self.vary_by_user.matches(path)
+    }
+
     /// Get the TTL for a specific procedure
     pub fn get_ttl(&self, path: &str) -> Duration {
         self.procedure_ttl
@@ -89,19 +278,59 @@ impl CacheConfig {
             .unwrap_or(self.default_ttl)
     }
 
-    /// Check if a procedure should be cached
-    pub fn should_cache(&self, path: &str) -> bool {
-        if !self.enabled {
-            return false;
+    /// Largest value the cache accepts, in serialized bytes
+    ///
+    /// The smaller of `max_entry_bytes` and `max_bytes`, since a value over
+    /// the total budget could never be kept.
+    pub fn entry_size_limit(&self) -> Option<usize> {
+        match (self.max_entry_bytes, self.max_bytes) {
+            (Some(entry), Some(total)) => Some(entry.min(total)),
+            (entry, total) => entry.or(total),
         }
+    }
 
-        for pattern in &self.excluded_patterns {
-            if pattern_matches(pattern, path) {
-                return false;
+    /// Get the stale-while-revalidate window for a specific procedure
+    ///
+    /// Zero when none is configured.
+    pub fn get_stale_window(&self, path: &str) -> Duration {
+        self.procedure_stale_window
+            .get(path)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Check the per-procedure settings against the router's procedures
+    ///
+    /// A TTL or stale window configured for a procedure whose results are
+    /// never cached is rejected: a mutation or subscription not declared
+    /// `cacheable`, or a procedure declared `no_store`. Paths the router does
+    /// not know are ignored.
+    ///
+    /// Caches added with [`Router::cache`](crate::Router::cache) are checked
+    /// when the plugin starts.
+    pub fn validate(&self, router: &dyn DynRouter) -> CacheResult<()> {
+        let settings = self.procedure_ttl.keys().map(|path| (path, "TTL")).chain(
+            self.procedure_stale_window
+                .keys()
+                .map(|path| (path, "stale window")),
+        );
+        for (path, setting) in settings {
+            let Some(procedure_type) = router.procedure_type(path) else {
+                continue;
+            };
+            if !router.cache_policy(path).allows(procedure_type) {
+                return Err(CacheError::InvalidConfig(format!(
+                    "{} configured for '{}', a {} whose results are never cached",
+                    setting, path, procedure_type
+                )));
             }
         }
+        Ok(())
+    }
 
-        true
+    /// Check if a procedure should be cached
+    pub fn should_cache(&self, path: &str) -> bool {
+        self.enabled && !self.excluded_patterns.matches(path)
     }
 }
 
@@ -110,3 +339,125 @@ impl Default for CacheConfig {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CompiledRouter, Context, EmptyContext, Router};
+
+    async fn noop(_ctx: Context<EmptyContext>, _: ()) -> crate::RpcResult<()> {
+        Ok(())
+    }
+
+    fn router() -> CompiledRouter<EmptyContext> {
+        Router::new()
+            .context(EmptyContext)
+            .query("user.get", noop)
+            .mutation("user.update", noop)
+            .procedure("user.put")
+            .cacheable()
+            .mutation(noop)
+            .procedure("user.live")
+            .no_store()
+            .query(noop)
+            .compile()
+    }
+
+    #[test]
+    fn test_key_input() {
+        let input = serde_json::json!({"id": 1, "nonce": "n1"});
+        let config = CacheConfig::new()
+            .with_key_exclude("user.get", ["/nonce"])
+            .with_key_include("user.list", ["/id"])
+            .with_key_fn("user.count", |_| serde_json::Value::Null)
+            .with_vary_by_user("user.*");
+
+        assert_eq!(
+            *config.key_input("user.get", &input),
+            serde_json::json!({"id": 1})
+        );
+        assert_eq!(
+            *config.key_input("user.list", &input),
+            serde_json::json!({"id": 1})
+        );
+        assert!(config.key_input("user.count", &input).is_null());
+        assert!(matches!(
+            config.key_input("post.get", &input),
+            Cow::Borrowed(_)
+        ));
+
+        assert!(config.varies_by_user("user.get"));
+        assert!(!config.varies_by_user("post.get"));
+    }
+
+    #[test]
+    fn test_entry_size_limit() {
+        assert_eq!(CacheConfig::new().entry_size_limit(), None);
+        assert_eq!(
+            CacheConfig::new().with_max_bytes(100).entry_size_limit(),
+            Some(100)
+        );
+        let config = CacheConfig::new()
+            .with_max_bytes(100)
+            .with_max_entry_bytes(10);
+        assert_eq!(config.entry_size_limit(), Some(10));
+        assert_eq!(
+            config.with_max_entry_bytes(1000).entry_size_limit(),
+            Some(100)
+        );
+    }
+
+    #[test]
+    fn test_cache_policy_allows() {
+        assert!(CachePolicy::Auto.allows(ProcedureType::Query));
+        assert!(!CachePolicy::Auto.allows(ProcedureType::Mutation));
+        assert!(!CachePolicy::Auto.allows(ProcedureType::Subscription));
+        assert!(CachePolicy::Cacheable.allows(ProcedureType::Mutation));
+        assert!(!CachePolicy::NoStore.allows(ProcedureType::Query));
+    }
+
+    #[test]
+    fn test_validate_rejects_ttl_for_uncached_procedures() {
+        let router = router();
+        let ttl = Duration::from_secs(30);
+
+        let valid = CacheConfig::new()
+            .with_procedure_ttl("user.get", ttl)
+            .with_procedure_ttl("user.put", ttl)
+            .with_procedure_ttl("unknown.path", ttl);
+        assert!(valid.validate(&router).is_ok());
+
+        let err = CacheConfig::new()
+            .with_procedure_ttl("user.update", ttl)
+            .validate(&router)
+            .unwrap_err();
+        assert!(matches!(err, CacheError::InvalidConfig(msg) if msg.contains("user.update")));
+
+        let stale = CacheConfig::new().with_stale_while_revalidate("user.live", ttl);
+        let err = stale.validate(&router).unwrap_err();
+        assert!(matches!(err, CacheError::InvalidConfig(msg) if msg.starts_with("stale window")));
+    }
+
+    #[test]
+    fn test_router_validates_its_caches() {
+        use crate::cache::Cache;
+
+        let ttl = Duration::from_secs(30);
+        let router = |config: CacheConfig| {
+            Router::new()
+                .context(EmptyContext)
+                .cache(Cache::new(config))
+                .query("user.get", noop)
+                .mutation("user.update", noop)
+        };
+
+        let valid = router(CacheConfig::new().with_procedure_ttl("user.get", ttl));
+        assert!(valid.validate_caches().is_ok());
+        assert!(valid.compile().validate_caches().is_ok());
+
+        let invalid = router(CacheConfig::new().with_procedure_ttl("user.update", ttl));
+        let err = invalid.validate_caches().unwrap_err();
+        assert!(matches!(err, CacheError::InvalidConfig(msg) if msg.starts_with("TTL")));
+        assert!(crate::DynRouter::validate(&invalid.compile()).is_err());
+    }
+}
@@ -11,29 +11,81 @@ pub struct CacheEntry {
     pub created_at: Instant,
     /// Time-to-live for this entry
     pub ttl: Duration,
+    /// Tags for [`Cache::invalidate_tags`](super::Cache::invalidate_tags)
+    pub tags: Vec<String>,
+    /// How long past its TTL the entry may still be served while it is refreshed
+    pub stale_window: Duration,
+    /// Number of times the entry was served
+    pub hits: u64,
+    /// Serialized size of the value in bytes
+    pub size: usize,
 }
 
 impl CacheEntry {
     /// Create a new cache entry
     pub fn new(value: serde_json::Value, ttl: Duration) -> Self {
         Self {
+            size: serialized_size(&value),
             value,
             created_at: Instant::now(),
             ttl,
+            tags: Vec::new(),
+            stale_window: Duration::ZERO,
+            hits: 0,
         }
     }
 
+    /// Attach invalidation tags to the entry
+    #[must_use = "This method returns a new CacheEntry and does not modify self"]
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Allow the entry to be served stale for `window` past its TTL
+    #[must_use = "This method returns a new CacheEntry and does not modify self"]
+    pub fn with_stale_window(mut self, window: Duration) -> Self {
+        self.stale_window = window;
+        self
+    }
+
     /// Check if the entry has expired
     pub fn is_expired(&self) -> bool {
         self.created_at.elapsed() >= self.ttl
     }
 
+    /// Check if the entry is past its stale window and can no longer be served
+    pub fn is_discardable(&self) -> bool {
+        self.created_at.elapsed() >= self.ttl.saturating_add(self.stale_window)
+    }
+
     /// Get the remaining TTL
     pub fn remaining_ttl(&self) -> Duration {
         self.ttl.saturating_sub(self.created_at.elapsed())
     }
 }
 
+/// Number of bytes `value` takes once serialized as JSON
+pub(crate) fn serialized_size(value: &serde_json::Value) -> usize {
+    struct ByteCounter(usize);
+
+    impl std::io::Write for ByteCounter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0 += buf.len();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut counter = ByteCounter(0);
+    // Serializing a `Value` into a writer that never fails cannot fail
+    let _ = serde_json::to_writer(&mut counter, value);
+    counter.0
+}
+
 /// Generate a deterministic cache key from path and input
 pub fn generate_cache_key(path: &str, input: &serde_json::Value) -> String {
     // Normalize the input to ensure deterministic key generation
@@ -41,6 +93,23 @@ pub fn generate_cache_key(path: &str, input: &serde_json::Value) -> String {
     format!("{}:{}", path, normalized_input)
 }
 
+/// Generate a cache key, scoped to a user when one is given
+///
+/// The user id follows the input as a JSON string, so entries of different
+/// users never share a key.
+pub(crate) fn scoped_cache_key(
+    path: &str,
+    input: &serde_json::Value,
+    user: Option<&str>,
+) -> String {
+    let mut key = generate_cache_key(path, input);
+    if let Some(user) = user {
+        key.push(' ');
+        key.push_str(&serde_json::Value::from(user).to_string());
+    }
+    key
+}
+
 /// Normalize JSON for deterministic key generation
 fn normalize_json(value: &serde_json::Value) -> String {
     match value {
@@ -72,6 +141,14 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn test_entry_size_matches_serialized_value() {
+        let value = json!({"users": [{"id": 1, "name": "Ann \"A\""}], "ok": true});
+        let entry = CacheEntry::new(value.clone(), Duration::from_secs(1));
+        assert_eq!(entry.size, serde_json::to_vec(&value).unwrap().len());
+        assert_eq!(CacheEntry::new(json!(true), Duration::ZERO).size, 4);
+    }
+
     #[test]
     fn test_cache_key_determinism() {
         let input1 = json!({"b": 2, "a": 1});
@@ -105,6 +182,17 @@ mod tests {
         assert_ne!(key1, key2);
     }
 
+    #[test]
+    fn test_entry_stale_window() {
+        let entry = CacheEntry::new(json!(1), Duration::ZERO);
+        assert!(entry.is_expired());
+        assert!(entry.is_discardable());
+
+        let entry = entry.with_stale_window(Duration::from_secs(60));
+        assert!(entry.is_expired());
+        assert!(!entry.is_discardable());
+    }
+
     #[test]
     fn test_normalize_json_primitives() {
         assert_eq!(normalize_json(&json!(null)), "null");
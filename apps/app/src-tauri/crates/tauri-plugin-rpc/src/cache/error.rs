@@ -22,6 +22,14 @@ pub enum CacheError {
     /// Failed to acquire lock
     #[error("Lock acquisition failed: {0}")]
     LockError(String),
+
+    /// Failed to read or write a persistent cache store
+    #[error("Cache storage failed: {0}")]
+    StorageError(String),
+
+    /// Cache configuration does not fit the router
+    #[error("Invalid cache config: {0}")]
+    InvalidConfig(String),
 }
 
 /// Result type for cache operations
@@ -64,6 +72,12 @@ mod tests {
         assert_eq!(error.to_string(), "Lock acquisition failed: timeout");
     }
 
+    #[test]
+    fn test_cache_error_storage_error_display() {
+        let error = CacheError::StorageError("disk full".to_string());
+        assert_eq!(error.to_string(), "Cache storage failed: disk full");
+    }
+
     #[test]
     fn test_cache_error_is_send_sync() {
         fn assert_send<T: Send>() {}
@@ -72,6 +86,12 @@ mod tests {
         assert_sync::<CacheError>();
     }
 
+    #[test]
+    fn test_cache_error_invalid_config_display() {
+        let error = CacheError::InvalidConfig("bad ttl".to_string());
+        assert_eq!(error.to_string(), "Invalid cache config: bad ttl");
+    }
+
     #[test]
     fn test_cache_result_ok() {
         // Test that CacheResult<T> works correctly with Ok values
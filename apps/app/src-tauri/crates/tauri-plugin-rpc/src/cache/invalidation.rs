@@ -0,0 +1,134 @@
+//! Invalidation notifications for client-side caches
+
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// A cache entry, identified by the call that produced it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InvalidatedEntry {
+    /// Procedure path
+    pub path: String,
+    /// Call input, as keyed by the cache
+    pub input: serde_json::Value,
+    /// User the entry belonged to, for procedures cached per user
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+}
+
+impl InvalidatedEntry {
+    /// Recover the call from a key built by [`generate_cache_key`](super::generate_cache_key)
+    pub(crate) fn from_key(key: &str) -> Self {
+        // Paths never contain ':', the normalized input is valid JSON, and a
+        // user id may follow it as a JSON string
+        let (path, rest) = key.split_once(':').unwrap_or((key, "null"));
+        let mut values = serde_json::Deserializer::from_str(rest).into_iter::<serde_json::Value>();
+        let input = values.next().and_then(Result::ok).unwrap_or_default();
+        let user = match values.next() {
+            Some(Ok(serde_json::Value::String(user))) => Some(user),
+            _ => None,
+        };
+        Self {
+            path: path.to_string(),
+            input,
+            user,
+        }
+    }
+}
+
+/// What triggered an invalidation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "cause", rename_all = "camelCase")]
+pub enum InvalidationCause {
+    /// Specific entries, through `invalidate` or `invalidate_batch`
+    Entries,
+    /// Every entry whose path matches a pattern
+    Pattern {
+        /// The pattern, e.g. `user.*`
+        pattern: String,
+    },
+    /// Every entry carrying one of the tags
+    Tags {
+        /// The invalidated tags
+        tags: Vec<String>,
+    },
+    /// The whole cache
+    All,
+}
+
+/// Notification sent to [`Cache::on_invalidate`](super::Cache::on_invalidate) listeners
+///
+/// `entries` lists the requested entries for [`InvalidationCause::Entries`]
+/// and the entries actually removed for patterns and tags, so clients can
+/// drop exactly those queries. It is empty for [`InvalidationCause::All`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheInvalidation {
+    /// What triggered the invalidation
+    #[serde(flatten)]
+    pub cause: InvalidationCause,
+    /// The affected entries
+    pub entries: Vec<InvalidatedEntry>,
+}
+
+/// Callback registered with [`Cache::on_invalidate`](super::Cache::on_invalidate)
+pub(crate) type InvalidationListener = Arc<dyn Fn(&CacheInvalidation) + Send + Sync>;
+
+/// Name of the Tauri event carrying [`CacheInvalidation`]s
+///
+/// Uses the plugin's subscription event prefix, e.g.
+/// `rpc:subscription:cache_invalidation`.
+pub fn invalidation_event_name(prefix: &str) -> String {
+    format!("{}cache_invalidation", prefix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::entry::scoped_cache_key;
+    use crate::cache::generate_cache_key;
+    use serde_json::json;
+
+    #[test]
+    fn test_entry_from_key_round_trip() {
+        let input = json!({"id": 1, "filter": {"name": "a:b"}});
+        let entry = InvalidatedEntry::from_key(&generate_cache_key("user.get", &input));
+        assert_eq!(entry.path, "user.get");
+        assert_eq!(entry.input, input);
+        assert_eq!(entry.user, None);
+
+        let key = scoped_cache_key("user.get", &input, Some("ann \"a\""));
+        let entry = InvalidatedEntry::from_key(&key);
+        assert_eq!(entry.input, input);
+        assert_eq!(entry.user.as_deref(), Some("ann \"a\""));
+    }
+
+    #[test]
+    fn test_invalidation_serialization() {
+        let invalidation = CacheInvalidation {
+            cause: InvalidationCause::Tags {
+                tags: vec!["user:1".into()],
+            },
+            entries: vec![InvalidatedEntry {
+                path: "user.get".into(),
+                input: json!(1),
+                user: None,
+            }],
+        };
+        assert_eq!(
+            serde_json::to_value(&invalidation).unwrap(),
+            json!({
+                "cause": "tags",
+                "tags": ["user:1"],
+                "entries": [{"path": "user.get", "input": 1}]
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(CacheInvalidation {
+                cause: InvalidationCause::All,
+                entries: Vec::new(),
+            })
+            .unwrap(),
+            json!({"cause": "all", "entries": []})
+        );
+    }
+}
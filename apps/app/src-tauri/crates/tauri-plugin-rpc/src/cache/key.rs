@@ -0,0 +1,181 @@
+//! Per-procedure cache key selection
+
+use serde_json::{Map, Value};
+use std::fmt;
+use std::sync::Arc;
+
+/// Custom function deriving the keyed part of an input
+pub type CacheKeyFn = Arc<dyn Fn(&Value) -> Value + Send + Sync>;
+
+/// Which part of a procedure's input identifies its cache entry
+///
+/// By default the whole input is keyed, so inputs differing only in an
+/// irrelevant field, such as a client nonce, never share an entry. Fields are
+/// addressed with JSON pointers (RFC 6901), e.g. `/filter/name`.
+#[derive(Clone)]
+pub enum CacheKeySelector {
+    /// Key on these fields only, kept where they sit in the input
+    ///
+    /// Missing fields are left out; array elements before an included one
+    /// are keyed as `null`.
+    Include(Vec<String>),
+    /// Key on everything but these fields
+    Exclude(Vec<String>),
+    /// Key on the value returned by a function of the input
+    Custom(CacheKeyFn),
+}
+
+impl CacheKeySelector {
+    /// Derive the keyed value from an input
+    pub fn select(&self, input: &Value) -> Value {
+        match self {
+            Self::Include(pointers) => {
+                let mut selected = Value::Object(Map::new());
+                for pointer in pointers {
+                    copy_pointer(&mut selected, input, pointer);
+                }
+                selected
+            }
+            Self::Exclude(pointers) => {
+                let mut selected = input.clone();
+                for pointer in pointers {
+                    remove_pointer(&mut selected, pointer);
+                }
+                selected
+            }
+            Self::Custom(key_fn) => key_fn(input),
+        }
+    }
+}
+
+impl fmt::Debug for CacheKeySelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Include(pointers) => f.debug_tuple("Include").field(pointers).finish(),
+            Self::Exclude(pointers) => f.debug_tuple("Exclude").field(pointers).finish(),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// Copy the value a JSON pointer refers to, if any, to the same place in `selected`
+fn copy_pointer(selected: &mut Value, input: &Value, pointer: &str) -> Option<()> {
+    let value = input.pointer(pointer)?;
+    let mut source = input;
+    let mut target = selected;
+    for token in pointer.split('/').skip(1) {
+        let token = token.replace("~1", "/").replace("~0", "~");
+        target = match source {
+            Value::Object(fields) => {
+                source = fields.get(&token)?;
+                if !target.is_object() {
+                    *target = Value::Object(Map::new());
+                }
+                let fields = target.as_object_mut()?;
+                fields.entry(token).or_insert(Value::Null)
+            }
+            Value::Array(items) => {
+                let index = token.parse::<usize>().ok()?;
+                source = items.get(index)?;
+                if !target.is_array() {
+                    *target = Value::Array(Vec::new());
+                }
+                let items = target.as_array_mut()?;
+                if items.len() <= index {
+                    items.resize(index + 1, Value::Null);
+                }
+                &mut items[index]
+            }
+            _ => return None,
+        };
+    }
+    *target = value.clone();
+    Some(())
+}
+
+/// Remove the value a JSON pointer refers to, if any
+fn remove_pointer(value: &mut Value, pointer: &str) {
+    let Some((parent, token)) = pointer.rsplit_once('/') else {
+        return;
+    };
+    let token = token.replace("~1", "/").replace("~0", "~");
+    match value.pointer_mut(parent) {
+        Some(Value::Object(fields)) => {
+            fields.remove(&token);
+        }
+        Some(Value::Array(items)) => {
+            if let Ok(index) = token.parse::<usize>()
+                && index < items.len()
+            {
+                items.remove(index);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn input() -> Value {
+        json!({
+            "id": 1,
+            "nonce": "abc",
+            "filter": {"name": "ann", "a/b": true},
+            "tags": ["x", "y"]
+        })
+    }
+
+    #[test]
+    fn test_include_keeps_listed_fields() {
+        let selector =
+            CacheKeySelector::Include(vec!["/id".into(), "/filter/name".into(), "/missing".into()]);
+        assert_eq!(
+            selector.select(&input()),
+            json!({"id": 1, "filter": {"name": "ann"}})
+        );
+    }
+
+    #[test]
+    fn test_include_keeps_input_shape() {
+        let selector = CacheKeySelector::Include(vec![
+            "/filter/a~1b".into(),
+            "/tags/1".into(),
+            "/filter/name".into(),
+        ]);
+        assert_eq!(
+            selector.select(&input()),
+            json!({"filter": {"a/b": true, "name": "ann"}, "tags": [null, "y"]})
+        );
+
+        // A field and its parent both listed key on the whole parent
+        let selector = CacheKeySelector::Include(vec!["/filter/name".into(), "/filter".into()]);
+        assert_eq!(
+            selector.select(&input()),
+            json!({"filter": {"name": "ann", "a/b": true}})
+        );
+    }
+
+    #[test]
+    fn test_exclude_drops_listed_fields() {
+        let selector = CacheKeySelector::Exclude(vec![
+            "/nonce".into(),
+            "/filter/a~1b".into(),
+            "/tags/0".into(),
+            "/missing/field".into(),
+        ]);
+        assert_eq!(
+            selector.select(&input()),
+            json!({"id": 1, "filter": {"name": "ann"}, "tags": ["y"]})
+        );
+    }
+
+    #[test]
+    fn test_custom_key_fn() {
+        let selector = CacheKeySelector::Custom(Arc::new(|input| input["id"].clone()));
+        assert_eq!(selector.select(&input()), json!(1));
+        assert_eq!(format!("{:?}", selector), "Custom(..)");
+    }
+}
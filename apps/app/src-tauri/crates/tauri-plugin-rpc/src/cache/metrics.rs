@@ -13,6 +13,12 @@ pub struct CacheMetrics {
     evictions: AtomicU64,
     /// Number of entries invalidated
     invalidations: AtomicU64,
+    /// Number of hits served from an expired entry within its stale window
+    stale_hits: AtomicU64,
+    /// Number of background refreshes started
+    refreshes: AtomicU64,
+    /// Number of values not stored for exceeding the size limit
+    oversized: AtomicU64,
 }
 
 impl CacheMetrics {
@@ -22,6 +28,9 @@ impl CacheMetrics {
             misses: AtomicU64::new(0),
             evictions: AtomicU64::new(0),
             invalidations: AtomicU64::new(0),
+            stale_hits: AtomicU64::new(0),
+            refreshes: AtomicU64::new(0),
+            oversized: AtomicU64::new(0),
         }
     }
 
@@ -37,14 +46,22 @@ impl CacheMetrics {
         self.evictions.fetch_add(1, Ordering::Relaxed);
     }
 
-    pub fn record_invalidation(&self) {
-        self.invalidations.fetch_add(1, Ordering::Relaxed);
-    }
-
     pub fn record_invalidations(&self, count: u64) {
         self.invalidations.fetch_add(count, Ordering::Relaxed);
     }
 
+    pub fn record_stale_hit(&self) {
+        self.stale_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_refresh(&self) {
+        self.refreshes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_oversized(&self) {
+        self.oversized.fetch_add(1, Ordering::Relaxed);
+    }
+
     pub fn get_hits(&self) -> u64 {
         self.hits.load(Ordering::Relaxed)
     }
@@ -61,6 +78,18 @@ impl CacheMetrics {
         self.invalidations.load(Ordering::Relaxed)
     }
 
+    pub fn get_stale_hits(&self) -> u64 {
+        self.stale_hits.load(Ordering::Relaxed)
+    }
+
+    pub fn get_refreshes(&self) -> u64 {
+        self.refreshes.load(Ordering::Relaxed)
+    }
+
+    pub fn get_oversized(&self) -> u64 {
+        self.oversized.load(Ordering::Relaxed)
+    }
+
     pub fn calculate_hit_ratio(&self) -> f64 {
         let hits = self.get_hits();
         let misses = self.get_misses();
@@ -78,6 +107,9 @@ impl CacheMetrics {
         self.misses.store(0, Ordering::Relaxed);
         self.evictions.store(0, Ordering::Relaxed);
         self.invalidations.store(0, Ordering::Relaxed);
+        self.stale_hits.store(0, Ordering::Relaxed);
+        self.refreshes.store(0, Ordering::Relaxed);
+        self.oversized.store(0, Ordering::Relaxed);
     }
 }
 
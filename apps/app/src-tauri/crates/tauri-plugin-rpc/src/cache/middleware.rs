@@ -4,14 +4,31 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::Context;
-use crate::middleware::{MiddlewareFn, Next, Request, from_fn};
+use crate::middleware::{MiddlewareFn, Next, ProcedureType, Request, from_fn};
+use crate::pattern::PathPattern;
 
-use super::store::Cache;
+use super::store::{Cache, CacheLookup};
 
 /// Create a caching middleware for query procedures
 ///
 /// This middleware caches successful query responses and returns cached
-/// values when available. It does NOT cache mutations or subscriptions.
+/// values when available. It does NOT cache mutations or subscriptions,
+/// unless a procedure is declared `.cacheable()`; procedures declared
+/// `.no_store()` are never cached. See [`CachePolicy`](super::CachePolicy).
+///
+/// Tags the handler declares with [`Context::add_cache_tags`] are stored
+/// with the cached result, for [`Cache::invalidate_tags`] to find later.
+///
+/// Entries are keyed as configured with the `CacheConfig` key selectors.
+/// Procedures configured with `with_vary_by_user` are cached per
+/// [`Context::user_id`], so the auth middleware must run first; calls
+/// without a user skip the cache for them.
+///
+/// Entries within their stale-while-revalidate window, and hot entries due
+/// for a [`RefreshAhead`](super::RefreshAhead), are returned immediately
+/// while a background task runs the handler again and stores the new result.
+/// A failed refresh is logged and leaves the entry as it was, and a refresh
+/// that finishes after an invalidation is discarded rather than stored.
 ///
 /// # Example
 ///
@@ -38,20 +55,65 @@ where
             // Optimization: Use references for cache lookup to avoid unnecessary cloning.
             // We only clone when we need to store values in the cache.
 
-            // Only cache queries (check procedure type if available)
-            // For now, we cache all procedures - mutations should use invalidation
+            if !ctx.cache_policy().allows(req.procedure_type) {
+                tracing::trace!(path = %req.path, "Cache bypass: not cacheable");
+                return next(ctx, req).await;
+            }
+
             if !cache.config.should_cache(&req.path) {
                 tracing::trace!(path = %req.path, "Cache bypass: path excluded");
                 return next(ctx, req).await;
             }
 
+            let user = ctx.user_id().map(str::to_string);
+            if user.is_none() && cache.config.varies_by_user(&req.path) {
+                tracing::trace!(path = %req.path, "Cache bypass: no user to scope the entry to");
+                return next(ctx, req).await;
+            }
+
             // Check cache first using references (no cloning needed for lookup)
-            if let Some(cached) = cache.get(&req.path, &req.input).await {
-                tracing::debug!(
-                    path = %req.path,
-                    "Cache hit"
-                );
-                return Ok(cached);
+            match cache
+                .lookup_for_user(&req.path, &req.input, user.as_deref())
+                .await
+            {
+                CacheLookup::Hit(cached) => {
+                    tracing::debug!(
+                        path = %req.path,
+                        "Cache hit"
+                    );
+                    return Ok(cached);
+                }
+                CacheLookup::Revalidate(cached, claim) => {
+                    tracing::debug!(path = %req.path, "Cache hit, refreshing in background");
+                    tokio::spawn(async move {
+                        // Hold the claim until the new result is stored
+                        let path = req.path.clone();
+                        let input = req.input.clone();
+                        let tags = ctx.cache_tags().clone();
+                        match next(ctx, req).await {
+                            Ok(result) => {
+                                let stored = cache
+                                    .set_refreshed(
+                                        &claim,
+                                        &path,
+                                        &input,
+                                        result,
+                                        tags.to_vec(),
+                                        user.as_deref(),
+                                    )
+                                    .await;
+                                if stored {
+                                    tracing::trace!(path = %path, "Cache entry refreshed");
+                                }
+                            }
+                            Err(e) => {
+                                tracing::warn!(path = %path, error = %e, "Background cache refresh failed");
+                            }
+                        }
+                    });
+                    return Ok(cached);
+                }
+                CacheLookup::Miss => {}
             }
 
             tracing::debug!(path = %req.path, "Cache miss");
@@ -60,13 +122,22 @@ where
             // This happens after the handler executes successfully
             let path = req.path.clone();
             let input = req.input.clone();
+            let tags = ctx.cache_tags().clone();
 
             // Execute handler
             let result = next(ctx, req).await?;
 
             // Cache successful result (now we use the cloned values)
             let ttl = cache.config.get_ttl(&path);
-            cache.set(&path, &input, result.clone()).await;
+            cache
+                .set_for_user(
+                    &path,
+                    &input,
+                    result.clone(),
+                    tags.to_vec(),
+                    user.as_deref(),
+                )
+                .await;
 
             tracing::trace!(
                 path = %path,
@@ -83,7 +154,11 @@ where
 /// Create a cache invalidation middleware for mutation procedures
 ///
 /// This middleware invalidates cache entries after successful mutations.
-/// Configure patterns to invalidate related cache entries.
+/// Configure patterns to invalidate related cache entries; they use the
+/// shared [pattern syntax](crate::pattern) and are compiled once. A
+/// mutation that declares tags with [`Context::add_cache_tags`] also
+/// invalidates every entry carrying one of them, whatever its path; no rule
+/// is needed for that.
 ///
 /// # Example
 ///
@@ -109,12 +184,15 @@ pub fn invalidation_middleware<Ctx>(
 where
     Ctx: Clone + Send + Sync + 'static,
 {
-    let rules: HashMap<String, Vec<String>> = rules
+    let rules: HashMap<String, Vec<PathPattern>> = rules
         .into_iter()
         .map(|(path, patterns)| {
             (
                 path.into(),
-                patterns.into_iter().map(|p| p.into()).collect(),
+                patterns
+                    .into_iter()
+                    .map(|p| PathPattern::compile(&p.into()))
+                    .collect(),
             )
         })
         .collect();
@@ -124,11 +202,26 @@ where
         let cache = cache.clone();
         let rules = Arc::clone(&rules);
         let path = req.path.clone();
+        let is_mutation = req.procedure_type == ProcedureType::Mutation;
+        let tags = ctx.cache_tags().clone();
 
         async move {
             // Execute handler first
             let result = next(ctx, req).await?;
 
+            // Invalidate the entries tagged by the mutation
+            if is_mutation {
+                let tags = tags.to_vec();
+                if !tags.is_empty() {
+                    tracing::debug!(
+                        path = %path,
+                        tags = %tags.len(),
+                        "Invalidating tagged cache entries"
+                    );
+                    cache.invalidate_tags(&tags).await;
+                }
+            }
+
             // Invalidate cache entries based on rules
             if let Some(patterns) = rules.get(&path) {
                 let pattern_count = patterns.len();
@@ -138,7 +231,7 @@ where
                         pattern = %pattern,
                         "Invalidating cache entries"
                     );
-                    cache.invalidate_pattern(pattern).await;
+                    cache.invalidate_matching(pattern).await;
                 }
                 tracing::trace!(
                     path = %path,
@@ -152,3 +245,233 @@ where
     };
     from_fn(middleware)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::CacheConfig;
+    use crate::{EmptyContext, Router};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_mutation_tags_invalidate_tagged_queries() {
+        let cache = Cache::new(CacheConfig::new());
+        let rules: Vec<(String, Vec<String>)> = Vec::new();
+
+        let router = Router::new()
+            .context(EmptyContext)
+            .middleware_fn(invalidation_middleware(cache.clone(), rules))
+            .middleware_fn(cache_middleware(cache.clone()))
+            .query(
+                "user.get",
+                |ctx: Context<EmptyContext>, id: i64| async move {
+                    ctx.add_cache_tags([format!("user:{}", id)]);
+                    Ok(json!({ "id": id }))
+                },
+            )
+            .query(
+                "team.members",
+                |ctx: Context<EmptyContext>, id: i64| async move {
+                    ctx.add_cache_tags(["user:1", "user:2"]);
+                    Ok(json!({ "team": id }))
+                },
+            )
+            .mutation(
+                "user.rename",
+                |ctx: Context<EmptyContext>, id: i64| async move {
+                    ctx.add_cache_tags([format!("user:{}", id)]);
+                    Ok(id)
+                },
+            )
+            .compile();
+
+        router.call("user.get", json!(1)).await.unwrap();
+        router.call("user.get", json!(2)).await.unwrap();
+        router.call("team.members", json!(7)).await.unwrap();
+        assert!(cache.contains("team.members", &json!(7)).await);
+
+        router.call("user.rename", json!(1)).await.unwrap();
+
+        assert!(!cache.contains("user.get", &json!(1)).await);
+        assert!(!cache.contains("team.members", &json!(7)).await);
+        assert!(cache.contains("user.get", &json!(2)).await);
+    }
+
+    #[tokio::test]
+    async fn test_only_cacheable_procedures_are_stored() {
+        use crate::ProcedureBuilder;
+        use crate::schema::ProcedureMeta;
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicI64, Ordering};
+
+        let cache = Cache::new(CacheConfig::new());
+        let calls = Arc::new(AtomicI64::new(0));
+        let counter = |calls: Arc<AtomicI64>| {
+            move |_ctx: Context<EmptyContext>, _: ()| {
+                let calls = calls.clone();
+                async move { Ok(calls.fetch_add(1, Ordering::SeqCst)) }
+            }
+        };
+
+        let router = Router::new()
+            .context(EmptyContext)
+            .middleware_fn(cache_middleware(cache.clone()))
+            .query("plain.get", counter(calls.clone()))
+            .mutation("plain.bump", counter(calls.clone()))
+            .procedure("live.get")
+            .no_store()
+            .query(counter(calls.clone()))
+            .register(
+                ProcedureBuilder::new("idempotent.put")
+                    .cacheable()
+                    // Later metadata keeps the declared policy
+                    .meta(ProcedureMeta::new().description("Idempotent put"))
+                    .mutation(counter(calls.clone())),
+            )
+            .compile();
+        assert_eq!(
+            router.cache_policy("idempotent.put"),
+            crate::cache::CachePolicy::Cacheable
+        );
+
+        for path in ["plain.get", "plain.bump", "live.get", "idempotent.put"] {
+            let first = router.call(path, json!(null)).await.unwrap();
+            let second = router.call(path, json!(null)).await.unwrap();
+            let cached = cache.contains(path, &json!(null)).await;
+            assert_eq!(first == second, cached, "{}", path);
+        }
+
+        assert!(cache.contains("plain.get", &json!(null)).await);
+        assert!(!cache.contains("plain.bump", &json!(null)).await);
+        assert!(!cache.contains("live.get", &json!(null)).await);
+        assert!(cache.contains("idempotent.put", &json!(null)).await);
+    }
+
+    #[tokio::test]
+    async fn test_vary_by_user_keys_on_authenticated_user() {
+        use crate::auth::{AlwaysAuthProvider, auth_middleware};
+
+        let cache = Cache::new(CacheConfig::new().with_vary_by_user("me.*"));
+        let router_for = |user: &str| {
+            Router::new()
+                .context(EmptyContext)
+                .middleware_fn(auth_middleware(AlwaysAuthProvider::new(user)))
+                .middleware_fn(cache_middleware(cache.clone()))
+                .query(
+                    "me.profile",
+                    |ctx: Context<EmptyContext>, _: ()| async move {
+                        Ok(json!({ "user": ctx.user_id() }))
+                    },
+                )
+                .compile()
+        };
+
+        let alice = router_for("alice");
+        let bob = router_for("bob");
+
+        assert_eq!(
+            alice.call("me.profile", json!(null)).await.unwrap(),
+            json!({ "user": "alice" })
+        );
+        assert_eq!(
+            bob.call("me.profile", json!(null)).await.unwrap(),
+            json!({ "user": "bob" })
+        );
+        assert_eq!(cache.stats().await.total_entries, 2);
+
+        // Anonymous calls are never cached, nor served another user's entry
+        let anonymous =
+            Router::new()
+                .context(EmptyContext)
+                .middleware_fn(cache_middleware(cache.clone()))
+                .query(
+                    "me.profile",
+                    |ctx: Context<EmptyContext>, _: ()| async move {
+                        Ok(json!({ "user": ctx.user_id() }))
+                    },
+                )
+                .compile();
+        assert_eq!(
+            anonymous.call("me.profile", json!(null)).await.unwrap(),
+            json!({ "user": null })
+        );
+        assert_eq!(cache.stats().await.total_entries, 2);
+    }
+
+    #[tokio::test]
+    async fn test_stale_entries_refresh_in_background() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicI64, Ordering};
+        use std::time::Duration;
+
+        let cache = Cache::new(
+            CacheConfig::new()
+                .with_default_ttl(Duration::from_millis(100))
+                .with_stale_while_revalidate("counter.get", Duration::from_secs(60)),
+        );
+        let calls = Arc::new(AtomicI64::new(0));
+
+        let router = Router::new()
+            .context(EmptyContext)
+            .middleware_fn(cache_middleware(cache.clone()))
+            .query("counter.get", move |_ctx: Context<EmptyContext>, _: ()| {
+                let calls = calls.clone();
+                async move { Ok(calls.fetch_add(1, Ordering::SeqCst) + 1) }
+            })
+            .compile();
+
+        assert_eq!(router.call("counter.get", json!(null)).await.unwrap(), 1);
+        tokio::time::sleep(Duration::from_millis(150)).await;
+
+        // The stale value is served while the handler runs again
+        assert_eq!(router.call("counter.get", json!(null)).await.unwrap(), 1);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(router.call("counter.get", json!(null)).await.unwrap(), 2);
+
+        let stats = cache.stats().await;
+        assert_eq!(stats.stale_hits, 1);
+        assert_eq!(stats.background_refreshes, 1);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_finishing_after_invalidation_is_discarded() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicI64, Ordering};
+        use std::time::Duration;
+
+        let cache = Cache::new(
+            CacheConfig::new()
+                .with_default_ttl(Duration::from_millis(50))
+                .with_stale_while_revalidate("counter.get", Duration::from_secs(60)),
+        );
+        let calls = Arc::new(AtomicI64::new(0));
+
+        let router = Router::new()
+            .context(EmptyContext)
+            .middleware_fn(cache_middleware(cache.clone()))
+            .query("counter.get", move |_ctx: Context<EmptyContext>, _: ()| {
+                let calls = calls.clone();
+                async move {
+                    let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                    if call == 2 {
+                        // The refresh reads its data before the invalidation
+                        tokio::time::sleep(Duration::from_millis(40)).await;
+                    }
+                    Ok(call)
+                }
+            })
+            .compile();
+
+        assert_eq!(router.call("counter.get", json!(null)).await.unwrap(), 1);
+        tokio::time::sleep(Duration::from_millis(80)).await;
+
+        // Serves the stale value and starts the slow refresh
+        assert_eq!(router.call("counter.get", json!(null)).await.unwrap(), 1);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        cache.invalidate("counter.get", &json!(null)).await;
+        tokio::time::sleep(Duration::from_millis(60)).await;
+
+        assert!(!cache.contains("counter.get", &json!(null)).await);
+        assert_eq!(router.call("counter.get", json!(null)).await.unwrap(), 3);
+    }
+}
@@ -1,6 +1,8 @@
 //! Caching layer for RPC query procedures
 //!
 //! Provides configurable caching with TTL support and automatic invalidation.
+//! Entries live in a [`CacheStore`]: an in-memory [`LruStore`] by default, or
+//! a [`FileCacheStore`] that survives restarts.
 //!
 //! # Example
 //!
@@ -44,28 +46,50 @@
 //! Cache keys are not logged to avoid exposing potentially sensitive input data.
 //! Only procedure paths and operation outcomes are included in trace events.
 
+mod backend;
 mod config;
 mod entry;
 mod error;
+mod invalidation;
+mod key;
 mod metrics;
 mod middleware;
-mod pattern;
+mod persistent;
+mod single_flight;
+mod snapshot;
 mod store;
+mod tags;
 
 // Re-export public API with inline documentation
 #[doc(inline)]
-pub use config::{CacheConfig, DEFAULT_MAX_ENTRIES, DEFAULT_TTL_SECS};
+pub use backend::{CacheStore, LruStore};
+#[doc(inline)]
+pub use config::{CacheConfig, CachePolicy, DEFAULT_MAX_ENTRIES, DEFAULT_TTL_SECS, RefreshAhead};
 #[doc(inline)]
 pub use entry::{CacheEntry, generate_cache_key};
 #[doc(inline)]
 pub use error::{CacheError, CacheResult};
 #[doc(inline)]
+pub use invalidation::{
+    CacheInvalidation, InvalidatedEntry, InvalidationCause, invalidation_event_name,
+};
+#[doc(inline)]
+pub use key::{CacheKeyFn, CacheKeySelector};
+#[doc(inline)]
 pub use middleware::{cache_middleware, invalidation_middleware};
 #[doc(inline)]
-pub use store::{Cache, CacheStats};
+pub use persistent::FileCacheStore;
+#[doc(inline)]
+pub use single_flight::{SingleFlight, dedup_middleware};
+#[doc(inline)]
+pub use snapshot::{CacheSnapshot, CacheSnapshotEntry};
+#[doc(inline)]
+pub use store::{Cache, CacheLookup, CacheStats, RefreshClaim};
+
+pub(crate) use tags::CacheTags;
 
 // Re-export pattern matching for advanced use cases
-pub use pattern::pattern_matches;
+pub use crate::pattern::pattern_matches;
 
 // Property-Based Tests
 #[cfg(test)]
@@ -0,0 +1,500 @@
+//! File-backed cache store that survives restarts
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use super::backend::{CacheStore, LruStore};
+use super::entry::{CacheEntry, serialized_size};
+use super::error::{CacheError, CacheResult};
+
+/// Minimum number of log records before the log is compacted
+const MIN_COMPACTION_RECORDS: usize = 64;
+
+/// One line of the append-only cache log
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum LogRecord {
+    Put {
+        key: String,
+        value: serde_json::Value,
+        /// Creation time in milliseconds since the Unix epoch
+        created_at_ms: u64,
+        ttl_ms: u64,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        tags: Vec<String>,
+        #[serde(default, skip_serializing_if = "is_zero")]
+        stale_window_ms: u64,
+    },
+    Remove {
+        key: String,
+    },
+    Clear,
+}
+
+/// Work queued for the background log writer
+#[derive(Debug)]
+enum WriterCommand {
+    /// Append one record
+    Append(LogRecord),
+    /// Replace the log with these records, oldest first
+    Compact(Vec<LogRecord>),
+}
+
+impl LogRecord {
+    fn put(key: &str, entry: &CacheEntry) -> Self {
+        let created_at = SystemTime::now()
+            .checked_sub(entry.created_at.elapsed())
+            .unwrap_or(UNIX_EPOCH);
+        LogRecord::Put {
+            key: key.to_string(),
+            value: entry.value.clone(),
+            created_at_ms: unix_millis(created_at),
+            ttl_ms: entry.ttl.as_millis() as u64,
+            tags: entry.tags.clone(),
+            stale_window_ms: entry.stale_window.as_millis() as u64,
+        }
+    }
+}
+
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Cache store persisted to a single append-only log file
+///
+/// Entries live in memory in an [`LruStore`]; every change is appended to
+/// the log as a JSON line. On open the log is replayed, dropping entries
+/// whose TTL ran out while the app was closed and keeping at most
+/// `max_entries`, then rewritten with only the live entries. The log is
+/// compacted the same way whenever it grows well past the live entries.
+///
+/// Log writes and compactions run on a dedicated writer thread, so cache
+/// operations never wait on the disk while the cache lock is held. Changes
+/// reach the file shortly after they are made; dropping the store waits
+/// until every queued change is written.
+///
+/// Write failures are logged and otherwise ignored, so a broken disk
+/// degrades the store to in-memory caching until the next compaction
+/// manages to rewrite the log.
+///
+/// Values are stored as plain JSON. This includes entries cached per user
+/// (see [`CacheConfig::with_vary_by_user`](super::CacheConfig::with_vary_by_user)),
+/// whose keys also contain the user id. Keep procedures returning secrets or
+/// personal data out of a persistent cache, or place the log where only the
+/// app can read it.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let path = app.path().app_data_dir()?.join("rpc-cache.log");
+/// let store = FileCacheStore::open(path, config.max_entries)?;
+/// let cache = Cache::with_store(config, store);
+/// ```
+#[derive(Debug)]
+pub struct FileCacheStore {
+    path: PathBuf,
+    entries: LruStore,
+    writer: Option<LogWriter>,
+    records: usize,
+}
+
+/// Handle to the thread owning the log file
+#[derive(Debug)]
+struct LogWriter {
+    commands: Sender<WriterCommand>,
+    thread: JoinHandle<()>,
+}
+
+impl FileCacheStore {
+    /// Open the store at `path`, loading entries persisted by a previous run
+    ///
+    /// The file and its parent directories are created when missing.
+    pub fn open(path: impl Into<PathBuf>, max_entries: usize) -> CacheResult<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| storage_error(&path, e))?;
+        }
+
+        let mut store = Self {
+            entries: LruStore::new(max_entries),
+            path,
+            writer: None,
+            records: 0,
+        };
+        store.load()?;
+
+        let records = store.live_records();
+        let log = rewrite_log(&store.path, &records)?;
+        store.records = records.len();
+        let (commands, queue) = mpsc::channel();
+        let writer_path = store.path.clone();
+        let thread = std::thread::Builder::new()
+            .name("rpc-cache-log".into())
+            .spawn(move || run_writer(&writer_path, Some(log), queue))
+            .map_err(|e| storage_error(&store.path, e))?;
+        store.writer = Some(LogWriter { commands, thread });
+
+        tracing::debug!(
+            path = %store.path.display(),
+            entries = store.entries.len(),
+            "Persistent cache loaded"
+        );
+        Ok(store)
+    }
+
+    /// Path of the backing log file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    fn load(&mut self) -> CacheResult<()> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(storage_error(&self.path, e)),
+        };
+
+        let now = SystemTime::now();
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| storage_error(&self.path, e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            // A torn final line from a crash is skipped rather than fatal
+            let Ok(record) = serde_json::from_str::<LogRecord>(&line) else {
+                tracing::warn!(path = %self.path.display(), "Skipping malformed cache log record");
+                continue;
+            };
+            match record {
+                LogRecord::Put {
+                    key,
+                    value,
+                    created_at_ms,
+                    ttl_ms,
+                    tags,
+                    stale_window_ms,
+                } => {
+                    let created_at = UNIX_EPOCH + Duration::from_millis(created_at_ms);
+                    let age = now.duration_since(created_at).unwrap_or_default();
+                    let ttl = Duration::from_millis(ttl_ms);
+                    let stale_window = Duration::from_millis(stale_window_ms);
+                    if age >= ttl.saturating_add(stale_window) {
+                        self.entries.remove(&key);
+                        continue;
+                    }
+                    let entry = CacheEntry {
+                        created_at: Instant::now().checked_sub(age).unwrap_or_else(Instant::now),
+                        ttl,
+                        tags,
+                        stale_window,
+                        hits: 0,
+                        size: serialized_size(&value),
+                        value,
+                    };
+                    self.entries.put(key, entry);
+                }
+                LogRecord::Remove { key } => {
+                    self.entries.remove(&key);
+                }
+                LogRecord::Clear => self.entries.clear(),
+            }
+        }
+        Ok(())
+    }
+
+    /// `Put` records of the live entries, oldest first so replaying restores
+    /// the recency order
+    fn live_records(&self) -> Vec<LogRecord> {
+        let mut entries: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| !entry.is_discardable())
+            .collect();
+        entries.reverse();
+        entries
+            .into_iter()
+            .map(|(key, entry)| LogRecord::put(key, entry))
+            .collect()
+    }
+
+    /// Queue a record for the log, compacting it once it grew too long
+    fn append(&mut self, record: LogRecord) {
+        let Some(writer) = self.writer.as_ref() else {
+            return;
+        };
+        if writer.commands.send(WriterCommand::Append(record)).is_err() {
+            return;
+        }
+        self.records += 1;
+
+        if self.records > MIN_COMPACTION_RECORDS.max(self.entries.capacity() * 2) {
+            let records = self.live_records();
+            self.records = records.len();
+            if let Some(writer) = self.writer.as_ref() {
+                let _ = writer.commands.send(WriterCommand::Compact(records));
+            }
+        }
+    }
+}
+
+impl Drop for FileCacheStore {
+    fn drop(&mut self) {
+        // Closing the queue lets the writer finish what is queued and exit
+        if let Some(LogWriter { commands, thread }) = self.writer.take() {
+            drop(commands);
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Apply queued commands until the store is dropped
+///
+/// Commands that arrive together are written with a single flush.
+fn run_writer(path: &Path, mut log: Option<BufWriter<File>>, queue: Receiver<WriterCommand>) {
+    while let Ok(first) = queue.recv() {
+        for command in std::iter::once(first).chain(queue.try_iter()) {
+            let result = match command {
+                WriterCommand::Append(record) => match log.as_mut() {
+                    Some(writer) => {
+                        write_record(writer, &record).map_err(|e| storage_error(path, e))
+                    }
+                    None => Ok(()),
+                },
+                WriterCommand::Compact(records) => {
+                    log = None;
+                    rewrite_log(path, &records).map(|writer| log = Some(writer))
+                }
+            };
+            if let Err(e) = result {
+                log_write_failure(&e);
+                log = None;
+            }
+        }
+        if let Some(writer) = log.as_mut()
+            && let Err(e) = writer.flush()
+        {
+            log_write_failure(&storage_error(path, e));
+            log = None;
+        }
+    }
+}
+
+/// Replace the log at `path` with `records` and open it for appending
+fn rewrite_log(path: &Path, records: &[LogRecord]) -> CacheResult<BufWriter<File>> {
+    let tmp = path.with_extension("tmp");
+    let mut writer = BufWriter::new(File::create(&tmp).map_err(|e| storage_error(&tmp, e))?);
+    for record in records {
+        write_record(&mut writer, record).map_err(|e| storage_error(&tmp, e))?;
+    }
+    writer.flush().map_err(|e| storage_error(&tmp, e))?;
+    drop(writer);
+    fs::rename(&tmp, path).map_err(|e| storage_error(path, e))?;
+
+    let file = OpenOptions::new()
+        .append(true)
+        .open(path)
+        .map_err(|e| storage_error(path, e))?;
+    Ok(BufWriter::new(file))
+}
+
+fn log_write_failure(error: &CacheError) {
+    tracing::warn!(
+        error = %error,
+        "Failed to write cache log; continuing in memory"
+    );
+}
+
+fn write_record(writer: &mut impl Write, record: &LogRecord) -> std::io::Result<()> {
+    serde_json::to_writer(&mut *writer, record)?;
+    writer.write_all(b"\n")
+}
+
+fn storage_error(path: &Path, error: std::io::Error) -> CacheError {
+    CacheError::StorageError(format!("{}: {}", path.display(), error))
+}
+
+impl CacheStore for FileCacheStore {
+    fn get(&mut self, key: &str) -> Option<&mut CacheEntry> {
+        self.entries.get(key)
+    }
+
+    fn peek(&self, key: &str) -> Option<&CacheEntry> {
+        self.entries.peek(key)
+    }
+
+    fn put(&mut self, key: String, entry: CacheEntry) -> Option<(String, CacheEntry)> {
+        let record = LogRecord::put(&key, &entry);
+        // Evictions need no record: replay applies the same capacity
+        let evicted = self.entries.put(key, entry);
+        self.append(record);
+        evicted
+    }
+
+    fn pop_lru(&mut self) -> Option<(String, CacheEntry)> {
+        let (key, entry) = self.entries.pop_lru()?;
+        self.append(LogRecord::Remove { key: key.clone() });
+        Some((key, entry))
+    }
+
+    fn remove(&mut self, key: &str) -> Option<CacheEntry> {
+        let removed = self.entries.remove(key);
+        if removed.is_some() {
+            self.append(LogRecord::Remove {
+                key: key.to_string(),
+            });
+        }
+        removed
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.append(LogRecord::Clear);
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (&String, &CacheEntry)> + '_> {
+        self.entries.iter()
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.entries.capacity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir()
+            .join(format!("rpc-cache-{}-{}", std::process::id(), nanos))
+            .join(name)
+    }
+
+    fn entry(value: i64, ttl: Duration) -> CacheEntry {
+        CacheEntry::new(json!(value), ttl)
+    }
+
+    #[test]
+    fn test_entries_survive_reopen() {
+        let path = temp_path("cache.log");
+        {
+            let mut store = FileCacheStore::open(&path, 10).unwrap();
+            store.put("a".into(), entry(1, Duration::from_secs(60)));
+            store.put("b".into(), entry(2, Duration::from_secs(60)));
+            store.remove("a");
+        }
+
+        let store = FileCacheStore::open(&path, 10).unwrap();
+        assert!(store.peek("a").is_none());
+        let b = store.peek("b").unwrap();
+        assert_eq!(b.value, json!(2));
+        assert!(b.remaining_ttl() <= Duration::from_secs(60));
+        assert!(!b.is_expired());
+    }
+
+    #[test]
+    fn test_tags_survive_reopen() {
+        let path = temp_path("cache.log");
+        {
+            let mut store = FileCacheStore::open(&path, 10).unwrap();
+            let tagged = entry(1, Duration::from_secs(60)).with_tags(vec!["user:1".into()]);
+            store.put("a".into(), tagged);
+        }
+
+        let store = FileCacheStore::open(&path, 10).unwrap();
+        assert_eq!(store.peek("a").unwrap().tags, ["user:1"]);
+    }
+
+    #[test]
+    fn test_expired_entries_dropped_on_reload() {
+        let path = temp_path("cache.log");
+        {
+            let mut store = FileCacheStore::open(&path, 10).unwrap();
+            store.put("short".into(), entry(1, Duration::from_millis(10)));
+            store.put("long".into(), entry(2, Duration::from_secs(60)));
+        }
+        std::thread::sleep(Duration::from_millis(20));
+
+        let store = FileCacheStore::open(&path, 10).unwrap();
+        assert!(store.peek("short").is_none());
+        assert!(store.peek("long").is_some());
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn test_reload_respects_max_entries() {
+        let path = temp_path("cache.log");
+        {
+            let mut store = FileCacheStore::open(&path, 10).unwrap();
+            for i in 0..5 {
+                store.put(format!("k{}", i), entry(i, Duration::from_secs(60)));
+            }
+        }
+
+        // Reopening with a smaller limit keeps the most recent entries
+        let store = FileCacheStore::open(&path, 2).unwrap();
+        assert_eq!(store.len(), 2);
+        assert!(store.peek("k4").is_some());
+        assert!(store.peek("k3").is_some());
+        assert!(store.peek("k0").is_none());
+    }
+
+    #[test]
+    fn test_clear_and_malformed_lines() {
+        let path = temp_path("cache.log");
+        {
+            let mut store = FileCacheStore::open(&path, 10).unwrap();
+            store.put("a".into(), entry(1, Duration::from_secs(60)));
+            store.clear();
+            store.put("b".into(), entry(2, Duration::from_secs(60)));
+        }
+        // Simulate a torn write at the end of the log
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"op":"put","key":"c""#).unwrap();
+        drop(file);
+
+        let store = FileCacheStore::open(&path, 10).unwrap();
+        assert!(store.peek("a").is_none());
+        assert!(store.peek("b").is_some());
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn test_log_is_compacted() {
+        let path = temp_path("cache.log");
+        let mut store = FileCacheStore::open(&path, 4).unwrap();
+        for i in 0..200 {
+            store.put("same".into(), entry(i, Duration::from_secs(60)));
+        }
+        assert_eq!(store.peek("same").unwrap().value, json!(199));
+        drop(store);
+
+        let lines = fs::read_to_string(&path).unwrap().lines().count();
+        assert!(
+            lines <= MIN_COMPACTION_RECORDS + 1,
+            "log has {} lines",
+            lines
+        );
+        let store = FileCacheStore::open(&path, 4).unwrap();
+        assert_eq!(store.peek("same").unwrap().value, json!(199));
+    }
+}
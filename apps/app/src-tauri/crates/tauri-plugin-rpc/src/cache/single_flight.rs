@@ -0,0 +1,328 @@
+//! Request deduplication for concurrent identical queries
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+use futures::FutureExt;
+use futures::future::Shared;
+
+use crate::middleware::{MiddlewareFn, Next, ProcedureType, Request, Response, from_fn};
+use crate::subscription::CancellationSignal;
+use crate::{Context, RpcResult};
+
+use super::entry::scoped_cache_key;
+
+type SharedCall = Shared<Pin<Box<dyn Future<Output = RpcResult<Response>> + Send>>>;
+
+/// One in-flight execution and the callers waiting on it
+struct Flight {
+    call: SharedCall,
+    signal: Arc<CancellationSignal>,
+    waiters: usize,
+}
+
+/// Registry of in-flight query executions, keyed like the cache
+///
+/// Cloning is cheap and clones share the same registry.
+#[derive(Clone, Default)]
+pub struct SingleFlight {
+    flights: Arc<DashMap<String, Flight>>,
+}
+
+impl SingleFlight {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of distinct executions currently in flight
+    pub fn in_flight(&self) -> usize {
+        self.flights.len()
+    }
+
+    /// Run the call built by `start` for `key`, or join the execution already
+    /// in flight for it
+    ///
+    /// `start` receives a cancellation signal owned by the execution rather
+    /// than by any one caller. It fires only once every waiting caller has
+    /// gone away, so one caller aborting never cancels the others. Every
+    /// caller receives a clone of the same result or error.
+    pub async fn run<F, Fut>(&self, key: String, start: F) -> RpcResult<Response>
+    where
+        F: FnOnce(Arc<CancellationSignal>) -> Fut,
+        Fut: Future<Output = RpcResult<Response>> + Send + 'static,
+    {
+        let call = match self.flights.entry(key.clone()) {
+            Entry::Occupied(mut entry) => {
+                tracing::trace!("Joining in-flight execution");
+                let flight = entry.get_mut();
+                flight.waiters += 1;
+                flight.call.clone()
+            }
+            Entry::Vacant(entry) => {
+                let signal = Arc::new(CancellationSignal::new());
+                let call: Pin<Box<dyn Future<Output = RpcResult<Response>> + Send>> =
+                    Box::pin(start(signal.clone()));
+                let call = call.shared();
+                entry.insert(Flight {
+                    call: call.clone(),
+                    signal,
+                    waiters: 1,
+                });
+                call
+            }
+        };
+
+        // Leave the execution once this caller finishes or is dropped; the
+        // last caller to leave forgets it, so an abandoned execution is
+        // never joined
+        let _guard = FlightGuard {
+            flights: &self.flights,
+            key,
+            call: call.clone(),
+        };
+        call.await
+    }
+}
+
+/// Drops one caller's interest in an execution when it goes away.
+struct FlightGuard<'a> {
+    flights: &'a DashMap<String, Flight>,
+    key: String,
+    call: SharedCall,
+}
+
+impl Drop for FlightGuard<'_> {
+    fn drop(&mut self) {
+        // Only touch the execution we joined; a newer one may have replaced it
+        let removed = self.flights.remove_if_mut(&self.key, |_, flight| {
+            if !Shared::ptr_eq(&flight.call, &self.call) {
+                return false;
+            }
+            flight.waiters -= 1;
+            flight.waiters == 0
+        });
+        if let Some((_, flight)) = removed {
+            flight.signal.cancel();
+        }
+    }
+}
+
+/// Create a single-flight middleware for query procedures
+///
+/// Concurrent queries with the same path and input (keyed with
+/// [`generate_cache_key`](super::generate_cache_key)) share one handler
+/// execution, and every caller receives its result or error. Mutations and
+/// subscriptions always run. This works with or without
+/// [`cache_middleware`](super::cache_middleware).
+///
+/// Once an auth middleware has run, only calls of the same
+/// [`Context::user_id`] are deduplicated. Callers are otherwise deduplicated
+/// regardless of their context, so place it after any other middleware that
+/// makes the result depend on the caller.
+///
+/// The shared execution does not see any caller's
+/// [`Context::cancellation`]: a caller that aborts simply stops waiting, and
+/// the handler is only cancelled once no caller is left.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use tauri_plugin_rpc::cache::{dedup_middleware, SingleFlight};
+///
+/// let router = Router::new()
+///     .middleware_fn(dedup_middleware(SingleFlight::new()))
+///     .query("user.get", get_user_handler);
+/// ```
+pub fn dedup_middleware<Ctx>(flights: SingleFlight) -> MiddlewareFn<Ctx>
+where
+    Ctx: Clone + Send + Sync + 'static,
+{
+    let middleware = move |ctx: Context<Ctx>, req: Request, next: Next<Ctx>| {
+        let flights = flights.clone();
+
+        async move {
+            if req.procedure_type != ProcedureType::Query {
+                return next(ctx, req).await;
+            }
+
+            let key = scoped_cache_key(&req.path, &req.input, ctx.user_id());
+            tracing::trace!(path = %req.path, "Single-flight lookup");
+            flights
+                .run(key, |signal| next(ctx.with_cancellation(signal), req))
+                .await
+        }
+    };
+    from_fn(middleware)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EmptyContext, Router, RpcError};
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    fn counting_router(calls: Arc<AtomicUsize>, flights: SingleFlight) -> Router<EmptyContext> {
+        let mutation_calls = calls.clone();
+        Router::new()
+            .context(EmptyContext)
+            .middleware_fn(dedup_middleware(flights))
+            .query("user.get", move |_ctx: Context<EmptyContext>, id: i64| {
+                let calls = calls.clone();
+                async move {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(30)).await;
+                    if id < 0 {
+                        return Err(RpcError::not_found("No such user"));
+                    }
+                    Ok(json!({ "id": id }))
+                }
+            })
+            .mutation("user.touch", move |_ctx: Context<EmptyContext>, id: i64| {
+                let calls = mutation_calls.clone();
+                async move {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(30)).await;
+                    Ok(id)
+                }
+            })
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_identical_queries_share_execution() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let flights = SingleFlight::new();
+        let router = counting_router(calls.clone(), flights.clone()).compile();
+
+        let (a, b, c) = tokio::join!(
+            router.call("user.get", json!(1)),
+            router.call("user.get", json!(1)),
+            router.call("user.get", json!(2)),
+        );
+
+        assert_eq!(a.unwrap(), json!({"id": 1}));
+        assert_eq!(b.unwrap(), json!({"id": 1}));
+        assert_eq!(c.unwrap(), json!({"id": 2}));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(flights.in_flight(), 0);
+
+        // A later call executes again
+        router.call("user.get", json!(1)).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_shared() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let router = counting_router(calls.clone(), SingleFlight::new()).compile();
+
+        let (a, b) = tokio::join!(
+            router.call("user.get", json!(-1)),
+            router.call("user.get", json!(-1)),
+        );
+
+        assert_eq!(a.unwrap_err().code, crate::RpcErrorCode::NotFound);
+        assert_eq!(b.unwrap_err().code, crate::RpcErrorCode::NotFound);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mutations_are_not_deduplicated() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let router = counting_router(calls.clone(), SingleFlight::new()).compile();
+
+        let (a, b) = tokio::join!(
+            router.call("user.touch", json!(1)),
+            router.call("user.touch", json!(1)),
+        );
+
+        assert!(a.is_ok() && b.is_ok());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_execution_outlives_callers_that_leave() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let flights = SingleFlight::new();
+        let router = counting_router(calls.clone(), flights.clone()).compile();
+
+        let (abandoned, kept) = tokio::join!(
+            tokio::time::timeout(Duration::from_millis(5), router.call("user.get", json!(1))),
+            async {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+                let joined = router.call("user.get", json!(1));
+                tokio::pin!(joined);
+                // Still registered after the first caller leaves
+                let early = tokio::time::timeout(Duration::from_millis(10), &mut joined).await;
+                assert!(early.is_err());
+                assert_eq!(flights.in_flight(), 1);
+                joined.await
+            },
+        );
+
+        assert!(abandoned.is_err());
+        assert_eq!(kept.unwrap(), json!({"id": 1}));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(flights.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_caller_cancellation_does_not_reach_shared_execution() {
+        use crate::middleware::RequestEnvelope;
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let handler_calls = calls.clone();
+        let router = Router::new()
+            .context(EmptyContext)
+            .middleware_fn(dedup_middleware(SingleFlight::new()))
+            .query("slow", move |ctx: Context<EmptyContext>, _input: ()| {
+                let calls = handler_calls.clone();
+                async move {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    tokio::select! {
+                        _ = ctx.cancellation().cancelled() => Err(RpcError::cancelled("slow")),
+                        _ = tokio::time::sleep(Duration::from_millis(30)) => Ok(json!("done")),
+                    }
+                }
+            })
+            .compile();
+
+        let signal = Arc::new(CancellationSignal::new());
+        let cancel = signal.clone();
+        let (first, second, _) = tokio::join!(
+            router.call_with_cancellation("slow", json!(null), RequestEnvelope::default(), signal),
+            router.call("slow", json!(null)),
+            async move {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                cancel.cancel();
+            },
+        );
+
+        assert_eq!(first.unwrap(), json!("done"));
+        assert_eq!(second.unwrap(), json!("done"));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_execution_is_forgotten() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let flights = SingleFlight::new();
+        let router = counting_router(calls.clone(), flights.clone()).compile();
+
+        let abandoned =
+            tokio::time::timeout(Duration::from_millis(5), router.call("user.get", json!(1))).await;
+        assert!(abandoned.is_err());
+        assert_eq!(flights.in_flight(), 0);
+
+        assert_eq!(
+            router.call("user.get", json!(1)).await.unwrap(),
+            json!({"id": 1})
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,138 @@
+//! Serializable dumps of cache contents
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use super::entry::CacheEntry;
+use super::invalidation::InvalidatedEntry;
+
+/// Contents of a cache, from [`Cache::export_snapshot`](super::Cache::export_snapshot)
+///
+/// Entries are listed most recently used first, with TTLs relative to the
+/// moment of export, so a snapshot can be loaded later with
+/// [`Cache::import_snapshot`](super::Cache::import_snapshot) to pre-warm a
+/// cache, kept as a test fixture, or shown in a debug panel.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheSnapshot {
+    /// The live entries
+    pub entries: Vec<CacheSnapshotEntry>,
+}
+
+/// One entry of a [`CacheSnapshot`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheSnapshotEntry {
+    /// Exact cache key; derived from `path`, `input` and `user` when missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// Procedure path
+    pub path: String,
+    /// Call input, as keyed by the cache
+    pub input: serde_json::Value,
+    /// User the entry belongs to, for procedures cached per user
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    /// The cached value
+    pub value: serde_json::Value,
+    /// Time left before the entry expires, in milliseconds
+    pub ttl_ms: u64,
+    /// Time the entry may be served stale after expiring, in milliseconds
+    #[serde(default)]
+    pub stale_window_ms: u64,
+    /// Invalidation tags
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Number of times the entry was served
+    #[serde(default)]
+    pub hits: u64,
+}
+
+impl CacheSnapshotEntry {
+    /// Capture an entry as of now
+    pub(crate) fn capture(key: &str, entry: &CacheEntry) -> Self {
+        // An expired entry still in its stale window keeps what is left of it
+        let elapsed = entry.created_at.elapsed();
+        let remaining_ttl = entry.ttl.saturating_sub(elapsed);
+        let remaining_stale = entry
+            .ttl
+            .saturating_add(entry.stale_window)
+            .saturating_sub(elapsed)
+            .saturating_sub(remaining_ttl);
+        let InvalidatedEntry { path, input, user } = InvalidatedEntry::from_key(key);
+        Self {
+            key: Some(key.to_string()),
+            path,
+            input,
+            user,
+            value: entry.value.clone(),
+            ttl_ms: remaining_ttl.as_millis() as u64,
+            stale_window_ms: remaining_stale.as_millis() as u64,
+            tags: entry.tags.clone(),
+            hits: entry.hits,
+        }
+    }
+
+    /// Rebuild the entry, starting its TTL now
+    pub(crate) fn restore(self) -> CacheEntry {
+        let mut entry = CacheEntry::new(self.value, Duration::from_millis(self.ttl_ms))
+            .with_tags(self.tags)
+            .with_stale_window(Duration::from_millis(self.stale_window_ms));
+        entry.hits = self.hits;
+        entry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_snapshot_entry_round_trip() {
+        let entry = CacheEntry::new(json!({"name": "Ann"}), Duration::from_secs(60))
+            .with_tags(vec!["user:1".into()])
+            .with_stale_window(Duration::from_secs(30));
+        let captured = CacheSnapshotEntry::capture(r#"user.get:{"id":1} "alice""#, &entry);
+
+        assert_eq!(captured.path, "user.get");
+        assert_eq!(captured.input, json!({"id": 1}));
+        assert_eq!(captured.user.as_deref(), Some("alice"));
+        assert!(captured.ttl_ms > 59_000 && captured.ttl_ms <= 60_000);
+        assert_eq!(captured.stale_window_ms, 30_000);
+
+        let json = serde_json::to_value(&captured).unwrap();
+        assert_eq!(json["staleWindowMs"], json!(30_000));
+        assert!(json.get("ttlMs").is_some());
+        let parsed: CacheSnapshotEntry = serde_json::from_value(json).unwrap();
+        let restored = parsed.restore();
+        assert_eq!(restored.value, entry.value);
+        assert_eq!(restored.tags, entry.tags);
+        assert_eq!(restored.stale_window, entry.stale_window);
+    }
+
+    #[test]
+    fn test_snapshot_entry_keeps_remaining_stale_window() {
+        let mut entry = CacheEntry::new(json!(1), Duration::from_secs(10))
+            .with_stale_window(Duration::from_secs(30));
+        entry.created_at -= Duration::from_secs(15);
+
+        let captured = CacheSnapshotEntry::capture("feed.list:null", &entry);
+        assert_eq!(captured.ttl_ms, 0);
+        assert!(captured.stale_window_ms > 24_000 && captured.stale_window_ms <= 25_000);
+    }
+
+    #[test]
+    fn test_minimal_fixture_entry() {
+        let parsed: CacheSnapshotEntry = serde_json::from_value(json!({
+            "path": "user.get",
+            "input": {"id": 1},
+            "value": {"name": "Ann"},
+            "ttlMs": 1000
+        }))
+        .unwrap();
+        assert_eq!(parsed.key, None);
+        assert_eq!(parsed.stale_window_ms, 0);
+        assert!(parsed.tags.is_empty());
+    }
+}
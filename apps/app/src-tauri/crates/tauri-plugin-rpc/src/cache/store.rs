@@ -1,17 +1,23 @@
 //! Cache store implementation
 
-use lru::LruCache;
+use dashmap::DashSet;
 use serde::{Deserialize, Serialize};
-use std::num::NonZeroUsize;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock as SyncRwLock};
 use std::time::Duration;
 use tokio::sync::RwLock;
 
+use super::backend::{CacheStore, LruStore};
 use super::config::CacheConfig;
-use super::entry::{CacheEntry, generate_cache_key};
+use super::entry::{CacheEntry, scoped_cache_key};
 use super::error::{CacheError, CacheResult};
+use super::invalidation::{
+    CacheInvalidation, InvalidatedEntry, InvalidationCause, InvalidationListener,
+};
 use super::metrics::CacheMetrics;
-use super::pattern::pattern_matches;
+use super::snapshot::{CacheSnapshot, CacheSnapshotEntry};
+use super::tags::TagIndex;
+use crate::pattern::PathPattern;
 
 #[cfg(test)]
 mod test_constants {
@@ -27,41 +33,308 @@ mod test_constants {
     pub const CLEANUP_WAIT: Duration = Duration::from_millis(100);
 }
 
-/// Thread-safe LRU cache with TTL support
+/// Outcome of [`Cache::lookup`]
+#[derive(Debug)]
+pub enum CacheLookup {
+    /// A value to serve as is
+    Hit(serde_json::Value),
+    /// A value to serve now and refresh in the background
+    ///
+    /// Returned for an entry within its stale-while-revalidate window, or for
+    /// a hot entry close to expiry. Only one lookup per key gets this
+    /// outcome until the claim is dropped; the others get [`Hit`](Self::Hit).
+    Revalidate(serde_json::Value, RefreshClaim),
+    /// Nothing usable is cached
+    Miss,
+}
+
+/// Exclusive right to refresh one cache key, released on drop
+///
+/// Store the new value with [`Cache::set_refreshed`], which discards it if
+/// the cache was invalidated since the claim was handed out.
+#[derive(Debug)]
+pub struct RefreshClaim {
+    refreshing: Arc<DashSet<String>>,
+    key: String,
+    generation: u64,
+}
+
+impl Drop for RefreshClaim {
+    fn drop(&mut self) {
+        self.refreshing.remove(&self.key);
+    }
+}
+
+/// Thread-safe cache with TTL support
+///
+/// Entries are kept in a [`CacheStore`], an in-memory [`LruStore`] by default.
+/// Entries stored with tags are also indexed by tag, so that
+/// [`invalidate_tags`](Self::invalidate_tags) finds them across paths. The
+/// cache tracks the serialized size of its values to enforce
+/// [`CacheConfig::with_max_bytes`].
 pub struct Cache {
     pub(crate) config: CacheConfig,
-    pub(crate) entries: Arc<RwLock<LruCache<String, CacheEntry>>>,
+    pub(crate) entries: Arc<RwLock<Box<dyn CacheStore>>>,
+    /// Locked only while holding the `entries` write lock
+    tags: Arc<Mutex<TagIndex>>,
+    /// Total size of the stored values, changed only while holding the `entries` write lock
+    bytes: Arc<AtomicUsize>,
+    /// Keys with a background refresh in flight
+    refreshing: Arc<DashSet<String>>,
+    /// Bumped by every invalidation, changed only while holding the `entries` write lock
+    generation: Arc<AtomicU64>,
+    listeners: Arc<SyncRwLock<Vec<InvalidationListener>>>,
     pub(crate) metrics: Arc<CacheMetrics>,
 }
 
 impl Cache {
-    /// Create a new cache with the given configuration
+    /// Create a new in-memory LRU cache with the given configuration
     pub fn new(config: CacheConfig) -> Self {
-        let max_entries = NonZeroUsize::new(config.max_entries.max(1)).unwrap();
+        let store = LruStore::new(config.max_entries);
+        Self::with_store(config, store)
+    }
+
+    /// Create a cache backed by a custom store
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let store = FileCacheStore::open(data_dir.join("rpc-cache.log"), config.max_entries)?;
+    /// let cache = Cache::with_store(config, store);
+    /// ```
+    pub fn with_store(config: CacheConfig, mut store: impl CacheStore + 'static) -> Self {
+        // A persistent store may come back over the byte budget
+        let mut bytes: usize = store.iter().map(|(_, entry)| entry.size).sum();
+        if let Some(max) = config.max_bytes {
+            while bytes > max {
+                let Some((_, evicted)) = store.pop_lru() else {
+                    break;
+                };
+                bytes -= evicted.size;
+            }
+        }
+
+        // ...and with tagged entries
+        let mut tags = TagIndex::default();
+        for (key, entry) in store.iter() {
+            tags.insert(key, entry);
+        }
+
         Self {
             config,
-            entries: Arc::new(RwLock::new(LruCache::new(max_entries))),
+            entries: Arc::new(RwLock::new(Box::new(store))),
+            tags: Arc::new(Mutex::new(tags)),
+            bytes: Arc::new(AtomicUsize::new(bytes)),
+            refreshing: Arc::new(DashSet::new()),
+            generation: Arc::default(),
+            listeners: Arc::default(),
             metrics: Arc::new(CacheMetrics::new()),
         }
     }
 
+    /// Register a callback run after every invalidation
+    ///
+    /// Listeners are shared by all clones of the cache and run outside the
+    /// cache lock. [`emit_cache_invalidations`](crate::emit_cache_invalidations)
+    /// uses this to forward invalidations to the frontend.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// cache.on_invalidate(|invalidation| {
+    ///     tracing::info!(?invalidation.cause, "cache invalidated");
+    /// });
+    /// ```
+    pub fn on_invalidate(&self, listener: impl Fn(&CacheInvalidation) + Send + Sync + 'static) {
+        self.listeners
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Arc::new(listener));
+    }
+
+    fn has_listeners(&self) -> bool {
+        !self
+            .listeners
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_empty()
+    }
+
+    /// Run the invalidation listeners; `entries` is only built when there are any
+    fn notify(&self, cause: InvalidationCause, entries: impl FnOnce() -> Vec<InvalidatedEntry>) {
+        if !self.has_listeners() {
+            return;
+        }
+        let invalidation = CacheInvalidation {
+            cause,
+            entries: entries(),
+        };
+        let listeners = self
+            .listeners
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        for listener in listeners {
+            listener(&invalidation);
+        }
+    }
+
+    /// Build the key of an entry, applying the procedure's key selector
+    ///
+    /// `user` only scopes the key for procedures configured with
+    /// [`CacheConfig::with_vary_by_user`].
+    fn key(&self, path: &str, input: &serde_json::Value, user: Option<&str>) -> String {
+        let input = self.config.key_input(path, input);
+        let user = user.filter(|_| self.config.varies_by_user(path));
+        scoped_cache_key(path, &input, user)
+    }
+
+    /// Keys of the entry for `path` and `input`
+    ///
+    /// For procedures cached per user, `None` yields the entries of every
+    /// user as well as the unscoped one.
+    fn entry_keys(
+        &self,
+        entries: &dyn CacheStore,
+        path: &str,
+        input: &serde_json::Value,
+        user: Option<&str>,
+    ) -> Vec<String> {
+        let key = self.key(path, input, user);
+        if user.is_some() || !self.config.varies_by_user(path) {
+            return vec![key];
+        }
+        // The user id follows the normalized input as a JSON string
+        let scoped = format!("{} \"", key);
+        entries
+            .iter()
+            .map(|(candidate, _)| candidate)
+            .filter(|candidate| **candidate == key || candidate.starts_with(&scoped))
+            .cloned()
+            .collect()
+    }
+
+    fn tag_index(&self) -> MutexGuard<'_, TagIndex> {
+        self.tags.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Store an entry, keeping the tag index, byte total and eviction metrics in step
+    ///
+    /// Past the byte budget, least recently used entries are evicted until
+    /// the total fits again. The new entry itself is never evicted: values
+    /// over the budget are turned away by [`store_entry`](Self::store_entry).
+    fn put_entry(&self, entries: &mut dyn CacheStore, key: String, mut entry: CacheEntry) {
+        let mut tags = self.tag_index();
+        if let Some(replaced) = entries.peek(&key) {
+            tags.remove(&key, replaced);
+            self.bytes.fetch_sub(replaced.size, Ordering::Relaxed);
+            // A refreshed key stays as hot as it was
+            entry.hits = replaced.hits;
+        }
+        tags.insert(&key, &entry);
+        self.bytes.fetch_add(entry.size, Ordering::Relaxed);
+
+        if let Some((evicted_key, evicted)) = entries.put(key, entry) {
+            tracing::debug!("LRU eviction occurred");
+            tags.remove(&evicted_key, &evicted);
+            self.bytes.fetch_sub(evicted.size, Ordering::Relaxed);
+            self.metrics.record_eviction();
+        }
+
+        let Some(max_bytes) = self.config.max_bytes else {
+            return;
+        };
+        while self.bytes.load(Ordering::Relaxed) > max_bytes && entries.len() > 1 {
+            let Some((evicted_key, evicted)) = entries.pop_lru() else {
+                break;
+            };
+            tracing::debug!(size = %evicted.size, "size eviction occurred");
+            tags.remove(&evicted_key, &evicted);
+            self.bytes.fetch_sub(evicted.size, Ordering::Relaxed);
+            self.metrics.record_eviction();
+        }
+    }
+
+    /// Remove an entry, keeping the tag index and byte total in step
+    fn remove_entry(&self, entries: &mut dyn CacheStore, key: &str) -> Option<CacheEntry> {
+        let removed = entries.remove(key)?;
+        self.tag_index().remove(key, &removed);
+        self.bytes.fetch_sub(removed.size, Ordering::Relaxed);
+        Some(removed)
+    }
+
+    /// Store a new value, unless it exceeds the size limit
+    ///
+    /// An oversized value also drops the entry cached under its key, so an
+    /// outdated value is not served in its place.
+    async fn store_entry(&self, key: String, entry: CacheEntry) {
+        // Acquire write lock only for the actual cache modification
+        let mut entries = self.entries.write().await;
+        self.store_locked(&mut **entries, key, entry);
+    }
+
+    /// [`store_entry`](Self::store_entry) with the `entries` write lock held
+    fn store_locked(&self, entries: &mut dyn CacheStore, key: String, entry: CacheEntry) {
+        let oversized = self
+            .config
+            .entry_size_limit()
+            .is_some_and(|limit| entry.size > limit);
+        if oversized {
+            tracing::debug!(size = %entry.size, "value too large to cache");
+            self.metrics.record_oversized();
+            self.remove_entry(entries, &key);
+        } else {
+            self.put_entry(entries, key, entry);
+        }
+    }
+
+    /// Build the entry stored for a value of `path`
+    fn new_entry(&self, path: &str, value: serde_json::Value, tags: Vec<String>) -> CacheEntry {
+        CacheEntry::new(value, self.config.get_ttl(path))
+            .with_tags(tags)
+            .with_stale_window(self.config.get_stale_window(path))
+    }
+
+    /// Mark the start of an invalidation, with the `entries` write lock held
+    ///
+    /// Background refreshes started before it no longer store their result.
+    fn bump_generation(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
     /// Get a cached value if it exists and hasn't expired (with error handling)
     ///
     /// Returns `Err(CacheError::CacheDisabled)` if caching is disabled.
     /// Returns `Ok(Some(value))` if the entry exists and is valid.
     /// Returns `Ok(None)` if the entry doesn't exist or has expired.
-    #[tracing::instrument(skip(self, input), fields(enabled = %self.config.enabled))]
+    ///
+    /// Procedures configured with [`CacheConfig::with_vary_by_user`] are
+    /// read through [`try_get_for_user`](Self::try_get_for_user).
     pub async fn try_get(
         &self,
         path: &str,
         input: &serde_json::Value,
+    ) -> CacheResult<Option<serde_json::Value>> {
+        self.try_get_for_user(path, input, None).await
+    }
+
+    /// Get a value cached for a user (with error handling)
+    ///
+    /// Behaves like [`try_get`](Self::try_get), reading the entry of `user`
+    /// for procedures configured with [`CacheConfig::with_vary_by_user`].
+    #[tracing::instrument(skip(self, input), fields(enabled = %self.config.enabled))]
+    pub async fn try_get_for_user(
+        &self,
+        path: &str,
+        input: &serde_json::Value,
+        user: Option<&str>,
     ) -> CacheResult<Option<serde_json::Value>> {
         if !self.config.enabled {
             tracing::trace!("cache disabled");
             return Err(CacheError::CacheDisabled);
         }
 
-        let key = generate_cache_key(path, input);
+        let key = self.key(path, input, user);
         tracing::trace!(key = %key, "generated cache key");
 
         let mut entries = self.entries.write().await;
@@ -69,11 +342,15 @@ impl Cache {
         if let Some(entry) = entries.get(&key) {
             if entry.is_expired() {
                 tracing::debug!("cache entry expired");
-                entries.pop(&key);
+                // Keep entries still within their stale window for `lookup`
+                if entry.is_discardable() {
+                    self.remove_entry(&mut **entries, &key);
+                }
                 self.metrics.record_miss();
                 return Ok(None);
             }
             tracing::debug!("cache hit");
+            entry.hits += 1;
             self.metrics.record_hit();
             return Ok(Some(entry.value.clone()));
         }
@@ -83,6 +360,75 @@ impl Cache {
         Ok(None)
     }
 
+    /// Look up a value, telling the caller when to refresh it in the background
+    ///
+    /// Unlike [`try_get`](Self::try_get), an expired entry still within its
+    /// stale-while-revalidate window is served, and with
+    /// [`RefreshAhead`](super::RefreshAhead) configured a hot entry close to
+    /// expiry asks for a refresh. Stale serves count as hits and are also
+    /// tracked in [`CacheStats::stale_hits`]. Returns [`CacheLookup::Miss`]
+    /// when caching is disabled.
+    pub async fn lookup(&self, path: &str, input: &serde_json::Value) -> CacheLookup {
+        self.lookup_for_user(path, input, None).await
+    }
+
+    /// Look up a value cached for a user
+    ///
+    /// Behaves like [`lookup`](Self::lookup), reading the entry of `user`
+    /// for procedures configured with [`CacheConfig::with_vary_by_user`].
+    #[tracing::instrument(skip(self, input), fields(enabled = %self.config.enabled))]
+    pub async fn lookup_for_user(
+        &self,
+        path: &str,
+        input: &serde_json::Value,
+        user: Option<&str>,
+    ) -> CacheLookup {
+        if !self.config.enabled {
+            return CacheLookup::Miss;
+        }
+
+        let key = self.key(path, input, user);
+        let mut entries = self.entries.write().await;
+
+        let Some(entry) = entries.get(&key) else {
+            tracing::debug!("cache miss");
+            self.metrics.record_miss();
+            return CacheLookup::Miss;
+        };
+
+        let needs_refresh = if !entry.is_expired() {
+            entry.hits += 1;
+            self.metrics.record_hit();
+            self.config.refresh_ahead.is_some_and(|ahead| {
+                entry.hits >= ahead.min_hits && entry.remaining_ttl() <= ahead.window
+            })
+        } else if !entry.is_discardable() {
+            tracing::debug!("serving stale cache entry");
+            entry.hits += 1;
+            self.metrics.record_hit();
+            self.metrics.record_stale_hit();
+            true
+        } else {
+            tracing::debug!("cache entry expired");
+            self.remove_entry(&mut **entries, &key);
+            self.metrics.record_miss();
+            return CacheLookup::Miss;
+        };
+
+        let value = entry.value.clone();
+        if needs_refresh && self.refreshing.insert(key.clone()) {
+            tracing::debug!("background refresh claimed");
+            self.metrics.record_refresh();
+            let claim = RefreshClaim {
+                refreshing: self.refreshing.clone(),
+                key,
+                generation: self.generation.load(Ordering::Relaxed),
+            };
+            return CacheLookup::Revalidate(value, claim);
+        }
+        CacheLookup::Hit(value)
+    }
+
     /// Get a cached value if it exists and hasn't expired
     ///
     /// This is a convenience wrapper around `try_get()` that returns `None`
@@ -92,16 +438,64 @@ impl Cache {
         self.try_get(path, input).await.ok().flatten()
     }
 
+    /// Get a value cached for a user
+    ///
+    /// This is a convenience wrapper around `try_get_for_user()` that returns `None` on any error.
+    pub async fn get_for_user(
+        &self,
+        path: &str,
+        input: &serde_json::Value,
+        user: Option<&str>,
+    ) -> Option<serde_json::Value> {
+        self.try_get_for_user(path, input, user)
+            .await
+            .ok()
+            .flatten()
+    }
+
     /// Set a cached value with the configured TTL for the procedure (with error handling)
     ///
     /// Returns `Err(CacheError::CacheDisabled)` if caching is disabled.
     /// Returns `Ok(())` if the value was successfully cached or if the path is excluded.
+    ///
+    /// Procedures configured with [`CacheConfig::with_vary_by_user`] are
+    /// stored through [`try_set_for_user`](Self::try_set_for_user).
     #[tracing::instrument(skip(self, input, value), fields(enabled = %self.config.enabled))]
     pub async fn try_set(
         &self,
         path: &str,
         input: &serde_json::Value,
         value: serde_json::Value,
+    ) -> CacheResult<()> {
+        self.try_set_with_tags(path, input, value, Vec::new()).await
+    }
+
+    /// Set a cached value tagged for [`invalidate_tags`](Self::invalidate_tags) (with error handling)
+    ///
+    /// Behaves like [`try_set`](Self::try_set) otherwise.
+    pub async fn try_set_with_tags(
+        &self,
+        path: &str,
+        input: &serde_json::Value,
+        value: serde_json::Value,
+        tags: Vec<String>,
+    ) -> CacheResult<()> {
+        self.try_set_for_user(path, input, value, tags, None).await
+    }
+
+    /// Set a tagged value cached for a user (with error handling)
+    ///
+    /// Behaves like [`try_set_with_tags`](Self::try_set_with_tags), storing
+    /// the entry of `user` for procedures configured with
+    /// [`CacheConfig::with_vary_by_user`].
+    #[tracing::instrument(skip(self, input, value), fields(enabled = %self.config.enabled))]
+    pub async fn try_set_for_user(
+        &self,
+        path: &str,
+        input: &serde_json::Value,
+        value: serde_json::Value,
+        tags: Vec<String>,
+        user: Option<&str>,
     ) -> CacheResult<()> {
         if !self.config.enabled {
             tracing::trace!("cache disabled");
@@ -115,26 +509,43 @@ impl Cache {
 
         // Lock contention optimization: Perform all computation before acquiring the write lock.
         // This minimizes the critical section and improves concurrent throughput.
-        let key = generate_cache_key(path, input);
-        let ttl = self.config.get_ttl(path);
-        let entry = CacheEntry::new(value, ttl);
+        let key = self.key(path, input, user);
+        let entry = self.new_entry(path, value, tags);
+        let ttl = entry.ttl;
+        self.store_entry(key, entry).await;
 
-        // Acquire write lock only for the actual cache modification
-        let mut entries = self.entries.write().await;
-
-        // Check if we're at capacity and the key doesn't exist (will cause eviction)
-        // Note: This check must be inside the lock to ensure atomicity
-        let will_evict = entries.len() >= entries.cap().get() && !entries.contains(&key);
-
-        entries.put(key, entry);
+        tracing::trace!(ttl_ms = %ttl.as_millis(), "cache entry stored");
+        Ok(())
+    }
 
-        if will_evict {
-            tracing::debug!("LRU eviction occurred");
-            self.metrics.record_eviction();
+    /// Store the result of a background refresh claimed by a lookup
+    ///
+    /// Behaves like [`set_for_user`](Self::set_for_user), except that the
+    /// value is discarded when any invalidation ran after `claim` was handed
+    /// out: the refresh may have read data the invalidation was meant to
+    /// drop. Returns whether the value was stored.
+    pub async fn set_refreshed(
+        &self,
+        claim: &RefreshClaim,
+        path: &str,
+        input: &serde_json::Value,
+        value: serde_json::Value,
+        tags: Vec<String>,
+        user: Option<&str>,
+    ) -> bool {
+        if !self.config.enabled || !self.config.should_cache(path) {
+            return false;
         }
 
-        tracing::trace!(ttl_ms = %ttl.as_millis(), "cache entry stored");
-        Ok(())
+        let key = self.key(path, input, user);
+        let entry = self.new_entry(path, value, tags);
+        let mut entries = self.entries.write().await;
+        if self.generation.load(Ordering::Relaxed) != claim.generation {
+            tracing::debug!("discarding refresh started before an invalidation");
+            return false;
+        }
+        self.store_locked(&mut **entries, key, entry);
+        true
     }
 
     /// Set a cached value with the configured TTL for the procedure
@@ -146,6 +557,33 @@ impl Cache {
         let _ = self.try_set(path, input, value).await;
     }
 
+    /// Set a cached value tagged for [`invalidate_tags`](Self::invalidate_tags)
+    ///
+    /// This is a convenience wrapper around `try_set_with_tags()` that silently ignores errors.
+    pub async fn set_with_tags(
+        &self,
+        path: &str,
+        input: &serde_json::Value,
+        value: serde_json::Value,
+        tags: Vec<String>,
+    ) {
+        let _ = self.try_set_with_tags(path, input, value, tags).await;
+    }
+
+    /// Set a tagged value cached for a user
+    ///
+    /// This is a convenience wrapper around `try_set_for_user()` that silently ignores errors.
+    pub async fn set_for_user(
+        &self,
+        path: &str,
+        input: &serde_json::Value,
+        value: serde_json::Value,
+        tags: Vec<String>,
+        user: Option<&str>,
+    ) {
+        let _ = self.try_set_for_user(path, input, value, tags, user).await;
+    }
+
     /// Set a cached value with a custom TTL (with error handling)
     ///
     /// Returns `Err(CacheError::CacheDisabled)` if caching is disabled.
@@ -162,21 +600,10 @@ impl Cache {
         }
 
         // Lock contention optimization: Perform all computation before acquiring the write lock
-        let key = generate_cache_key(path, input);
-        let entry = CacheEntry::new(value, ttl);
-
-        // Acquire write lock only for the actual cache modification
-        let mut entries = self.entries.write().await;
-
-        // Check if we're at capacity and the key doesn't exist (will cause eviction)
-        // Note: This check must be inside the lock to ensure atomicity
-        let will_evict = entries.len() >= entries.cap().get() && !entries.contains(&key);
-
-        entries.put(key, entry);
-
-        if will_evict {
-            self.metrics.record_eviction();
-        }
+        let key = self.key(path, input, None);
+        let entry =
+            CacheEntry::new(value, ttl).with_stale_window(self.config.get_stale_window(path));
+        self.store_entry(key, entry).await;
 
         Ok(())
     }
@@ -196,22 +623,70 @@ impl Cache {
     }
 
     /// Invalidate a specific cache entry
+    ///
+    /// For procedures configured with [`CacheConfig::with_vary_by_user`],
+    /// the entries of every user are invalidated.
     #[tracing::instrument(skip(self, input))]
     pub async fn invalidate(&self, path: &str, input: &serde_json::Value) {
-        let key = generate_cache_key(path, input);
+        self.invalidate_entry(path, input, None).await;
+    }
+
+    /// Invalidate the entry cached for a user
+    ///
+    /// Only the entry of `user` is removed for procedures configured with
+    /// [`CacheConfig::with_vary_by_user`]; for other procedures this behaves
+    /// like [`invalidate`](Self::invalidate).
+    #[tracing::instrument(skip(self, input))]
+    pub async fn invalidate_for_user(&self, path: &str, input: &serde_json::Value, user: &str) {
+        self.invalidate_entry(path, input, Some(user)).await;
+    }
+
+    async fn invalidate_entry(&self, path: &str, input: &serde_json::Value, user: Option<&str>) {
         let mut entries = self.entries.write().await;
-        if entries.pop(&key).is_some() {
-            tracing::debug!("cache entry invalidated");
-            self.metrics.record_invalidation();
+        self.bump_generation();
+        let keys = self.entry_keys(&**entries, path, input, user);
+        let mut invalidated = 0u64;
+        for key in &keys {
+            if self.remove_entry(&mut **entries, key).is_some() {
+                invalidated += 1;
+            }
+        }
+        drop(entries);
+
+        if invalidated > 0 {
+            tracing::debug!(invalidated = %invalidated, "cache entry invalidated");
+            self.metrics.record_invalidations(invalidated);
         } else {
             tracing::trace!("cache entry not found for invalidation");
         }
+
+        self.notify(InvalidationCause::Entries, || {
+            vec![InvalidatedEntry {
+                path: path.to_string(),
+                input: input.clone(),
+                user: user.map(str::to_string),
+            }]
+        });
     }
 
     /// Invalidate all entries matching a pattern
-    #[tracing::instrument(skip(self))]
+    ///
+    /// Accepts the shared [pattern syntax](crate::pattern), e.g. `user.*`,
+    /// `user.*.get` or `{user,post}.**`.
+    ///
+    /// Compiles the pattern on every call; use
+    /// [`invalidate_matching`](Self::invalidate_matching) for patterns that
+    /// are applied repeatedly.
     pub async fn invalidate_pattern(&self, pattern: &str) {
+        self.invalidate_matching(&PathPattern::compile(pattern))
+            .await;
+    }
+
+    /// Invalidate all entries matching a compiled pattern
+    #[tracing::instrument(skip(self), fields(pattern = %pattern))]
+    pub async fn invalidate_matching(&self, pattern: &PathPattern) {
         let mut entries = self.entries.write().await;
+        self.bump_generation();
 
         // Collect keys to remove (can't modify while iterating)
         let keys_to_remove: Vec<String> = entries
@@ -219,7 +694,7 @@ impl Cache {
             .filter_map(|(key, _)| {
                 // Extract path from key (format: "path:input")
                 let path = key.split(':').next()?;
-                if pattern_matches(pattern, path) {
+                if pattern.matches(path) {
                     Some(key.clone())
                 } else {
                     None
@@ -228,9 +703,10 @@ impl Cache {
             .collect();
 
         let count = keys_to_remove.len() as u64;
-        for key in keys_to_remove {
-            entries.pop(&key);
+        for key in &keys_to_remove {
+            self.remove_entry(&mut **entries, key);
         }
+        drop(entries);
 
         if count > 0 {
             tracing::debug!(count = %count, "cache entries invalidated");
@@ -238,14 +714,30 @@ impl Cache {
         } else {
             tracing::trace!("no matching entries found for pattern");
         }
+
+        self.notify(
+            InvalidationCause::Pattern {
+                pattern: pattern.as_str().to_string(),
+            },
+            || {
+                keys_to_remove
+                    .iter()
+                    .map(|key| InvalidatedEntry::from_key(key))
+                    .collect()
+            },
+        );
     }
 
     /// Invalidate all cache entries
     #[tracing::instrument(skip(self))]
     pub async fn invalidate_all(&self) {
         let mut entries = self.entries.write().await;
+        self.bump_generation();
         let count = entries.len() as u64;
         entries.clear();
+        self.tag_index().clear();
+        self.bytes.store(0, Ordering::Relaxed);
+        drop(entries);
 
         if count > 0 {
             tracing::debug!(count = %count, "all cache entries invalidated");
@@ -253,16 +745,21 @@ impl Cache {
         } else {
             tracing::trace!("cache was already empty");
         }
+
+        self.notify(InvalidationCause::All, Vec::new);
     }
 
     /// Invalidate multiple specific cache entries efficiently
     ///
     /// This method is more efficient than calling `invalidate()` multiple times
     /// because it acquires the write lock only once for all invalidations.
+    /// Like `invalidate()`, it removes the entries of every user for
+    /// procedures configured with [`CacheConfig::with_vary_by_user`].
     ///
     /// # Performance
     ///
-    /// - Time complexity: O(n) where n is the number of entries to invalidate
+    /// - Time complexity: O(n) where n is the number of entries to invalidate,
+    ///   plus a scan of the cache for each entry of a per-user procedure
     /// - Lock acquisitions: 1 (regardless of batch size)
     ///
     /// # Example
@@ -281,21 +778,21 @@ impl Cache {
             return;
         }
 
-        // Generate all keys before acquiring the lock
+        // Single lock acquisition for all removals
+        let mut cache = self.entries.write().await;
+        self.bump_generation();
         let keys: Vec<String> = entries
             .iter()
-            .map(|(path, input)| generate_cache_key(path, input))
+            .flat_map(|(path, input)| self.entry_keys(&**cache, path, input, None))
             .collect();
-
-        // Single lock acquisition for all removals
-        let mut cache = self.entries.write().await;
         let mut invalidated = 0u64;
 
         for key in keys {
-            if cache.pop(&key).is_some() {
+            if self.remove_entry(&mut **cache, &key).is_some() {
                 invalidated += 1;
             }
         }
+        drop(cache);
 
         if invalidated > 0 {
             tracing::debug!(invalidated = %invalidated, total = %entries.len(), "batch invalidation complete");
@@ -303,30 +800,173 @@ impl Cache {
         } else {
             tracing::trace!("no entries found in batch");
         }
+
+        self.notify(InvalidationCause::Entries, || {
+            entries
+                .iter()
+                .map(|(path, input)| InvalidatedEntry {
+                    path: path.clone(),
+                    input: input.clone(),
+                    user: None,
+                })
+                .collect()
+        });
+    }
+
+    /// Invalidate every entry carrying any of the given tags
+    ///
+    /// Tags are attached when a value is stored, either through
+    /// [`set_with_tags`](Self::set_with_tags) or by a handler calling
+    /// [`Context::add_cache_tags`](crate::Context::add_cache_tags) under
+    /// [`cache_middleware`](super::cache_middleware). Only the tagged entries
+    /// are removed, whatever their path.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// cache.set_with_tags("user.get", &json!({"id": 42}), user, vec!["user:42".into()]).await;
+    /// cache.set_with_tags("team.members", &json!({"id": 7}), members, vec!["user:42".into()]).await;
+    ///
+    /// // Removes both entries
+    /// cache.invalidate_tags(&["user:42"]).await;
+    /// ```
+    #[tracing::instrument(skip(self, tags), fields(tags = tags.len()))]
+    pub async fn invalidate_tags<S: AsRef<str>>(&self, tags: &[S]) {
+        if tags.is_empty() {
+            return;
+        }
+
+        let mut entries = self.entries.write().await;
+        self.bump_generation();
+        let keys = self.tag_index().keys_for(tags);
+
+        let removed: Vec<String> = keys
+            .into_iter()
+            .filter(|key| self.remove_entry(&mut **entries, key).is_some())
+            .collect();
+        drop(entries);
+
+        let invalidated = removed.len() as u64;
+        if invalidated > 0 {
+            tracing::debug!(invalidated = %invalidated, "tagged cache entries invalidated");
+            self.metrics.record_invalidations(invalidated);
+        } else {
+            tracing::trace!("no entries carry the given tags");
+        }
+
+        self.notify(
+            InvalidationCause::Tags {
+                tags: tags.iter().map(|tag| tag.as_ref().to_string()).collect(),
+            },
+            || {
+                removed
+                    .iter()
+                    .map(|key| InvalidatedEntry::from_key(key))
+                    .collect()
+            },
+        );
     }
 
     /// Get cache statistics
     pub async fn stats(&self) -> CacheStats {
         let entries = self.entries.read().await;
         let total = entries.len();
-        let expired = entries.iter().filter(|(_, e)| e.is_expired()).count();
+        let expired = entries.iter().filter(|(_, e)| e.is_discardable()).count();
 
         CacheStats {
             total_entries: total,
             expired_entries: expired,
             max_entries: self.config.max_entries,
+            total_bytes: self.bytes.load(Ordering::Relaxed),
+            max_bytes: self.config.max_bytes,
+            oversized_rejections: self.metrics.get_oversized(),
             hits: self.metrics.get_hits(),
             misses: self.metrics.get_misses(),
             hit_ratio: self.metrics.calculate_hit_ratio(),
             evictions: self.metrics.get_evictions(),
             invalidations: self.metrics.get_invalidations(),
+            stale_hits: self.metrics.get_stale_hits(),
+            background_refreshes: self.metrics.get_refreshes(),
+        }
+    }
+
+    /// Dump the live entries with their remaining TTLs
+    ///
+    /// Entries past their stale window are left out. Reading the entries
+    /// does not count as a hit or change their recency.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let snapshot = cache.export_snapshot().await;
+    /// std::fs::write("cache-fixture.json", serde_json::to_vec(&snapshot)?)?;
+    /// ```
+    pub async fn export_snapshot(&self) -> CacheSnapshot {
+        let entries = self.entries.read().await;
+        CacheSnapshot {
+            entries: entries
+                .iter()
+                .filter(|(_, entry)| !entry.is_discardable())
+                .map(|(key, entry)| CacheSnapshotEntry::capture(key, entry))
+                .collect(),
+        }
+    }
+
+    /// Load the entries of a snapshot and return how many were stored
+    ///
+    /// TTLs restart at import, and the snapshot's recency order is kept.
+    /// Entries replace cached values under the same key; entries without a
+    /// `key` are keyed from their path, input and user as in
+    /// [`set_for_user`](Self::set_for_user). Entries for excluded paths,
+    /// already past their stale window or over the size limit are skipped.
+    /// Listeners are not notified.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let snapshot: CacheSnapshot = serde_json::from_slice(&std::fs::read("cache-fixture.json")?)?;
+    /// cache.import_snapshot(snapshot).await;
+    /// ```
+    pub async fn import_snapshot(&self, snapshot: CacheSnapshot) -> usize {
+        if !self.config.enabled {
+            tracing::trace!("cache disabled");
+            return 0;
+        }
+
+        let size_limit = self.config.entry_size_limit();
+        let mut imported = 0;
+        let mut entries = self.entries.write().await;
+        // Least recently used first, so the most recent ends up on top
+        for snapshot_entry in snapshot.entries.into_iter().rev() {
+            if !self.config.should_cache(&snapshot_entry.path)
+                || snapshot_entry
+                    .ttl_ms
+                    .saturating_add(snapshot_entry.stale_window_ms)
+                    == 0
+            {
+                continue;
+            }
+            let key = snapshot_entry.key.clone().unwrap_or_else(|| {
+                let user = snapshot_entry.user.as_deref();
+                self.key(&snapshot_entry.path, &snapshot_entry.input, user)
+            });
+            let entry = snapshot_entry.restore();
+            if size_limit.is_some_and(|limit| entry.size > limit) {
+                self.metrics.record_oversized();
+                continue;
+            }
+            self.put_entry(&mut **entries, key, entry);
+            imported += 1;
         }
+
+        tracing::debug!(count = %imported, "cache snapshot imported");
+        imported
     }
 
     /// Remove expired entries and return the count of removed entries
     ///
-    /// This method scans all cache entries and removes those that have expired.
-    /// It returns the number of entries that were removed.
+    /// This method scans all cache entries and removes those that have expired
+    /// and are past their stale-while-revalidate window. It returns the number of entries that were removed.
     ///
     /// # Performance
     ///
@@ -339,7 +979,7 @@ impl Cache {
         let expired_keys: Vec<String> = entries
             .iter()
             .filter_map(|(key, entry)| {
-                if entry.is_expired() {
+                if entry.is_discardable() {
                     Some(key.clone())
                 } else {
                     None
@@ -349,7 +989,7 @@ impl Cache {
 
         let count = expired_keys.len();
         for key in expired_keys {
-            entries.pop(&key);
+            self.remove_entry(&mut **entries, &key);
         }
 
         if count > 0 {
@@ -413,12 +1053,25 @@ impl Cache {
     }
 
     /// Check if a value is cached (without retrieving it)
+    ///
+    /// Procedures configured with [`CacheConfig::with_vary_by_user`] are
+    /// checked through [`contains_for_user`](Self::contains_for_user).
     pub async fn contains(&self, path: &str, input: &serde_json::Value) -> bool {
+        self.contains_for_user(path, input, None).await
+    }
+
+    /// Check if a value is cached for a user (without retrieving it)
+    pub async fn contains_for_user(
+        &self,
+        path: &str,
+        input: &serde_json::Value,
+        user: Option<&str>,
+    ) -> bool {
         if !self.config.enabled {
             return false;
         }
 
-        let key = generate_cache_key(path, input);
+        let key = self.key(path, input, user);
         let entries = self.entries.read().await;
 
         if let Some(entry) = entries.peek(&key) {
@@ -462,6 +1115,11 @@ impl Clone for Cache {
         Self {
             config: self.config.clone(),
             entries: self.entries.clone(),
+            tags: self.tags.clone(),
+            bytes: self.bytes.clone(),
+            refreshing: self.refreshing.clone(),
+            generation: self.generation.clone(),
+            listeners: self.listeners.clone(),
             metrics: self.metrics.clone(),
         }
     }
@@ -472,20 +1130,30 @@ impl Clone for Cache {
 pub struct CacheStats {
     /// Total number of entries in the cache
     pub total_entries: usize,
-    /// Number of expired entries (not yet cleaned up)
+    /// Number of entries past their TTL and stale window (not yet cleaned up)
     pub expired_entries: usize,
     /// Maximum number of entries allowed
     pub max_entries: usize,
+    /// Total serialized size of the cached values, in bytes
+    pub total_bytes: usize,
+    /// Byte budget, if any
+    pub max_bytes: Option<usize>,
+    /// Values not stored because they exceeded the size limit
+    pub oversized_rejections: u64,
     /// Number of cache hits (since last reset)
     pub hits: u64,
     /// Number of cache misses (since last reset)
     pub misses: u64,
     /// Hit ratio (0.0 to 1.0)
     pub hit_ratio: f64,
-    /// Total evictions due to LRU
+    /// Total evictions, to respect `max_entries` or `max_bytes`
     pub evictions: u64,
     /// Total invalidations
     pub invalidations: u64,
+    /// Hits served from an expired entry within its stale window
+    pub stale_hits: u64,
+    /// Background refreshes started, for stale or hot entries
+    pub background_refreshes: u64,
 }
 
 #[cfg(test)]
@@ -682,6 +1350,466 @@ mod tests {
         assert_eq!(stats.max_entries, 1000);
     }
 
+    #[tokio::test]
+    async fn test_cache_with_file_store_survives_restart() {
+        use crate::cache::FileCacheStore;
+
+        let path = std::env::temp_dir()
+            .join(format!("rpc-cache-store-{}", std::process::id()))
+            .join("cache.log");
+        let _ = std::fs::remove_file(&path);
+        let input = json!({"id": 1});
+
+        {
+            let store = FileCacheStore::open(&path, 10).unwrap();
+            let cache = Cache::with_store(CacheConfig::new(), store);
+            cache
+                .set("user.get", &input, json!({"name": "Alice"}))
+                .await;
+        }
+
+        let store = FileCacheStore::open(&path, 10).unwrap();
+        let cache = Cache::with_store(CacheConfig::new(), store);
+        assert_eq!(
+            cache.get("user.get", &input).await,
+            Some(json!({"name": "Alice"}))
+        );
+
+        cache.invalidate_all().await;
+        // Dropping the store waits for its queued writes
+        drop(cache);
+        let store = FileCacheStore::open(&path, 10).unwrap();
+        assert!(store.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_tags_across_paths() {
+        let cache = Cache::new(CacheConfig::new());
+        let tags = |tags: &[&str]| tags.iter().map(|t| t.to_string()).collect();
+
+        cache
+            .set_with_tags("user.get", &json!({"id": 42}), json!(1), tags(&["user:42"]))
+            .await;
+        cache
+            .set_with_tags(
+                "team.members",
+                &json!({"id": 7}),
+                json!(2),
+                tags(&["user:42", "team:7"]),
+            )
+            .await;
+        cache
+            .set_with_tags("user.get", &json!({"id": 43}), json!(3), tags(&["user:43"]))
+            .await;
+        cache.set("post.list", &json!({}), json!(4)).await;
+
+        cache.invalidate_tags(&["user:42"]).await;
+
+        assert!(cache.get("user.get", &json!({"id": 42})).await.is_none());
+        assert!(cache.get("team.members", &json!({"id": 7})).await.is_none());
+        assert!(cache.get("user.get", &json!({"id": 43})).await.is_some());
+        assert!(cache.get("post.list", &json!({})).await.is_some());
+        assert_eq!(cache.stats().await.invalidations, 2);
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_tags_skips_retagged_entries() {
+        let cache = Cache::new(CacheConfig::new().with_max_entries(2));
+        let input = json!({"id": 1});
+
+        // Replacing an entry drops its old tags
+        cache
+            .set_with_tags("user.get", &input, json!(1), vec!["user:1".into()])
+            .await;
+        cache.set("user.get", &input, json!(2)).await;
+        cache.invalidate_tags(&["user:1"]).await;
+        assert_eq!(cache.get("user.get", &input).await, Some(json!(2)));
+
+        // Evicted entries leave the index
+        cache.invalidate_all().await;
+        cache
+            .set_with_tags("a", &json!({}), json!(1), vec!["t".into()])
+            .await;
+        cache
+            .set_with_tags("b", &json!({}), json!(2), vec!["t".into()])
+            .await;
+        cache.set("c", &json!({}), json!(3)).await;
+        assert_eq!(cache.tag_index().keys_for(&["t"]).len(), 1);
+
+        cache.invalidate_all().await;
+        assert!(cache.tag_index().keys_for(&["t"]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_lookup_serves_stale_within_window() {
+        use test_constants::*;
+
+        let config = CacheConfig::new()
+            .with_default_ttl(SHORT_TTL)
+            .with_stale_while_revalidate("user.get", LONG_TTL);
+        let cache = Cache::new(config);
+        let input = json!({"id": 1});
+        cache.set("user.get", &input, json!("old")).await;
+        cache.set("post.get", &input, json!("old")).await;
+
+        sleep(CLEANUP_WAIT).await;
+
+        // Strict reads ignore stale entries but keep them
+        assert!(cache.get("user.get", &input).await.is_none());
+        assert_eq!(cache.cleanup_expired().await, 1);
+
+        let claim = match cache.lookup("user.get", &input).await {
+            CacheLookup::Revalidate(value, claim) => {
+                assert_eq!(value, json!("old"));
+                claim
+            }
+            other => panic!("expected revalidate, got {:?}", other),
+        };
+        // A refresh is already in flight
+        assert!(matches!(
+            cache.lookup("user.get", &input).await,
+            CacheLookup::Hit(_)
+        ));
+        drop(claim);
+
+        assert!(matches!(
+            cache.lookup("post.get", &input).await,
+            CacheLookup::Miss
+        ));
+
+        let stats = cache.stats().await;
+        assert_eq!(stats.stale_hits, 2);
+        assert_eq!(stats.background_refreshes, 1);
+
+        cache.set("user.get", &input, json!("new")).await;
+        assert!(matches!(
+            cache.lookup("user.get", &input).await,
+            CacheLookup::Hit(value) if value == json!("new")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_lookup_refreshes_hot_entries_ahead() {
+        let config = CacheConfig::new()
+            .with_default_ttl(Duration::from_secs(1))
+            .with_refresh_ahead(Duration::from_secs(5), 2);
+        let cache = Cache::new(config);
+        let input = json!({"id": 1});
+        cache.set("user.get", &input, json!(1)).await;
+
+        // Not hot yet
+        assert!(matches!(
+            cache.lookup("user.get", &input).await,
+            CacheLookup::Hit(_)
+        ));
+        assert!(matches!(
+            cache.lookup("user.get", &input).await,
+            CacheLookup::Revalidate(..)
+        ));
+
+        // Refreshing keeps the key hot
+        cache.set("user.get", &input, json!(2)).await;
+        assert!(matches!(
+            cache.lookup("user.get", &input).await,
+            CacheLookup::Revalidate(value, _) if value == json!(2)
+        ));
+        assert_eq!(cache.stats().await.stale_hits, 0);
+    }
+
+    #[tokio::test]
+    async fn test_byte_budget_evicts_least_recently_used() {
+        // Each `"xxxxxxxx"` value is 10 bytes serialized
+        let value = json!("x".repeat(8));
+        let cache = Cache::new(CacheConfig::new().with_max_bytes(25));
+
+        cache.set("a", &json!(null), value.clone()).await;
+        cache.set("b", &json!(null), value.clone()).await;
+        assert_eq!(cache.stats().await.total_bytes, 20);
+
+        // Touch `a` so `b` is the least recently used
+        cache.get("a", &json!(null)).await;
+        cache.set("c", &json!(null), value.clone()).await;
+
+        assert!(cache.contains("a", &json!(null)).await);
+        assert!(!cache.contains("b", &json!(null)).await);
+        assert!(cache.contains("c", &json!(null)).await);
+        let stats = cache.stats().await;
+        assert_eq!(stats.total_bytes, 20);
+        assert_eq!(stats.max_bytes, Some(25));
+        assert_eq!(stats.evictions, 1);
+
+        // Replacing a value accounts for the old size
+        cache.set("a", &json!(null), json!(1)).await;
+        assert_eq!(cache.stats().await.total_bytes, 11);
+
+        cache.invalidate("c", &json!(null)).await;
+        assert_eq!(cache.stats().await.total_bytes, 1);
+        cache.invalidate_all().await;
+        assert_eq!(cache.stats().await.total_bytes, 0);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_values_are_not_cached() {
+        let cache = Cache::new(CacheConfig::new().with_max_entry_bytes(5));
+
+        cache.set("small", &json!(1), json!(true)).await;
+        cache.set("large", &json!(1), json!("too large")).await;
+        assert!(cache.contains("small", &json!(1)).await);
+        assert!(!cache.contains("large", &json!(1)).await);
+
+        // A new oversized value drops the outdated one
+        cache.set("small", &json!(1), json!([1, 2, 3, 4])).await;
+        assert!(!cache.contains("small", &json!(1)).await);
+
+        let stats = cache.stats().await;
+        assert_eq!(stats.oversized_rejections, 2);
+        assert_eq!(stats.total_bytes, 0);
+        assert_eq!(stats.evictions, 0);
+    }
+
+    #[tokio::test]
+    async fn test_key_selectors_share_entries() {
+        let cache = Cache::new(CacheConfig::new().with_key_exclude("user.list", ["/nonce"]));
+
+        cache
+            .set(
+                "user.list",
+                &json!({"page": 1, "nonce": "a"}),
+                json!(["ann"]),
+            )
+            .await;
+        assert_eq!(
+            cache
+                .get("user.list", &json!({"page": 1, "nonce": "b"}))
+                .await,
+            Some(json!(["ann"]))
+        );
+        assert!(
+            cache
+                .get("user.list", &json!({"page": 2, "nonce": "a"}))
+                .await
+                .is_none()
+        );
+
+        cache
+            .invalidate("user.list", &json!({"page": 1, "nonce": "c"}))
+            .await;
+        assert!(!cache.contains("user.list", &json!({"page": 1})).await);
+    }
+
+    #[tokio::test]
+    async fn test_vary_by_user_isolates_entries() {
+        let cache = Cache::new(CacheConfig::new().with_vary_by_user("me.*"));
+        let input = json!(null);
+
+        cache
+            .set_for_user("me.get", &input, json!("ann"), Vec::new(), Some("ann"))
+            .await;
+        assert!(matches!(
+            cache.lookup_for_user("me.get", &input, Some("ann")).await,
+            CacheLookup::Hit(value) if value == json!("ann")
+        ));
+        assert!(matches!(
+            cache.lookup_for_user("me.get", &input, Some("bob")).await,
+            CacheLookup::Miss
+        ));
+        assert!(matches!(
+            cache.lookup("me.get", &input).await,
+            CacheLookup::Miss
+        ));
+
+        // Other procedures ignore the user
+        cache
+            .set_for_user("post.get", &input, json!(1), Vec::new(), Some("ann"))
+            .await;
+        assert!(matches!(
+            cache.lookup_for_user("post.get", &input, Some("bob")).await,
+            CacheLookup::Hit(_)
+        ));
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        cache.on_invalidate(move |invalidation| {
+            sink.lock().unwrap().extend(invalidation.entries.clone())
+        });
+        cache.invalidate_pattern("me.*").await;
+        assert_eq!(seen.lock().unwrap()[0].user.as_deref(), Some("ann"));
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_reaches_per_user_entries() {
+        let cache = Cache::new(CacheConfig::new().with_vary_by_user("me.*"));
+        let input = json!({"id": 1});
+        for user in ["ann", "bob"] {
+            cache
+                .set_for_user("me.get", &input, json!(user), Vec::new(), Some(user))
+                .await;
+        }
+        cache
+            .set_for_user(
+                "me.get",
+                &json!({"id": 2}),
+                json!(2),
+                Vec::new(),
+                Some("ann"),
+            )
+            .await;
+
+        cache.invalidate_for_user("me.get", &input, "ann").await;
+        assert!(!cache.contains_for_user("me.get", &input, Some("ann")).await);
+        assert_eq!(
+            cache.get_for_user("me.get", &input, Some("bob")).await,
+            Some(json!("bob"))
+        );
+
+        cache.invalidate("me.get", &input).await;
+        assert!(!cache.contains_for_user("me.get", &input, Some("bob")).await);
+        assert!(
+            cache
+                .contains_for_user("me.get", &json!({"id": 2}), Some("ann"))
+                .await
+        );
+
+        cache
+            .invalidate_batch(&[("me.get".to_string(), json!({"id": 2}))])
+            .await;
+        assert_eq!(cache.stats().await.total_entries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_round_trip() {
+        let config = CacheConfig::new()
+            .with_max_entries(3)
+            .with_key_include("search.run", ["/query"])
+            .with_vary_by_user("me.*");
+        let source = Cache::new(config.clone());
+        source
+            .set_with_tags(
+                "user.get",
+                &json!({"id": 1}),
+                json!("Ann"),
+                vec!["user:1".into()],
+            )
+            .await;
+        source
+            .set("search.run", &json!({"query": "a", "nonce": 1}), json!([1]))
+            .await;
+        source
+            .set_for_user(
+                "me.profile",
+                &json!(null),
+                json!("alice"),
+                Vec::new(),
+                Some("alice"),
+            )
+            .await;
+        source.get("user.get", &json!({"id": 1})).await;
+
+        let snapshot = source.export_snapshot().await;
+        let paths: Vec<_> = snapshot.entries.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, ["user.get", "me.profile", "search.run"]);
+        assert_eq!(snapshot.entries[0].hits, 1);
+        assert_eq!(snapshot.entries[1].user.as_deref(), Some("alice"));
+
+        // Survives serialization and keeps recency in the new cache
+        let snapshot = serde_json::from_str(&serde_json::to_string(&snapshot).unwrap()).unwrap();
+        let target = Cache::new(config);
+        assert_eq!(target.import_snapshot(snapshot).await, 3);
+        assert_eq!(target.export_snapshot().await.entries.len(), 3);
+
+        assert_eq!(
+            target
+                .get("search.run", &json!({"query": "a", "nonce": 2}))
+                .await,
+            Some(json!([1]))
+        );
+        let lookup = target
+            .lookup_for_user("me.profile", &json!(null), Some("alice"))
+            .await;
+        assert!(matches!(lookup, CacheLookup::Hit(value) if value == json!("alice")));
+
+        target.invalidate_tags(&["user:1"]).await;
+        assert!(!target.contains("user.get", &json!({"id": 1})).await);
+        assert_eq!(
+            target.stats().await.total_bytes,
+            source.stats().await.total_bytes - 5
+        );
+    }
+
+    #[tokio::test]
+    async fn test_import_fixture_snapshot() {
+        let cache = Cache::new(
+            CacheConfig::new()
+                .exclude_pattern("admin.*")
+                .with_max_entry_bytes(16),
+        );
+        let snapshot: CacheSnapshot = serde_json::from_value(json!({
+            "entries": [
+                {"path": "user.get", "input": {"id": 1}, "value": "Ann", "ttlMs": 60000},
+                {"path": "admin.users", "input": null, "value": [], "ttlMs": 60000},
+                {"path": "user.old", "input": null, "value": 1, "ttlMs": 0},
+                {"path": "user.big", "input": null, "value": "x".repeat(32), "ttlMs": 60000}
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(cache.import_snapshot(snapshot).await, 1);
+        assert_eq!(
+            cache.get("user.get", &json!({"id": 1})).await,
+            Some(json!("Ann"))
+        );
+        assert_eq!(cache.stats().await.oversized_rejections, 1);
+    }
+
+    #[tokio::test]
+    async fn test_invalidation_listeners() {
+        let cache = Cache::new(CacheConfig::new());
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        // Registered on a clone, still notified by the original
+        cache
+            .clone()
+            .on_invalidate(move |invalidation| sink.lock().unwrap().push(invalidation.clone()));
+
+        cache
+            .set_with_tags(
+                "user.get",
+                &json!({"id": 1}),
+                json!(1),
+                vec!["user:1".into()],
+            )
+            .await;
+        cache.set("user.list", &json!({}), json!([])).await;
+
+        cache.invalidate_tags(&["user:1"]).await;
+        cache.invalidate_pattern("user.*").await;
+        cache.invalidate("post.get", &json!(7)).await;
+        cache.invalidate_all().await;
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 4);
+        assert_eq!(
+            seen[0].cause,
+            InvalidationCause::Tags {
+                tags: vec!["user:1".into()]
+            }
+        );
+        assert_eq!(
+            seen[0].entries,
+            [InvalidatedEntry {
+                path: "user.get".into(),
+                input: json!({"id": 1}),
+                user: None,
+            }]
+        );
+        assert_eq!(seen[1].entries.len(), 1);
+        assert_eq!(seen[1].entries[0].path, "user.list");
+        // Requested entries are reported even when not cached
+        assert_eq!(seen[2].cause, InvalidationCause::Entries);
+        assert_eq!(seen[2].entries[0].input, json!(7));
+        assert_eq!(seen[3].cause, InvalidationCause::All);
+    }
+
     #[tokio::test]
     async fn test_cache_contains() {
         let cache = Cache::new(CacheConfig::new());
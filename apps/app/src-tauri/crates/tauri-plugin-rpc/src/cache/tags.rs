@@ -0,0 +1,116 @@
+//! Cache tags declared by handlers
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use super::entry::CacheEntry;
+
+/// Tags declared by a handler through [`Context::add_cache_tags`](crate::Context::add_cache_tags)
+///
+/// One collector lives on each call's context and is read back by the cache
+/// middleware once the handler returns.
+#[derive(Debug, Default)]
+pub(crate) struct CacheTags {
+    tags: Mutex<Vec<String>>,
+}
+
+impl CacheTags {
+    /// Record tags, skipping ones already declared
+    pub(crate) fn add(&self, tags: impl IntoIterator<Item = String>) {
+        let mut current = self.tags.lock().unwrap_or_else(|e| e.into_inner());
+        for tag in tags {
+            if !current.contains(&tag) {
+                current.push(tag);
+            }
+        }
+    }
+
+    /// The tags declared so far
+    pub(crate) fn to_vec(&self) -> Vec<String> {
+        self.tags.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// Keys of the tagged cache entries, by tag
+///
+/// Kept in step with the store: every stored entry is indexed under its
+/// tags and unindexed when it is replaced, removed or evicted.
+#[derive(Debug, Default)]
+pub(crate) struct TagIndex {
+    keys: HashMap<String, HashSet<String>>,
+}
+
+impl TagIndex {
+    pub(crate) fn insert(&mut self, key: &str, entry: &CacheEntry) {
+        for tag in &entry.tags {
+            self.keys
+                .entry(tag.clone())
+                .or_default()
+                .insert(key.to_string());
+        }
+    }
+
+    pub(crate) fn remove(&mut self, key: &str, entry: &CacheEntry) {
+        for tag in &entry.tags {
+            if let Some(keys) = self.keys.get_mut(tag) {
+                keys.remove(key);
+                if keys.is_empty() {
+                    self.keys.remove(tag);
+                }
+            }
+        }
+    }
+
+    /// Keys of the entries carrying any of `tags`
+    pub(crate) fn keys_for<S: AsRef<str>>(&self, tags: &[S]) -> HashSet<String> {
+        tags.iter()
+            .filter_map(|tag| self.keys.get(tag.as_ref()))
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.keys.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::time::Duration;
+
+    fn tagged(tags: &[&str]) -> CacheEntry {
+        CacheEntry::new(json!(null), Duration::from_secs(60))
+            .with_tags(tags.iter().map(|t| t.to_string()).collect())
+    }
+
+    #[test]
+    fn test_cache_tags_dedup() {
+        let tags = CacheTags::default();
+        assert!(tags.to_vec().is_empty());
+
+        tags.add(["user:1".to_string(), "user:2".to_string()]);
+        tags.add(["user:1".to_string()]);
+        assert_eq!(tags.to_vec(), ["user:1", "user:2"]);
+    }
+
+    #[test]
+    fn test_tag_index_tracks_keys() {
+        let mut index = TagIndex::default();
+        let a = tagged(&["user:1", "users"]);
+        let b = tagged(&["user:2", "users"]);
+        index.insert("a", &a);
+        index.insert("b", &b);
+
+        assert_eq!(index.keys_for(&["user:1"]).len(), 1);
+        assert_eq!(index.keys_for(&["users"]).len(), 2);
+        assert_eq!(index.keys_for(&["user:1", "user:2"]).len(), 2);
+
+        index.remove("a", &a);
+        assert!(index.keys_for(&["user:1"]).is_empty());
+        assert!(!index.keys.contains_key("user:1"));
+        assert_eq!(index.keys_for(&["users"]).len(), 1);
+    }
+}
@@ -15,6 +15,7 @@
 //!     debug_logging: true,
 //!     cleanup_interval_secs: 30,
 //!     batch_config: BatchConfig::default(),
+//!     default_timeout_ms: Some(30_000),
 //! };
 //!
 //! let plugin_config = PluginConfig::default()
@@ -39,6 +40,8 @@ pub enum ConfigValidationError {
     InvalidCleanupInterval,
     /// BatchConfig validation failed
     InvalidBatchConfig(String),
+    /// default_timeout_ms must be greater than 0 when set
+    InvalidDefaultTimeout,
 }
 
 impl fmt::Display for ConfigValidationError {
@@ -56,6 +59,9 @@ impl fmt::Display for ConfigValidationError {
             Self::InvalidBatchConfig(msg) => {
                 write!(f, "invalid batch config: {}", msg)
             }
+            Self::InvalidDefaultTimeout => {
+                write!(f, "default_timeout_ms must be greater than 0")
+            }
         }
     }
 }
@@ -142,6 +148,7 @@ pub enum BackpressureStrategy {
 ///     debug_logging: cfg!(debug_assertions),
 ///     cleanup_interval_secs: 120,
 ///     batch_config: BatchConfig::default(),
+///     default_timeout_ms: None,
 /// };
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -158,6 +165,9 @@ pub struct RpcConfig {
     pub cleanup_interval_secs: u64,
     /// Batch request configuration
     pub batch_config: BatchConfig,
+    /// Timeout in milliseconds for queries and mutations without their own (default: none)
+    #[serde(default)]
+    pub default_timeout_ms: Option<u64>,
 }
 
 impl Default for RpcConfig {
@@ -169,6 +179,7 @@ impl Default for RpcConfig {
             debug_logging: false,
             cleanup_interval_secs: 60,
             batch_config: BatchConfig::default(),
+            default_timeout_ms: None,
         }
     }
 }
@@ -188,6 +199,7 @@ impl RpcConfig {
     /// - `default_channel_buffer` is 0
     /// - `cleanup_interval_secs` is 0
     /// - `batch_config` is invalid (e.g., max_batch_size is 0)
+    /// - `default_timeout_ms` is set to zero
     ///
     /// # Example
     /// ```rust,ignore
@@ -208,6 +220,9 @@ impl RpcConfig {
         if let Err(e) = self.batch_config.validate() {
             return Err(ConfigValidationError::InvalidBatchConfig(e));
         }
+        if self.default_timeout_ms == Some(0) {
+            return Err(ConfigValidationError::InvalidDefaultTimeout);
+        }
         Ok(())
     }
 
@@ -288,6 +303,26 @@ impl RpcConfig {
         self.batch_config = config;
         self
     }
+
+    /// Set the timeout applied to queries and mutations without their own.
+    ///
+    /// Procedures configured with `ProcedureBuilder::timeout` keep their own
+    /// deadline.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let config = RpcConfig::new().with_default_timeout(Duration::from_secs(30));
+    /// ```
+    #[must_use = "This method returns a new RpcConfig and does not modify self"]
+    pub fn with_default_timeout(mut self, timeout: Duration) -> Self {
+        self.default_timeout_ms = Some(timeout.as_millis() as u64);
+        self
+    }
+
+    /// Get the timeout applied to queries and mutations without their own.
+    pub fn default_timeout(&self) -> Option<Duration> {
+        self.default_timeout_ms.map(Duration::from_millis)
+    }
 }
 
 // =============================================================================
@@ -1,13 +1,25 @@
 //! Context types for dependency injection
 
+use crate::auth::AuthResult;
+use crate::cache::{CachePolicy, CacheTags};
+use crate::middleware::RequestEnvelope;
+use crate::subscription::CancellationSignal;
 use std::sync::Arc;
 
 /// Context wrapper providing access to application state
 ///
 /// The context is cloned for each request, so use `Arc` for shared state.
+/// Besides the application state it carries the [`RequestEnvelope`], the
+/// cancellation signal, the cache policy and tags and, once an auth
+/// middleware ran, the [`AuthResult`] of the call being handled.
 #[derive(Clone)]
 pub struct Context<T: Clone + Send + Sync + 'static> {
     inner: Arc<T>,
+    envelope: Arc<RequestEnvelope>,
+    cancellation: Arc<CancellationSignal>,
+    cache_policy: CachePolicy,
+    cache_tags: Arc<CacheTags>,
+    auth: Option<Arc<AuthResult>>,
 }
 
 impl<T: Clone + Send + Sync + 'static> Context<T> {
@@ -15,6 +27,117 @@ impl<T: Clone + Send + Sync + 'static> Context<T> {
     pub fn new(ctx: T) -> Self {
         Self {
             inner: Arc::new(ctx),
+            envelope: Arc::default(),
+            cancellation: Arc::default(),
+            cache_policy: CachePolicy::default(),
+            cache_tags: Arc::default(),
+            auth: None,
+        }
+    }
+
+    /// Attach the request envelope for the call being handled
+    #[must_use = "This method returns a new Context and does not modify self"]
+    pub fn with_envelope(mut self, envelope: RequestEnvelope) -> Self {
+        self.envelope = Arc::new(envelope);
+        self
+    }
+
+    /// Get the request envelope (transport metadata) for this call
+    pub fn envelope(&self) -> &RequestEnvelope {
+        &self.envelope
+    }
+
+    /// Attach the cancellation signal for the call being handled
+    #[must_use = "This method returns a new Context and does not modify self"]
+    pub fn with_cancellation(mut self, signal: Arc<CancellationSignal>) -> Self {
+        self.cancellation = signal;
+        self
+    }
+
+    /// Get the cancellation signal, fired when the caller aborts this call
+    ///
+    /// Long-running handlers can poll [`CancellationSignal::is_cancelled`] or
+    /// await [`CancellationSignal::cancelled`] to stop early.
+    pub fn cancellation(&self) -> &Arc<CancellationSignal> {
+        &self.cancellation
+    }
+
+    /// Check whether the caller has cancelled this call
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+
+    /// Attach the authentication result for the call being handled
+    ///
+    /// Done by the [`auth`](crate::auth) middlewares.
+    #[must_use = "This method returns a new Context and does not modify self"]
+    pub fn with_auth(mut self, auth: AuthResult) -> Self {
+        self.auth = Some(Arc::new(auth));
+        self
+    }
+
+    /// Get the authentication result, if an auth middleware ran
+    pub fn auth(&self) -> Option<&AuthResult> {
+        self.auth.as_deref()
+    }
+
+    /// Get the authenticated user's id, if any
+    pub fn user_id(&self) -> Option<&str> {
+        self.auth()
+            .filter(|auth| auth.authenticated)
+            .and_then(|auth| auth.user_id.as_deref())
+    }
+
+    /// Tag the result of this call for cache invalidation
+    ///
+    /// Under [`cache_middleware`](crate::cache::cache_middleware) the tags of
+    /// a query are stored with its cached result. Under
+    /// [`invalidation_middleware`](crate::cache::invalidation_middleware) a
+    /// successful mutation invalidates every entry carrying one of its tags.
+    ///
+    /// ```rust,ignore
+    /// async fn get_user(ctx: Context<AppContext>, id: u32) -> RpcResult<User> {
+    ///     ctx.add_cache_tags([format!("user:{id}")]);
+    ///     ctx.db.get_user(id).await
+    /// }
+    /// ```
+    pub fn add_cache_tags<I, S>(&self, tags: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.cache_tags.add(tags.into_iter().map(Into::into));
+    }
+
+    /// Attach the cache policy declared for the procedure being called
+    #[must_use = "This method returns a new Context and does not modify self"]
+    pub(crate) fn with_cache_policy(mut self, policy: CachePolicy) -> Self {
+        self.cache_policy = policy;
+        self
+    }
+
+    /// Get the cache policy declared for the procedure being called
+    ///
+    /// Read by [`cache_middleware`](crate::cache::cache_middleware) to decide
+    /// whether results may be stored.
+    pub fn cache_policy(&self) -> CachePolicy {
+        self.cache_policy
+    }
+
+    /// Get the cache tags collected for this call
+    pub(crate) fn cache_tags(&self) -> &Arc<CacheTags> {
+        &self.cache_tags
+    }
+
+    /// Wrap a new state value while keeping this context's request-scoped data
+    pub(crate) fn rebind<U: Clone + Send + Sync + 'static>(&self, ctx: U) -> Context<U> {
+        Context {
+            inner: Arc::new(ctx),
+            envelope: self.envelope.clone(),
+            cancellation: self.cancellation.clone(),
+            cache_policy: self.cache_policy,
+            cache_tags: self.cache_tags.clone(),
+            auth: self.auth.clone(),
         }
     }
 
@@ -49,6 +49,8 @@ pub enum RpcErrorCode {
     PayloadTooLarge,
     /// Too many requests - rate limit exceeded
     RateLimited,
+    /// The caller cancelled the request before it completed
+    Cancelled,
 
     // Server errors (5xx equivalent)
     /// An unexpected internal error occurred
@@ -57,6 +59,8 @@ pub enum RpcErrorCode {
     NotImplemented,
     /// The service is temporarily unavailable
     ServiceUnavailable,
+    /// The procedure did not complete before its deadline
+    Timeout,
 
     // RPC-specific errors
     /// The requested procedure was not found
@@ -81,9 +85,11 @@ impl RpcErrorCode {
             Self::Conflict => "CONFLICT",
             Self::PayloadTooLarge => "PAYLOAD_TOO_LARGE",
             Self::RateLimited => "RATE_LIMITED",
+            Self::Cancelled => "CANCELLED",
             Self::InternalError => "INTERNAL_ERROR",
             Self::NotImplemented => "NOT_IMPLEMENTED",
             Self::ServiceUnavailable => "SERVICE_UNAVAILABLE",
+            Self::Timeout => "TIMEOUT",
             Self::ProcedureNotFound => "PROCEDURE_NOT_FOUND",
             Self::SubscriptionError => "SUBSCRIPTION_ERROR",
             Self::MiddlewareError => "MIDDLEWARE_ERROR",
@@ -103,6 +109,7 @@ impl RpcErrorCode {
                 | Self::Conflict
                 | Self::PayloadTooLarge
                 | Self::RateLimited
+                | Self::Cancelled
         )
     }
 
@@ -110,7 +117,7 @@ impl RpcErrorCode {
     pub fn is_server_error(&self) -> bool {
         matches!(
             self,
-            Self::InternalError | Self::NotImplemented | Self::ServiceUnavailable
+            Self::InternalError | Self::NotImplemented | Self::ServiceUnavailable | Self::Timeout
         )
     }
 }
@@ -324,6 +331,28 @@ impl RpcError {
     pub fn rate_limited(message: impl Into<String>) -> Self {
         Self::new(RpcErrorCode::RateLimited, message)
     }
+
+    /// Create a TIMEOUT error for a procedure that exceeded its deadline.
+    pub fn timeout(path: &str, timeout: std::time::Duration) -> Self {
+        let timeout_ms = timeout.as_millis() as u64;
+        Self::new(
+            RpcErrorCode::Timeout,
+            format!("Procedure '{}' timed out after {}ms", path, timeout_ms),
+        )
+        .with_details(serde_json::json!({
+            "path": path,
+            "timeout_ms": timeout_ms,
+        }))
+    }
+
+    /// Create a CANCELLED error for a call aborted by the caller.
+    pub fn cancelled(path: &str) -> Self {
+        Self::new(
+            RpcErrorCode::Cancelled,
+            format!("Procedure '{}' was cancelled", path),
+        )
+        .with_details(serde_json::json!({ "path": path }))
+    }
 }
 
 impl From<serde_json::Error> for RpcError {
@@ -742,6 +771,8 @@ mod proptests {
             Just(RpcErrorCode::InternalError),
             Just(RpcErrorCode::NotImplemented),
             Just(RpcErrorCode::ServiceUnavailable),
+            Just(RpcErrorCode::Timeout),
+            Just(RpcErrorCode::Cancelled),
             Just(RpcErrorCode::ProcedureNotFound),
             Just(RpcErrorCode::SubscriptionError),
             Just(RpcErrorCode::MiddlewareError),
@@ -110,6 +110,46 @@ where
     })
 }
 
+// =============================================================================
+// Compensation Handlers
+// =============================================================================
+
+/// Boxed compensation (undo) handler, called with a mutation's input and output
+pub(crate) type BoxedCompensation<Ctx> = Arc<
+    dyn Fn(
+            Context<Ctx>,
+            serde_json::Value,
+            serde_json::Value,
+        ) -> Pin<Box<dyn Future<Output = RpcResult<()>> + Send>>
+        + Send
+        + Sync,
+>;
+
+/// Convert a compensation handler into a boxed compensation for storage
+pub(crate) fn into_boxed_compensation<Ctx, Input, Output, F, Fut>(
+    handler: F,
+) -> BoxedCompensation<Ctx>
+where
+    Ctx: Clone + Send + Sync + 'static,
+    Input: DeserializeOwned + Send + 'static,
+    Output: DeserializeOwned + Send + 'static,
+    F: Fn(Context<Ctx>, Input, Output) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = RpcResult<()>> + Send + 'static,
+{
+    Arc::new(move |ctx, input_value, output_value| {
+        let parsed = serde_json::from_value::<Input>(input_value).and_then(|input| {
+            serde_json::from_value::<Output>(output_value).map(|output| (input, output))
+        });
+        match parsed {
+            Ok((input, output)) => Box::pin(handler(ctx, input, output)),
+            Err(e) => {
+                warn!(error = %e, "Compensation input deserialization failed");
+                Box::pin(async move { Err(e.into()) })
+            }
+        }
+    })
+}
+
 /// Estimate JSON value size without allocating a string
 #[inline]
 fn estimate_json_size(value: &serde_json::Value) -> usize {
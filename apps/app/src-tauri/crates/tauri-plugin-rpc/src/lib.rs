@@ -271,6 +271,8 @@
 //! - [`Context`] - Context wrapper for dependency injection
 //! - [`Handler`] - Handler trait for procedures
 //! - [`middleware`] - Middleware types and execution
+//! - [`pattern`] - Path patterns shared by caching, auth, rate limiting and logging
+//! - [`bulkhead`] - Concurrency limits for expensive procedures
 //! - [`subscription`] - Subscription system with events and channels
 //! - [`RpcError`] - Error types and codes
 //! - [`RpcConfig`] - Plugin configuration
@@ -286,6 +288,7 @@
 
 pub mod auth;
 pub mod batch;
+pub mod bulkhead;
 pub mod cache;
 mod config;
 mod context;
@@ -293,6 +296,7 @@ mod error;
 mod handler;
 pub mod logging;
 pub mod middleware;
+pub mod pattern;
 mod plugin;
 pub mod procedure;
 pub mod rate_limit;
@@ -312,10 +316,15 @@ pub use auth::{
 };
 pub use batch::{
     BatchConfig, BatchMetrics, BatchRequest, BatchResponse, BatchResult, BatchResultData,
-    SingleRequest, execute_batch,
+    BatchStreamEvent, CompensationResult, RESULT_REF_KEY, ResultSink, SingleRequest,
+    batch_event_name, execute_batch, execute_batch_streaming,
 };
+pub use bulkhead::{Bulkhead, BulkheadConfig, BulkheadLimit, BulkheadPermit, bulkhead_middleware};
 pub use cache::{
-    Cache, CacheConfig, CacheEntry, CacheStats, cache_middleware, generate_cache_key,
+    Cache, CacheConfig, CacheEntry, CacheInvalidation, CacheKeyFn, CacheKeySelector, CacheLookup,
+    CachePolicy, CacheSnapshot, CacheSnapshotEntry, CacheStats, CacheStore, FileCacheStore,
+    InvalidatedEntry, InvalidationCause, LruStore, RefreshAhead, RefreshClaim, SingleFlight,
+    cache_middleware, dedup_middleware, generate_cache_key, invalidation_event_name,
     invalidation_middleware,
 };
 pub use config::{BackpressureStrategy, ConfigValidationError, PluginConfig, RpcConfig};
@@ -329,30 +338,35 @@ pub use logging::{
     AuthLogEvent, CacheLogEvent, JsonLogger, LogConfig, LogEntry, LogLevel, Logger, MetricsLogger,
     RateLimitLogEvent, RequestId, RequestMeta, SubscriptionLogEvent, TracingConfig, TracingLogger,
     log_auth_event, log_batch_request, log_cache_event, log_plugin_init, log_plugin_shutdown,
-    log_procedure_registered, log_rate_limit_event, log_router_compiled, log_subscription_event,
-    logging_middleware, logging_middleware_with_logger, redact_value,
+    log_procedure_cancelled, log_procedure_registered, log_procedure_timeout, log_rate_limit_event,
+    log_router_compiled, log_subscription_event, logging_middleware,
+    logging_middleware_with_logger, redact_value,
 };
-pub use middleware::{Middleware, MiddlewareFn, Next, ProcedureType, Request, from_fn};
+pub use middleware::{
+    Middleware, MiddlewareFn, Next, ProcedureType, Request, RequestEnvelope, from_fn,
+};
+pub use pattern::{PathPattern, PatternSet};
 pub use plugin::{
-    DynRouter, SubscribeRequest, SubscriptionFuture, init, init_with_config, init_with_full_config,
+    CompensationFuture, DynRouter, SubscribeRequest, SubscriptionFuture, emit_cache_invalidations,
+    init, init_with_config, init_with_full_config,
 };
 pub use procedure::{
     ContextTransformedBuilder, ContextTransformedTypedBuilder, ContextTransformedValidatedBuilder,
     ContextTransformer, ProcedureBuilder, RegisteredProcedure, ValidatedProcedureBuilder,
 };
 pub use rate_limit::{
-    RateLimit, RateLimitConfig, RateLimitStrategy, RateLimitUsage, RateLimiter,
-    rate_limit_middleware,
+    RateLimit, RateLimitConfig, RateLimitCost, RateLimitCostFn, RateLimitLayer, RateLimitStrategy,
+    RateLimitUsage, RateLimiter, RateLimiterStats, rate_limit_middleware,
 };
 pub use router::{
     CompiledRouter, ContextTransformedChain, ContextTransformedTypedChain,
-    ContextTransformedValidatedChain, ProcedureChain, Router, TypedProcedureChain,
-    ValidatedProcedureChain,
+    ContextTransformedValidatedChain, ProcedureChain, Router, SCHEMA_PROCEDURE_PATH,
+    TypedProcedureChain, ValidatedProcedureChain,
 };
 pub use schema::{
     OpenApiComponents, OpenApiInfo, OpenApiMediaType, OpenApiOperation, OpenApiPathItem,
     OpenApiRequestBody, OpenApiResponse, OpenApiSchema, ProcedureMeta, ProcedureSchema,
-    ProcedureTypeSchema, RouterSchema, SchemaBuilder, TypeSchema,
+    ProcedureTypeSchema, RouterSchema, SchemaBuilder, ToTauriSchema, TypeSchema,
 };
 pub use subscription::{
     CancellationSignal, ChannelPublisher, CompletionReason, Event, EventMeta, EventPublisher,
@@ -393,6 +407,10 @@ pub mod prelude {
         BatchRequest,
         BatchResponse,
         BatchResult,
+        // Concurrency limiting
+        Bulkhead,
+        BulkheadConfig,
+        BulkheadLimit,
         // Cache
         Cache,
         CacheConfig,
@@ -458,12 +476,16 @@ pub mod prelude {
         // Rate limiting
         RateLimit,
         RateLimitConfig,
+        RateLimitCost,
+        RateLimitLayer,
         RateLimitLogEvent,
         RateLimitStrategy,
         RateLimitUsage,
         RateLimiter,
+        RateLimiterStats,
         RegisteredProcedure,
         Request,
+        RequestEnvelope,
         RequestId,
         RequestMeta,
         Router,
@@ -475,6 +497,7 @@ pub mod prelude {
         RpcResult,
         SchemaBuilder,
         ShutdownResult,
+        SingleFlight,
         SingleRequest,
         SubscriptionContext,
         SubscriptionEvent,
@@ -485,6 +508,7 @@ pub mod prelude {
         SubscriptionMetrics,
         SubscriptionState,
         SuccessResponse,
+        ToTauriSchema,
         TracingConfig,
         TracingLogger,
         TypeSchema,
@@ -498,6 +522,7 @@ pub mod prelude {
         auth_middleware,
         auth_with_config,
         cache_middleware,
+        dedup_middleware,
         event_channel,
         execute_batch,
         generate_cache_key,
@@ -512,7 +537,9 @@ pub mod prelude {
         log_cache_event,
         log_plugin_init,
         log_plugin_shutdown,
+        log_procedure_cancelled,
         log_procedure_registered,
+        log_procedure_timeout,
         log_rate_limit_event,
         log_router_compiled,
         log_subscription_event,
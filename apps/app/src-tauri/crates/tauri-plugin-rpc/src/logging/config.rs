@@ -9,6 +9,7 @@
 
 use super::constants::{DEFAULT_MAX_ATTRIBUTE_SIZE, DEFAULT_SLOW_THRESHOLD_MS};
 use super::types::LogLevel;
+use crate::pattern::PatternSet;
 use std::collections::{HashMap, HashSet};
 
 /// Configuration for distributed tracing integration.
@@ -132,8 +133,11 @@ pub struct LogConfig {
     pub log_success: bool,
     /// Whether to log failed requests.
     pub log_errors: bool,
-    /// Set of paths to exclude from logging.
-    pub excluded_paths: HashSet<String>,
+    /// Paths and path patterns to exclude from logging.
+    ///
+    /// Formerly a `HashSet<String>`; `collect()` strings into a
+    /// [`PatternSet`] to build one directly.
+    pub excluded_paths: PatternSet,
     /// Per-procedure log level overrides.
     pub procedure_levels: HashMap<String, LogLevel>,
     /// Optional distributed tracing configuration.
@@ -155,7 +159,7 @@ impl Default for LogConfig {
             redaction_replacement: "[REDACTED]".to_string(),
             log_success: true,
             log_errors: true,
-            excluded_paths: HashSet::new(),
+            excluded_paths: PatternSet::new(),
             procedure_levels: HashMap::new(),
             tracing: Some(TracingConfig::default()),
             slow_request_threshold_ms: Some(DEFAULT_SLOW_THRESHOLD_MS),
@@ -235,16 +239,17 @@ impl LogConfig {
     }
 
     /// Excludes a path from logging.
+    ///
+    /// Accepts path patterns such as `health.*` or `**.ping`; a negated
+    /// pattern like `!admin.audit` logs paths excluded by earlier patterns.
     pub fn exclude_path(mut self, path: impl Into<String>) -> Self {
-        self.excluded_paths.insert(path.into());
+        self.excluded_paths.insert(path);
         self
     }
 
     /// Excludes multiple paths from logging.
     pub fn exclude_paths(mut self, paths: impl IntoIterator<Item = impl Into<String>>) -> Self {
-        for path in paths {
-            self.excluded_paths.insert(path.into());
-        }
+        self.excluded_paths.extend(paths);
         self
     }
 
@@ -284,9 +289,9 @@ impl LogConfig {
         self
     }
 
-    /// Checks if a path should be logged (not in excluded paths).
+    /// Checks if a path should be logged (not matched by the excluded paths).
     pub fn should_log_path(&self, path: &str) -> bool {
-        !self.excluded_paths.contains(path)
+        !self.excluded_paths.matches(path)
     }
 
     /// Gets the effective log level for a specific path.
@@ -108,6 +108,31 @@ pub fn log_batch_request(
     );
 }
 
+// =============================================================================
+// Timeout and Cancellation Logging
+// =============================================================================
+
+/// Log a procedure that exceeded its deadline.
+///
+/// The handler future has already been dropped when this is logged.
+pub fn log_procedure_timeout(request_id: &str, path: &str, timeout_ms: u64) {
+    tracing::warn!(
+        request_id = %request_id,
+        path = %path,
+        timeout_ms = %timeout_ms,
+        "Procedure timed out"
+    );
+}
+
+/// Log a call aborted by the caller through `rpc_cancel`.
+pub fn log_procedure_cancelled(request_id: &str, path: &str) {
+    tracing::info!(
+        request_id = %request_id,
+        path = %path,
+        "Procedure cancelled by caller"
+    );
+}
+
 // =============================================================================
 // Cache Events
 // =============================================================================
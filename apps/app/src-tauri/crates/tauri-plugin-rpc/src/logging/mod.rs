@@ -139,7 +139,8 @@ pub use middleware::{logging_middleware, logging_middleware_with_logger, should_
 // Event Types and Logging
 pub use events::{
     AuthLogEvent, CacheLogEvent, RateLimitLogEvent, SubscriptionLogEvent, log_auth_event,
-    log_batch_request, log_cache_event, log_rate_limit_event, log_subscription_event,
+    log_batch_request, log_cache_event, log_procedure_cancelled, log_procedure_timeout,
+    log_rate_limit_event, log_subscription_event,
 };
 
 // Lifecycle Logging
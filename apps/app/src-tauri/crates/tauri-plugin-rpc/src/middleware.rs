@@ -2,6 +2,7 @@
 
 use crate::{Context, RpcResult};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
@@ -29,6 +30,99 @@ impl std::fmt::Display for ProcedureType {
     }
 }
 
+/// Transport metadata sent alongside a procedure call
+///
+/// The envelope travels next to the procedure input so that middleware such as
+/// auth, rate limiting and logging can inspect credentials, client identifiers
+/// and the calling window without every input type having to carry them.
+///
+/// The frontend supplies `requestId` and `metadata`; the plugin fills in
+/// `windowLabel` and `origin` from the invoking webview, overriding any
+/// client-provided values.
+///
+/// # Example
+/// ```rust,ignore
+/// let envelope = RequestEnvelope::new()
+///     .with_request_id("req-1")
+///     .with_metadata("authorization", "Bearer abc123");
+///
+/// let result = router.call_with_envelope("users.get", input, envelope).await;
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestEnvelope {
+    /// Client-supplied request identifier (generated by the plugin if absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// Headers-like key/value metadata (e.g., `authorization`, `x-client-id`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub metadata: HashMap<String, String>,
+    /// Label of the window that issued the request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    /// Origin of the webview that issued the request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+}
+
+impl RequestEnvelope {
+    /// Create an empty envelope
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the request identifier
+    #[must_use = "This method returns a new RequestEnvelope and does not modify self"]
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+
+    /// Add a metadata entry (keys are stored lowercase)
+    #[must_use = "This method returns a new RequestEnvelope and does not modify self"]
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata
+            .insert(key.into().to_ascii_lowercase(), value.into());
+        self
+    }
+
+    /// Set the calling window label
+    #[must_use = "This method returns a new RequestEnvelope and does not modify self"]
+    pub fn with_window_label(mut self, label: impl Into<String>) -> Self {
+        self.window_label = Some(label.into());
+        self
+    }
+
+    /// Set the calling origin
+    #[must_use = "This method returns a new RequestEnvelope and does not modify self"]
+    pub fn with_origin(mut self, origin: impl Into<String>) -> Self {
+        self.origin = Some(origin.into());
+        self
+    }
+
+    /// Get a metadata value by key (case-insensitive, like HTTP headers)
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.metadata
+            .get(key)
+            .or_else(|| {
+                self.metadata
+                    .iter()
+                    .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                    .map(|(_, v)| v)
+            })
+            .map(String::as_str)
+    }
+
+    /// Get the bearer token from the `authorization` metadata entry, if any
+    pub fn bearer_token(&self) -> Option<&str> {
+        self.get("authorization").and_then(|value| {
+            value
+                .strip_prefix("Bearer ")
+                .or_else(|| value.strip_prefix("bearer "))
+        })
+    }
+}
+
 /// Request information passed to middleware
 #[derive(Clone, Debug)]
 pub struct Request {
@@ -38,9 +132,21 @@ pub struct Request {
     pub procedure_type: ProcedureType,
     /// Input data as JSON
     pub input: serde_json::Value,
+    /// Transport metadata sent with the call
+    pub envelope: RequestEnvelope,
 }
 
 impl Request {
+    /// Get a metadata value from the request envelope (case-insensitive)
+    pub fn metadata(&self, key: &str) -> Option<&str> {
+        self.envelope.get(key)
+    }
+
+    /// Get the request identifier from the envelope, if any
+    pub fn request_id(&self) -> Option<&str> {
+        self.envelope.request_id.as_deref()
+    }
+
     /// Get the namespace (first part of path)
     pub fn namespace(&self) -> Option<&str> {
         self.path.split('.').next()
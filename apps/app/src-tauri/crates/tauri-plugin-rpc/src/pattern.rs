@@ -0,0 +1,562 @@
+//! Procedure path patterns
+//!
+//! One pattern language shared by caching, authorization, rate limiting and
+//! logging, so a rule written for one behaves identically in the others.
+//!
+//! Patterns are dot-separated like procedure paths:
+//!
+//! | Pattern            | Matches                                         |
+//! |--------------------|-------------------------------------------------|
+//! | `user.get`         | exactly `user.get`                              |
+//! | `user.*.get`       | `user.<one segment>.get`                        |
+//! | `user.**.get`      | `user.get`, `user.a.get`, `user.a.b.get`, ...   |
+//! | `user.{get,list}`  | `user.get` and `user.list`                      |
+//! | `!admin.*`         | every path `admin.*` does not match             |
+//! | `user.*` / `*`     | `user` and everything below it / every path     |
+//!
+//! A trailing `*` matches any remaining segments, including none, which keeps
+//! the long-standing meaning of `user.*` and `*`. Elsewhere `*` stands for
+//! exactly one segment.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use tauri_plugin_rpc::pattern::PathPattern;
+//!
+//! let pattern = PathPattern::compile("user.*.{get,list}");
+//! assert!(pattern.matches("user.posts.get"));
+//! assert!(!pattern.matches("user.posts.delete"));
+//! ```
+
+use std::fmt;
+
+// =============================================================================
+// Path Pattern
+// =============================================================================
+
+/// A path pattern, compiled once and matched without allocating in the common cases
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathPattern {
+    source: String,
+    negated: bool,
+    matcher: Matcher,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Matcher {
+    /// Every path
+    All,
+    /// A single path
+    Exact(String),
+    /// A path and everything below it
+    Prefix(String),
+    /// Anything else, segment by segment
+    Segments(Vec<Segment>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Alternatives(Vec<String>),
+    /// `*`: exactly one segment
+    Any,
+    /// `**`: zero or more segments
+    AnyDepth,
+}
+
+impl PathPattern {
+    /// Compile a pattern string
+    ///
+    /// See the [module documentation](self) for the syntax. Malformed
+    /// alternations such as `{get` match literally.
+    pub fn compile(pattern: &str) -> Self {
+        let source = pattern.to_string();
+        let (negated, body) = match source.strip_prefix('!') {
+            Some(body) => (true, body),
+            None => (false, source.as_str()),
+        };
+        let matcher = Matcher::compile(body);
+        Self {
+            source,
+            negated,
+            matcher,
+        }
+    }
+
+    /// Check if this pattern matches a path
+    pub fn matches(&self, path: &str) -> bool {
+        self.matcher.matches(path) != self.negated
+    }
+
+    /// Whether the pattern starts with `!`
+    pub fn is_negated(&self) -> bool {
+        self.negated
+    }
+
+    /// The pattern as written
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+}
+
+impl fmt::Display for PathPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl From<&str> for PathPattern {
+    fn from(pattern: &str) -> Self {
+        Self::compile(pattern)
+    }
+}
+
+impl From<String> for PathPattern {
+    fn from(pattern: String) -> Self {
+        Self::compile(&pattern)
+    }
+}
+
+impl Matcher {
+    fn compile(body: &str) -> Self {
+        let mut segments: Vec<Segment> = body.split('.').map(Segment::parse).collect();
+        if let Some(last) = segments.last_mut()
+            && *last == Segment::Any
+        {
+            *last = Segment::AnyDepth;
+        }
+        // `**.**` matches what `**` does
+        segments.dedup_by(|a, b| *a == Segment::AnyDepth && *b == Segment::AnyDepth);
+
+        let literals = |segments: &[Segment]| {
+            segments
+                .iter()
+                .all(|segment| matches!(segment, Segment::Literal(_)))
+        };
+        match segments.as_slice() {
+            [Segment::AnyDepth] => Self::All,
+            all if literals(all) => Self::Exact(body.to_string()),
+            [init @ .., Segment::AnyDepth] if literals(init) => {
+                let prefix: Vec<&str> = init
+                    .iter()
+                    .filter_map(|segment| match segment {
+                        Segment::Literal(literal) => Some(literal.as_str()),
+                        _ => None,
+                    })
+                    .collect();
+                Self::Prefix(prefix.join("."))
+            }
+            _ => Self::Segments(segments),
+        }
+    }
+
+    fn matches(&self, path: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::Exact(exact) => path == exact,
+            Self::Prefix(prefix) => {
+                path == prefix
+                    || (path.len() > prefix.len()
+                        && path.starts_with(prefix.as_str())
+                        && path.as_bytes()[prefix.len()] == b'.')
+            }
+            Self::Segments(segments) => {
+                let path: Vec<&str> = path.split('.').collect();
+                match_segments(segments, &path)
+            }
+        }
+    }
+}
+
+impl Segment {
+    fn parse(segment: &str) -> Self {
+        match segment {
+            "*" => Self::Any,
+            "**" => Self::AnyDepth,
+            _ => match segment
+                .strip_prefix('{')
+                .and_then(|rest| rest.strip_suffix('}'))
+            {
+                Some(alternatives) => Self::Alternatives(
+                    alternatives
+                        .split(',')
+                        .map(|alt| alt.trim().to_string())
+                        .collect(),
+                ),
+                None => Self::Literal(segment.to_string()),
+            },
+        }
+    }
+
+    fn matches(&self, segment: &str) -> bool {
+        match self {
+            Self::Literal(literal) => segment == literal,
+            Self::Alternatives(alternatives) => alternatives.iter().any(|alt| alt == segment),
+            Self::Any => true,
+            Self::AnyDepth => unreachable!("`**` spans segments and is handled by the caller"),
+        }
+    }
+}
+
+/// Match segment by segment, in `O(pattern × path)` steps.
+///
+/// On a mismatch only the latest `**` is retried, one segment further: any
+/// match an earlier `**` could still find by spanning more segments, the
+/// latest one finds too.
+fn match_segments(pattern: &[Segment], path: &[&str]) -> bool {
+    let (mut p, mut s) = (0, 0);
+    // Pattern index after the latest `**`, and the path index it resumes at
+    let mut retry: Option<(usize, usize)> = None;
+    while s < path.len() {
+        match pattern.get(p) {
+            Some(Segment::AnyDepth) => {
+                p += 1;
+                retry = Some((p, s));
+            }
+            Some(segment) if segment.matches(path[s]) => {
+                p += 1;
+                s += 1;
+            }
+            _ => match retry {
+                Some((after, from)) => {
+                    p = after;
+                    s = from + 1;
+                    retry = Some((after, s));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..]
+        .iter()
+        .all(|segment| *segment == Segment::AnyDepth)
+}
+
+/// Check if a pattern matches a path
+///
+/// Compiles the pattern on every call; keep a [`PathPattern`] for patterns
+/// that are matched repeatedly.
+pub fn pattern_matches(pattern: &str, path: &str) -> bool {
+    PathPattern::compile(pattern).matches(path)
+}
+
+// =============================================================================
+// Pattern Set
+// =============================================================================
+
+/// An ordered list of patterns, such as exclusion lists
+///
+/// Patterns apply in order and the last one that applies to a path decides:
+/// a plain pattern adds the paths it matches, a negated one takes them back
+/// out. `["admin.**", "!admin.health"]` thus matches everything under
+/// `admin` except `admin.health`. A set starting with a negated pattern
+/// starts from every path, so `["!health"]` matches all paths but `health`,
+/// as the pattern alone would.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PatternSet {
+    patterns: Vec<PathPattern>,
+}
+
+impl PatternSet {
+    /// Create an empty set, matching nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a pattern, unless the set already holds it
+    pub fn insert(&mut self, pattern: impl Into<String>) {
+        let pattern = PathPattern::compile(&pattern.into());
+        if !self.patterns.contains(&pattern) {
+            self.patterns.push(pattern);
+        }
+    }
+
+    /// Check if the set holds a pattern, as written
+    pub fn contains(&self, pattern: &str) -> bool {
+        self.patterns.iter().any(|p| p.as_str() == pattern)
+    }
+
+    /// Check if the set matches a path
+    pub fn matches(&self, path: &str) -> bool {
+        let initial = self.patterns.first().is_some_and(PathPattern::is_negated);
+        self.patterns.iter().fold(initial, |matched, pattern| {
+            if pattern.matcher.matches(path) {
+                !pattern.negated
+            } else {
+                matched
+            }
+        })
+    }
+
+    /// Iterate over the patterns in order
+    pub fn iter(&self) -> impl Iterator<Item = &PathPattern> {
+        self.patterns.iter()
+    }
+
+    /// Number of patterns
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    /// Whether the set holds no patterns
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for PatternSet {
+    fn from_iter<I: IntoIterator<Item = S>>(patterns: I) -> Self {
+        let mut set = Self::new();
+        set.extend(patterns);
+        set
+    }
+}
+
+impl<S: Into<String>> Extend<S> for PatternSet {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, patterns: I) {
+        for pattern in patterns {
+            self.insert(pattern);
+        }
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_exact() {
+        let pattern = PathPattern::compile("user.get");
+        assert_eq!(pattern.matcher, Matcher::Exact("user.get".to_string()));
+        assert!(pattern.matches("user.get"));
+        assert!(!pattern.matches("user.create"));
+        assert!(!pattern.matches("user.get.all"));
+    }
+
+    #[test]
+    fn test_trailing_wildcard_matches_any_depth() {
+        let pattern = PathPattern::compile("user.*");
+        assert_eq!(pattern.matcher, Matcher::Prefix("user".to_string()));
+        assert!(pattern.matches("user"));
+        assert!(pattern.matches("user.get"));
+        assert!(pattern.matches("user.profile.update"));
+        assert!(!pattern.matches("users.get"));
+        assert!(!pattern.matches("post.get"));
+
+        assert_eq!(PathPattern::compile("*").matcher, Matcher::All);
+        assert_eq!(PathPattern::compile("**").matcher, Matcher::All);
+        assert!(PathPattern::compile("*").matches("anything"));
+    }
+
+    #[test]
+    fn test_inner_wildcard_matches_one_segment() {
+        let pattern = PathPattern::compile("user.*.get");
+        assert!(pattern.matches("user.posts.get"));
+        assert!(!pattern.matches("user.get"));
+        assert!(!pattern.matches("user.a.b.get"));
+        assert!(!pattern.matches("user.posts.list"));
+    }
+
+    #[test]
+    fn test_recursive_wildcard() {
+        let pattern = PathPattern::compile("user.**.get");
+        assert!(pattern.matches("user.get"));
+        assert!(pattern.matches("user.posts.get"));
+        assert!(pattern.matches("user.a.b.get"));
+        assert!(!pattern.matches("user.a.b.list"));
+
+        let pattern = PathPattern::compile("**.health");
+        assert!(pattern.matches("health"));
+        assert!(pattern.matches("api.v1.health"));
+        assert!(!pattern.matches("api.v1.healthz"));
+    }
+
+    #[test]
+    fn test_several_recursive_wildcards() {
+        let pattern = PathPattern::compile("a.**.b.**.c");
+        assert!(pattern.matches("a.b.c"));
+        assert!(pattern.matches("a.x.b.y.z.c"));
+        assert!(pattern.matches("a.b.b.c.c"));
+        assert!(!pattern.matches("a.c.b"));
+
+        // Adjacent `**` collapse into one
+        let pattern = PathPattern::compile("a.**.**.b");
+        assert!(pattern.matches("a.b"));
+        assert!(pattern.matches("a.x.y.b"));
+        assert_eq!(
+            PathPattern::compile("user.**.*").matcher,
+            Matcher::Prefix("user".to_string())
+        );
+
+        // Would take exponential time with backtracking into every `**`
+        let pattern = PathPattern::compile("**.a.**.a.**.a.**.a.**.a.**.a.**.b");
+        let path = vec!["a"; 64].join(".");
+        assert!(!pattern.matches(&path));
+        assert!(pattern.matches(&format!("{}.b", path)));
+    }
+
+    #[test]
+    fn test_alternation() {
+        let pattern = PathPattern::compile("user.{get, list}");
+        assert!(pattern.matches("user.get"));
+        assert!(pattern.matches("user.list"));
+        assert!(!pattern.matches("user.delete"));
+
+        let pattern = PathPattern::compile("{user,post}.*.get");
+        assert!(pattern.matches("post.comments.get"));
+        assert!(!pattern.matches("admin.comments.get"));
+
+        // Malformed alternations match literally
+        assert!(PathPattern::compile("user.{get").matches("user.{get"));
+    }
+
+    #[test]
+    fn test_negation() {
+        let pattern = PathPattern::compile("!admin.*");
+        assert!(pattern.is_negated());
+        assert!(pattern.matches("user.get"));
+        assert!(!pattern.matches("admin"));
+        assert!(!pattern.matches("admin.users.delete"));
+        assert_eq!(pattern.to_string(), "!admin.*");
+    }
+
+    #[test]
+    fn test_pattern_matches() {
+        assert!(pattern_matches("user.get", "user.get"));
+        assert!(pattern_matches("user.*", "user.get"));
+        assert!(pattern_matches("*", "user.get"));
+        assert!(!pattern_matches("user.*", "post.get"));
+    }
+
+    #[test]
+    fn test_pattern_set_last_match_wins() {
+        let set: PatternSet = ["admin.**", "!admin.health"].into_iter().collect();
+        assert!(set.matches("admin.users.delete"));
+        assert!(!set.matches("admin.health"));
+        assert!(!set.matches("user.get"));
+        assert!(set.contains("!admin.health"));
+        assert!(!set.contains("admin.health"));
+
+        let set: PatternSet = ["!health"].into_iter().collect();
+        assert!(set.matches("user.get"));
+        assert!(!set.matches("health"));
+
+        assert!(!PatternSet::new().matches("user.get"));
+    }
+
+    #[test]
+    fn test_pattern_set_dedups() {
+        let mut set = PatternSet::new();
+        set.insert("health");
+        set.insert("health");
+        assert_eq!(set.len(), 1);
+    }
+
+    fn segment_strategy() -> impl Strategy<Value = String> {
+        "[a-z]{1,6}"
+    }
+
+    fn path_strategy() -> impl Strategy<Value = Vec<String>> {
+        prop::collection::vec(segment_strategy(), 1..5)
+    }
+
+    /// Backtracking reference matcher, exponential in the number of `**`
+    fn match_segments_naive(pattern: &[Segment], path: &[&str]) -> bool {
+        match pattern.split_first() {
+            None => path.is_empty(),
+            Some((Segment::AnyDepth, rest)) => {
+                (0..=path.len()).any(|skip| match_segments_naive(rest, &path[skip..]))
+            }
+            Some((segment, rest)) => match path.split_first() {
+                Some((first, tail)) => segment.matches(first) && match_segments_naive(rest, tail),
+                None => false,
+            },
+        }
+    }
+
+    proptest! {
+        /// Property: Matching agrees with trying every split of every `**`
+        #[test]
+        fn prop_matches_like_backtracking(
+            pattern in prop::collection::vec(prop::sample::select(vec!["a", "b", "*", "**"]), 1..7),
+            path in prop::collection::vec(prop::sample::select(vec!["a", "b"]), 1..9),
+        ) {
+            let segments: Vec<Segment> = pattern.iter().map(|s| Segment::parse(s)).collect();
+            prop_assert_eq!(
+                match_segments(&segments, &path),
+                match_segments_naive(&segments, &path)
+            );
+        }
+
+        /// Property: A path used as a pattern matches exactly itself
+        #[test]
+        fn prop_exact_pattern_matches_itself(path in path_strategy(), other in path_strategy()) {
+            let path = path.join(".");
+            let other = other.join(".");
+            let pattern = PathPattern::compile(&path);
+            prop_assert!(pattern.matches(&path));
+            prop_assert_eq!(pattern.matches(&other), path == other);
+        }
+
+        /// Property: Negation inverts every match
+        #[test]
+        fn prop_negation_inverts(path in path_strategy(), candidate in path_strategy(), star in 0usize..4) {
+            let mut segments = path.clone();
+            if star < segments.len() {
+                segments[star] = "*".to_string();
+            }
+            let body = segments.join(".");
+            let candidate = candidate.join(".");
+            prop_assert_ne!(
+                PathPattern::compile(&body).matches(&candidate),
+                PathPattern::compile(&format!("!{}", body)).matches(&candidate)
+            );
+        }
+
+        /// Property: Segment matching agrees with the prefix fast path
+        #[test]
+        fn prop_prefix_agrees_with_recursive_wildcard(
+            prefix in path_strategy(),
+            rest in prop::collection::vec(segment_strategy(), 0..3),
+            below in any::<bool>(),
+            other in path_strategy(),
+        ) {
+            let path = if below {
+                let mut path = prefix.clone();
+                path.extend(rest);
+                path.join(".")
+            } else {
+                other.join(".")
+            };
+            let fast = PathPattern::compile(&format!("{}.*", prefix.join(".")));
+            prop_assert_eq!(&fast.matcher, &Matcher::Prefix(prefix.join(".")));
+            let mut segments: Vec<Segment> = prefix.iter().cloned().map(Segment::Literal).collect();
+            segments.push(Segment::AnyDepth);
+            let slow = Matcher::Segments(segments);
+
+            prop_assert_eq!(fast.matches(&path), slow.matches(&path));
+            if below {
+                prop_assert!(fast.matches(&path));
+            }
+        }
+
+        /// Property: Inner `*` matches exactly one arbitrary segment
+        #[test]
+        fn prop_inner_wildcard_one_segment(
+            head in segment_strategy(),
+            middle in prop::collection::vec(segment_strategy(), 0..3),
+            tail in segment_strategy(),
+        ) {
+            let pattern = PathPattern::compile(&format!("{}.*.{}", head, tail));
+            let mut path = vec![head.clone()];
+            path.extend(middle.iter().cloned());
+            path.push(tail.clone());
+            prop_assert_eq!(pattern.matches(&path.join(".")), middle.len() == 1);
+        }
+    }
+}
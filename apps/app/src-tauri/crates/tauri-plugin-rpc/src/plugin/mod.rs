@@ -1,19 +1,28 @@
 //! Tauri plugin integration
 
 use crate::RpcError;
-use crate::batch::{BatchRequest, BatchResponse, execute_batch};
+use crate::batch::{
+    BatchMetrics, BatchRequest, BatchResponse, BatchStreamEvent, batch_event_name, execute_batch,
+    execute_batch_streaming,
+};
+use crate::cache::CachePolicy;
 use crate::config::{PluginConfig, RpcConfig};
+use crate::middleware::{ProcedureType, RequestEnvelope};
+use crate::router::SCHEMA_PROCEDURE_PATH;
+use crate::schema::RouterSchema;
 use crate::subscription::{
-    Event, SubscriptionContext, SubscriptionEvent, SubscriptionManager, generate_subscription_id,
-    handle_subscription_events, handle_subscription_events_buffered, subscription_event_name,
+    CancellationSignal, Event, SubscriptionContext, SubscriptionEvent, SubscriptionManager,
+    generate_subscription_id, handle_subscription_events, handle_subscription_events_buffered,
+    subscription_event_name,
 };
 use crate::validation::{validate_rpc_input, validate_subscription_id};
 use serde::{Deserialize, Serialize};
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 use tauri::{
-    AppHandle, Emitter, Manager, Runtime, State,
+    Emitter, Manager, Runtime, State, Webview,
     plugin::{Builder, TauriPlugin},
 };
 use tokio::sync::mpsc;
@@ -30,6 +39,10 @@ pub type SubscriptionFuture<'a> = Pin<
     >,
 >;
 
+/// Future type for compensation results, `None` when no compensation is registered
+pub type CompensationFuture<'a> =
+    Pin<Box<dyn Future<Output = Option<Result<(), RpcError>>> + Send + 'a>>;
+
 // =============================================================================
 // Helper Functions
 // =============================================================================
@@ -52,34 +65,269 @@ fn generate_request_id() -> uuid::Uuid {
     uuid::Uuid::new_v7(uuid::Timestamp::now(uuid::NoContext))
 }
 
+/// Build the request envelope for an incoming command.
+///
+/// Client-supplied metadata and request ID are kept (a request ID is generated
+/// when missing), while the window label and origin are always taken from the
+/// invoking webview so the frontend cannot spoof them.
+fn resolve_envelope<R: Runtime>(
+    meta: Option<RequestEnvelope>,
+    webview: &Webview<R>,
+) -> RequestEnvelope {
+    let mut envelope = meta.unwrap_or_default();
+    if envelope.request_id.as_deref().is_none_or(str::is_empty) {
+        envelope.request_id = Some(generate_request_id().to_string());
+    }
+    envelope.window_label = Some(webview.window().label().to_string());
+    envelope.origin = webview
+        .url()
+        .ok()
+        .map(|url| url.origin().ascii_serialization());
+    envelope
+}
+
 // =============================================================================
 // Router Trait
 // =============================================================================
 
 /// Type-erased router trait for plugin storage
 pub trait DynRouter: Send + Sync {
-    /// Call a procedure by path
+    /// Call a procedure by path with the request envelope sent by the caller
     fn call<'a>(
         &'a self,
         path: &'a str,
         input: serde_json::Value,
+        envelope: RequestEnvelope,
     ) -> Pin<Box<dyn Future<Output = Result<serde_json::Value, RpcError>> + Send + 'a>>;
 
+    /// Call a procedure, exposing `signal` to the handler's context
+    ///
+    /// The default implementation ignores the signal.
+    fn call_with_cancellation<'a>(
+        &'a self,
+        path: &'a str,
+        input: serde_json::Value,
+        envelope: RequestEnvelope,
+        _signal: Arc<CancellationSignal>,
+    ) -> Pin<Box<dyn Future<Output = Result<serde_json::Value, RpcError>> + Send + 'a>> {
+        self.call(path, input, envelope)
+    }
+
     /// List all registered procedures
     fn procedures(&self) -> Vec<String>;
 
+    /// Generate the schema of all registered procedures
+    fn schema(&self) -> RouterSchema;
+
     /// Check if a path is a subscription
     fn is_subscription(&self, path: &str) -> bool;
 
-    /// Start a subscription
+    /// Get the execution timeout configured for a procedure, if any
+    fn timeout(&self, _path: &str) -> Option<Duration> {
+        None
+    }
+
+    /// Get the type of a registered procedure
+    fn procedure_type(&self, _path: &str) -> Option<ProcedureType> {
+        None
+    }
+
+    /// Get the cache policy declared for a procedure
+    fn cache_policy(&self, _path: &str) -> CachePolicy {
+        CachePolicy::Auto
+    }
+
+    /// Check the router's setup, run once when the plugin is initialized
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Run the compensation (undo) handler registered for a mutation
+    ///
+    /// Resolves to `None` when no compensation is registered for `path`.
+    fn compensate<'a>(
+        &'a self,
+        _path: &'a str,
+        _input: serde_json::Value,
+        _output: serde_json::Value,
+        _envelope: RequestEnvelope,
+    ) -> CompensationFuture<'a> {
+        Box::pin(async { None })
+    }
+
+    /// Start a subscription with the request envelope sent by the caller
     fn subscribe<'a>(
         &'a self,
         path: &'a str,
         input: serde_json::Value,
         ctx: SubscriptionContext,
+        envelope: RequestEnvelope,
     ) -> SubscriptionFuture<'a>;
 }
 
+// =============================================================================
+// Default Timeout
+// =============================================================================
+
+/// Call a procedure, applying `default_timeout` when the procedure has no
+/// timeout of its own.
+pub(crate) async fn call_with_default_timeout(
+    router: &dyn DynRouter,
+    path: &str,
+    input: serde_json::Value,
+    envelope: RequestEnvelope,
+    default_timeout: Option<Duration>,
+    signal: Arc<CancellationSignal>,
+) -> Result<serde_json::Value, RpcError> {
+    if router.timeout(path).is_some() || default_timeout.is_none() {
+        return router
+            .call_with_cancellation(path, input, envelope, signal)
+            .await;
+    }
+    let request_id = envelope.request_id.clone();
+    crate::router::with_timeout(
+        path,
+        request_id.as_deref(),
+        default_timeout,
+        router.call_with_cancellation(path, input, envelope, signal),
+    )
+    .await
+}
+
+// =============================================================================
+// Cancellation
+// =============================================================================
+
+/// How long a cancel for an unknown request ID is remembered.
+///
+/// `rpc_cancel` and `rpc_call` are separate commands, so a cancel can overtake
+/// the call it targets.
+const EARLY_CANCEL_TTL: Duration = Duration::from_secs(30);
+
+/// Calling window label and request ID of an in-flight request.
+///
+/// Request IDs are chosen by the frontend, so they are only unique per window.
+type RequestKey = (String, String);
+
+/// Cancellation signals of in-flight `rpc_call` requests, keyed by calling
+/// window and request ID so a window can only cancel its own calls.
+#[derive(Default)]
+pub(crate) struct InFlightRequests {
+    signals: dashmap::DashMap<RequestKey, Arc<CancellationSignal>>,
+    /// Cancels that arrived before their request registered
+    early_cancels: dashmap::DashMap<RequestKey, std::time::Instant>,
+}
+
+impl InFlightRequests {
+    /// Track a request until the returned guard is dropped.
+    ///
+    /// The signal starts cancelled if the same window sent a cancel for this
+    /// ID within [`EARLY_CANCEL_TTL`] before the request registered.
+    pub(crate) fn register(&self, window_label: &str, request_id: &str) -> InFlightGuard<'_> {
+        let key = (window_label.to_string(), request_id.to_string());
+        let signal = Arc::new(CancellationSignal::new());
+        // Publish the signal before checking for an early cancel: `cancel`
+        // records its tombstone before looking up the signal, so one of the
+        // two always sees the other.
+        self.signals.insert(key.clone(), signal.clone());
+        if self
+            .early_cancels
+            .remove(&key)
+            .is_some_and(|(_, at)| at.elapsed() < EARLY_CANCEL_TTL)
+        {
+            signal.cancel();
+        }
+        InFlightGuard {
+            requests: self,
+            key,
+            signal,
+        }
+    }
+
+    /// Fire the cancellation signal of a request `window_label` has in flight.
+    ///
+    /// Returns `false` if that window has no request with this ID in flight;
+    /// the cancel is then remembered for [`EARLY_CANCEL_TTL`] and applied if
+    /// the window's request registers in that time.
+    pub(crate) fn cancel(&self, window_label: &str, request_id: &str) -> bool {
+        let key = (window_label.to_string(), request_id.to_string());
+        self.early_cancels
+            .retain(|_, at| at.elapsed() < EARLY_CANCEL_TTL);
+        self.early_cancels
+            .insert(key.clone(), std::time::Instant::now());
+        match self.signals.get(&key) {
+            Some(signal) => {
+                signal.cancel();
+                self.early_cancels.remove(&key);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Registration of an in-flight request, removed from [`InFlightRequests`] on drop.
+pub(crate) struct InFlightGuard<'a> {
+    requests: &'a InFlightRequests,
+    key: RequestKey,
+    signal: Arc<CancellationSignal>,
+}
+
+impl InFlightGuard<'_> {
+    /// Signal fired when the caller cancels this request.
+    pub(crate) fn signal(&self) -> Arc<CancellationSignal> {
+        self.signal.clone()
+    }
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        // A reused request ID may have replaced our entry; only remove our own.
+        self.requests
+            .signals
+            .remove_if(&self.key, |_, signal| Arc::ptr_eq(signal, &self.signal));
+    }
+}
+
+/// Call a procedure, failing with `CANCELLED` as soon as `signal` fires.
+///
+/// Handlers see the same signal on their context and may stop earlier on
+/// their own by returning a `CANCELLED` error. Any other error a call
+/// produces is returned unchanged, even after a cancel has arrived.
+pub(crate) async fn call_cancellable(
+    router: &dyn DynRouter,
+    path: &str,
+    input: serde_json::Value,
+    envelope: RequestEnvelope,
+    default_timeout: Option<Duration>,
+    signal: Arc<CancellationSignal>,
+) -> Result<serde_json::Value, RpcError> {
+    let request_id = envelope.request_id.clone().unwrap_or_default();
+    if signal.is_cancelled() {
+        crate::logging::log_procedure_cancelled(&request_id, path);
+        return Err(RpcError::cancelled(path));
+    }
+    let call = call_with_default_timeout(
+        router,
+        path,
+        input,
+        envelope,
+        default_timeout,
+        signal.clone(),
+    );
+    let result = tokio::select! {
+        biased;
+        result = call => result,
+        _ = signal.cancelled() => Err(RpcError::cancelled(path)),
+    };
+    if let Err(e) = &result
+        && e.code == crate::RpcErrorCode::Cancelled
+    {
+        crate::logging::log_procedure_cancelled(&request_id, path);
+    }
+    result
+}
+
 // =============================================================================
 // Plugin State
 // =============================================================================
@@ -88,6 +336,7 @@ struct RouterState(Arc<dyn DynRouter>);
 struct SubscriptionState(Arc<SubscriptionManager>);
 struct ConfigState(RpcConfig);
 struct PluginConfigState(PluginConfig);
+struct InFlightState(InFlightRequests);
 
 // =============================================================================
 // Request Types
@@ -114,13 +363,17 @@ pub struct SubscribeRequest {
 // =============================================================================
 
 #[tauri::command]
-async fn rpc_call(
+async fn rpc_call<R: Runtime>(
     path: String,
     input: serde_json::Value,
+    meta: Option<RequestEnvelope>,
+    webview: Webview<R>,
     state: State<'_, RouterState>,
     config: State<'_, ConfigState>,
+    in_flight: State<'_, InFlightState>,
 ) -> Result<serde_json::Value, String> {
-    let request_id = generate_request_id();
+    let envelope = resolve_envelope(meta, &webview);
+    let request_id = envelope.request_id.clone().unwrap_or_default();
     let start = std::time::Instant::now();
 
     debug!(
@@ -129,6 +382,10 @@ async fn rpc_call(
         "RPC call started"
     );
 
+    // Register first so a cancel sent right after the call is not missed
+    let window_label = envelope.window_label.clone().unwrap_or_default();
+    let guard = in_flight.0.register(&window_label, &request_id);
+
     validate_rpc_input(&path, &input, &config.0).map_err(|e| {
         warn!(
             request_id = %request_id,
@@ -139,7 +396,16 @@ async fn rpc_call(
         serde_json::to_string(&e).unwrap_or_else(|_| e.to_string())
     })?;
 
-    let result = state.0.call(&path, input).await.map_err(|e| {
+    let result = call_cancellable(
+        state.0.as_ref(),
+        &path,
+        input,
+        envelope,
+        config.0.default_timeout(),
+        guard.signal(),
+    )
+    .await
+    .map_err(|e| {
         let duration = start.elapsed();
         warn!(
             request_id = %request_id,
@@ -166,11 +432,15 @@ async fn rpc_call(
 }
 
 #[tauri::command]
-async fn rpc_call_batch(
+async fn rpc_call_batch<R: Runtime>(
     batch: BatchRequest,
+    meta: Option<RequestEnvelope>,
+    webview: Webview<R>,
     state: State<'_, RouterState>,
     config: State<'_, ConfigState>,
 ) -> Result<BatchResponse, String> {
+    let batch = batch.with_envelope(resolve_envelope(meta, &webview));
+
     // Use the new batch processor module
     let (response, metrics) = execute_batch(batch, state.0.clone(), &config.0)
         .await
@@ -187,15 +457,86 @@ async fn rpc_call_batch(
     Ok(response)
 }
 
+/// Execute a batch, emitting each result as a [`BatchStreamEvent`] as soon as
+/// it finishes.
+///
+/// Events go to [`batch_event_name`] for the request ID in `meta`, so the
+/// frontend should choose the ID and listen before invoking.
+#[tauri::command]
+async fn rpc_call_batch_stream<R: Runtime>(
+    batch: BatchRequest,
+    meta: Option<RequestEnvelope>,
+    webview: Webview<R>,
+    state: State<'_, RouterState>,
+    config: State<'_, ConfigState>,
+    plugin_config: State<'_, PluginConfigState>,
+) -> Result<BatchMetrics, String> {
+    let envelope = resolve_envelope(meta, &webview);
+    let batch_id = envelope.request_id.clone().unwrap_or_default();
+    let event_name = batch_event_name(&plugin_config.0.subscription_event_prefix, &batch_id);
+    let batch = batch.with_envelope(envelope);
+
+    let emit = |event: BatchStreamEvent| {
+        if let Err(e) = webview.emit(&event_name, &event) {
+            warn!(batch_id = %batch_id, error = %e, "Failed to emit batch event");
+        }
+    };
+
+    let (response, metrics) =
+        execute_batch_streaming(batch, state.0.clone(), &config.0, &|result| {
+            emit(BatchStreamEvent::Result {
+                result: result.clone(),
+            })
+        })
+        .await
+        .map_err(|e| serde_json::to_string(&e).unwrap_or_else(|_| e.to_string()))?;
+
+    emit(BatchStreamEvent::Complete {
+        metrics: metrics.clone(),
+        compensations: response.compensations,
+    });
+
+    debug!(
+        batch_id = %batch_id,
+        total = metrics.total_requests,
+        success = metrics.success_count,
+        errors = metrics.error_count,
+        duration_ms = metrics.duration_ms,
+        "Streaming batch execution completed"
+    );
+
+    Ok(metrics)
+}
+
 #[tauri::command]
 fn rpc_procedures(state: State<'_, RouterState>) -> Vec<String> {
     state.0.procedures()
 }
 
+/// Answer with the router schema by calling [`SCHEMA_PROCEDURE_PATH`].
+///
+/// The schema goes through router middleware (auth, logging, rate limiting)
+/// like any other call, so the router must register it with
+/// [`Router::schema_procedure`](crate::Router::schema_procedure).
+#[tauri::command]
+async fn rpc_schema<R: Runtime>(
+    meta: Option<RequestEnvelope>,
+    webview: Webview<R>,
+    state: State<'_, RouterState>,
+) -> Result<serde_json::Value, String> {
+    let envelope = resolve_envelope(meta, &webview);
+    state
+        .0
+        .call(SCHEMA_PROCEDURE_PATH, serde_json::Value::Null, envelope)
+        .await
+        .map_err(|e| serde_json::to_string(&e).unwrap_or_else(|_| e.to_string()))
+}
+
 #[tauri::command]
 async fn rpc_subscribe<R: Runtime>(
     request: SubscribeRequest,
-    app: AppHandle<R>,
+    meta: Option<RequestEnvelope>,
+    webview: Webview<R>,
     router_state: State<'_, RouterState>,
     sub_state: State<'_, SubscriptionState>,
     config: State<'_, ConfigState>,
@@ -235,6 +576,7 @@ async fn rpc_subscribe<R: Runtime>(
         "Subscription started"
     );
 
+    let envelope = resolve_envelope(meta, &webview);
     let sub_ctx = SubscriptionContext::new(subscription_id, last_event_id);
     let signal = sub_ctx.signal();
     let handle =
@@ -247,14 +589,17 @@ async fn rpc_subscribe<R: Runtime>(
     let router = router_state.0.clone();
     let sub_manager = sub_state.0.clone();
     let path_clone = path.clone();
-    let app_clone = app.clone();
+    let app_clone = webview.app_handle().clone();
     let plugin_config_clone = plugin_config.0.clone();
 
     // Use spawn_subscription for tracked task management
     sub_state
         .0
         .spawn_subscription(subscription_id, async move {
-            match router.subscribe(&path_clone, input, sub_ctx).await {
+            match router
+                .subscribe(&path_clone, input, sub_ctx, envelope)
+                .await
+            {
                 Ok(stream) => {
                     // Use buffered handler if buffering is enabled
                     let _metrics = if plugin_config_clone.is_buffering_enabled() {
@@ -323,6 +668,34 @@ async fn rpc_unsubscribe(
     Ok(result)
 }
 
+/// Cancel a call the invoking window has in flight.
+///
+/// Only the window that issued a request can cancel it.
+#[tauri::command]
+fn rpc_cancel<R: Runtime>(
+    request_id: String,
+    webview: Webview<R>,
+    in_flight: State<'_, InFlightState>,
+) -> bool {
+    let window = webview.window();
+    let window_label = window.label();
+    let cancelled = in_flight.0.cancel(window_label, &request_id);
+    if cancelled {
+        debug!(
+            request_id = %request_id,
+            window = %window_label,
+            "RPC call cancellation requested"
+        );
+    } else {
+        debug!(
+            request_id = %request_id,
+            window = %window_label,
+            "Cancel called for a request that is not in flight"
+        );
+    }
+    cancelled
+}
+
 #[tauri::command]
 async fn rpc_subscription_count(sub_state: State<'_, SubscriptionState>) -> Result<usize, String> {
     Ok(sub_state.0.count())
@@ -383,7 +756,10 @@ where
 ///
 /// # Panics
 ///
-/// Panics if either configuration is invalid.
+/// Panics if either configuration is invalid, or if [`DynRouter::validate`]
+/// rejects the router, e.g. a cache added with
+/// [`Router::cache`](crate::Router::cache) configuring a TTL for a procedure
+/// it never stores.
 ///
 /// # Example
 /// ```rust,ignore
@@ -421,6 +797,9 @@ where
     if let Err(e) = plugin_config.validate() {
         panic!("Invalid Plugin configuration: {}", e);
     }
+    if let Err(e) = router.validate() {
+        panic!("Invalid router configuration: {}", e);
+    }
 
     let router: Arc<dyn DynRouter> = Arc::new(router);
     let subscription_manager = Arc::new(SubscriptionManager::new());
@@ -441,7 +820,10 @@ where
         .invoke_handler(tauri::generate_handler![
             rpc_call,
             rpc_call_batch,
+            rpc_call_batch_stream,
             rpc_procedures,
+            rpc_schema,
+            rpc_cancel,
             rpc_subscribe,
             rpc_unsubscribe,
             rpc_subscription_count
@@ -456,6 +838,7 @@ where
             app.manage(SubscriptionState(subscription_manager.clone()));
             app.manage(ConfigState(config.clone()));
             app.manage(PluginConfigState(plugin_config.clone()));
+            app.manage(InFlightState(InFlightRequests::default()));
             Ok(())
         })
         .on_drop(move |_app| {
@@ -522,3 +905,42 @@ where
         })
         .build()
 }
+
+// =============================================================================
+// Cache Invalidation Events
+// =============================================================================
+
+/// Forward a cache's invalidations to the frontend as Tauri events
+///
+/// Every invalidation of `cache` — from [`invalidation_middleware`](crate::invalidation_middleware),
+/// tag invalidation or explicit calls — is emitted as a
+/// [`CacheInvalidation`](crate::CacheInvalidation) on
+/// [`invalidation_event_name`](crate::invalidation_event_name), using the
+/// plugin's subscription event prefix (`rpc:subscription:cache_invalidation`
+/// by default). Client-side caches listen to it to refetch exactly the
+/// affected queries.
+///
+/// # Example
+/// ```rust,ignore
+/// tauri::Builder::default()
+///     .plugin(tauri_plugin_rpc::init(create_router(cache.clone())))
+///     .setup(move |app| {
+///         tauri_plugin_rpc::emit_cache_invalidations(app.handle(), &cache);
+///         Ok(())
+///     })
+/// ```
+pub fn emit_cache_invalidations<R: Runtime>(app: &tauri::AppHandle<R>, cache: &crate::Cache) {
+    let app = app.clone();
+    cache.on_invalidate(move |invalidation| {
+        // Resolved per event: the plugin state may be managed after registration
+        let event = match app.try_state::<PluginConfigState>() {
+            Some(config) => crate::invalidation_event_name(&config.0.subscription_event_prefix),
+            None => {
+                crate::invalidation_event_name(&PluginConfig::default().subscription_event_prefix)
+            }
+        };
+        if let Err(e) = app.emit(&event, invalidation) {
+            warn!(event = %event, error = %e, "Failed to emit cache invalidation");
+        }
+    });
+}
@@ -40,8 +40,9 @@
 //!         .query(get_profile); // Handler receives Context<AuthenticatedContext>
 //! ```
 
+use crate::cache::CachePolicy;
 use crate::middleware::{MiddlewareFn, Next, ProcedureType, Request, Response};
-use crate::schema::ProcedureMeta;
+use crate::schema::{ProcedureMeta, ToTauriSchema};
 use crate::validation::Validate;
 use crate::{Context, RpcError, RpcResult};
 use serde::Serialize;
@@ -50,6 +51,7 @@ use std::future::Future;
 use std::marker::PhantomData;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::{debug, trace, warn};
 
 /// Type alias for the boxed async handler function.
@@ -69,6 +71,47 @@ pub type ContextTransformer<FromCtx, ToCtx> = Arc<
         + Sync,
 >;
 
+/// Generates the per-procedure settings methods shared by every builder.
+///
+/// Each builder stores these settings in the same `timeout` and `meta` fields,
+/// so the methods are written once here instead of on each builder type.
+macro_rules! procedure_settings_methods {
+    () => {
+        /// Sets the maximum execution time for this procedure.
+        ///
+        /// When the deadline elapses the handler future is dropped and the call
+        /// fails with `RpcErrorCode::Timeout`. Overrides
+        /// `RpcConfig::default_timeout_ms`.
+        #[must_use = "This method returns a new builder and does not modify self"]
+        pub fn timeout(mut self, timeout: Duration) -> Self {
+            self.timeout = Some(timeout);
+            self
+        }
+
+        /// Lets the cache middleware store results, even for a mutation.
+        #[must_use = "This method returns a new builder and does not modify self"]
+        pub fn cacheable(mut self) -> Self {
+            self.meta = Some(
+                self.meta
+                    .unwrap_or_default()
+                    .cache_policy(CachePolicy::Cacheable),
+            );
+            self
+        }
+
+        /// Keeps the cache middleware from ever storing results.
+        #[must_use = "This method returns a new builder and does not modify self"]
+        pub fn no_store(mut self) -> Self {
+            self.meta = Some(
+                self.meta
+                    .unwrap_or_default()
+                    .cache_policy(CachePolicy::NoStore),
+            );
+            self
+        }
+    };
+}
+
 /// Helper macro to generate the handler logic with optional validation and context transformation.
 ///
 /// This macro eliminates code duplication across the four builder types while maintaining
@@ -211,10 +254,11 @@ macro_rules! build_handler_impl {
         Box::pin(async move {
             // Transform context
             trace!("Transforming procedure context");
+            let parent_ctx = $ctx.clone();
             let new_ctx_state = (context_transformer)($ctx).await.inspect_err(|e| {
                 debug!(error_code = %e.code, "Context transformation failed");
             })?;
-            let new_ctx = Context::new(new_ctx_state);
+            let new_ctx = parent_ctx.rebind(new_ctx_state);
 
             // Deserialize input
             trace!("Deserializing procedure input");
@@ -266,10 +310,11 @@ macro_rules! build_handler_impl {
         Box::pin(async move {
             // Transform context
             trace!("Transforming procedure context");
+            let parent_ctx = $ctx.clone();
             let new_ctx_state = (context_transformer)($ctx).await.inspect_err(|e| {
                 debug!(error_code = %e.code, "Context transformation failed");
             })?;
-            let new_ctx = Context::new(new_ctx_state);
+            let new_ctx = parent_ctx.rebind(new_ctx_state);
 
             // Deserialize input
             trace!("Deserializing procedure input");
@@ -346,6 +391,8 @@ where
     pub middleware: Vec<MiddlewareFn<Ctx>>,
     /// OpenAPI metadata for this procedure.
     pub meta: Option<ProcedureMeta>,
+    /// Maximum execution time before the call fails with `TIMEOUT`.
+    pub timeout: Option<Duration>,
 }
 
 /// Builder for configuring individual procedures with middleware, validation, and transformation.
@@ -380,6 +427,8 @@ where
         Option<Arc<dyn Fn(serde_json::Value) -> serde_json::Value + Send + Sync + 'static>>,
     /// OpenAPI metadata for this procedure.
     meta: Option<ProcedureMeta>,
+    /// Maximum execution time for this procedure.
+    timeout: Option<Duration>,
     /// Phantom data for type tracking.
     _phantom: PhantomData<Input>,
 }
@@ -397,6 +446,7 @@ where
             middleware: Vec::new(),
             output_transformer: None,
             meta: None,
+            timeout: None,
             _phantom: PhantomData,
         }
     }
@@ -425,7 +475,7 @@ where
     ///     .query(get_user);
     /// ```
     pub fn meta(mut self, meta: ProcedureMeta) -> Self {
-        self.meta = Some(meta);
+        self.meta = Some(meta.keep_cache_policy(self.meta.as_ref()));
         self
     }
 
@@ -449,6 +499,7 @@ where
             middleware: self.middleware,
             output_transformer: self.output_transformer,
             meta: self.meta,
+            timeout: self.timeout,
             _phantom: PhantomData,
         }
     }
@@ -474,6 +525,7 @@ where
             middleware: self.middleware,
             output_transformer: self.output_transformer,
             meta: self.meta,
+            timeout: self.timeout,
             _phantom: PhantomData,
         }
     }
@@ -518,6 +570,7 @@ where
             output_transformer: self.output_transformer,
             context_transformer: Arc::new(move |ctx| Box::pin(transformer(ctx))),
             meta: self.meta,
+            timeout: self.timeout,
             _phantom: PhantomData,
         }
     }
@@ -563,6 +616,8 @@ where
         self
     }
 
+    procedure_settings_methods!();
+
     /// Sets an output transformer for this procedure.
     ///
     /// The transformer is applied to the handler's output before returning.
@@ -630,6 +685,35 @@ where
         self.build_procedure(ProcedureType::Mutation, handler)
     }
 
+    /// Registers this procedure as a query whose schema is derived from its types.
+    ///
+    /// The input and output reported by `Router::schema` come from `Input`
+    /// and the handler's return type instead of a hand-written [`ProcedureMeta`].
+    pub fn typed_query<H, Fut, Output>(mut self, handler: H) -> RegisteredProcedure<Ctx>
+    where
+        Input: ToTauriSchema,
+        H: Fn(Context<Ctx>, Input) -> Fut + Send + Sync + Clone + 'static,
+        Fut: Future<Output = RpcResult<Output>> + Send + 'static,
+        Output: Serialize + ToTauriSchema + Send + 'static,
+    {
+        self.meta = Some(self.meta.unwrap_or_default().with_types::<Input, Output>());
+        self.query(handler)
+    }
+
+    /// Registers this procedure as a mutation whose schema is derived from its types.
+    ///
+    /// See [`ProcedureBuilder::typed_query`].
+    pub fn typed_mutation<H, Fut, Output>(mut self, handler: H) -> RegisteredProcedure<Ctx>
+    where
+        Input: ToTauriSchema,
+        H: Fn(Context<Ctx>, Input) -> Fut + Send + Sync + Clone + 'static,
+        Fut: Future<Output = RpcResult<Output>> + Send + 'static,
+        Output: Serialize + ToTauriSchema + Send + 'static,
+    {
+        self.meta = Some(self.meta.unwrap_or_default().with_types::<Input, Output>());
+        self.mutation(handler)
+    }
+
     /// Builds the procedure with the given type and handler.
     fn build_procedure<H, Fut, Output>(
         self,
@@ -672,6 +756,7 @@ where
             handler: boxed_handler,
             middleware: self.middleware,
             meta: self.meta,
+            timeout: self.timeout,
         }
     }
 }
@@ -689,6 +774,7 @@ where
     output_transformer:
         Option<Arc<dyn Fn(serde_json::Value) -> serde_json::Value + Send + Sync + 'static>>,
     meta: Option<ProcedureMeta>,
+    timeout: Option<Duration>,
     _phantom: PhantomData<Input>,
 }
 
@@ -720,6 +806,8 @@ where
         self
     }
 
+    procedure_settings_methods!();
+
     /// Sets an output transformer for this procedure.
     pub fn output<F>(mut self, transformer: F) -> Self
     where
@@ -749,6 +837,35 @@ where
         self.build_validated_procedure(ProcedureType::Mutation, handler)
     }
 
+    /// Registers this procedure as a query whose schema is derived from its types.
+    ///
+    /// The input and output reported by `Router::schema` come from `Input`
+    /// and the handler's return type instead of a hand-written [`ProcedureMeta`].
+    pub fn typed_query<H, Fut, Output>(mut self, handler: H) -> RegisteredProcedure<Ctx>
+    where
+        Input: ToTauriSchema,
+        H: Fn(Context<Ctx>, Input) -> Fut + Send + Sync + Clone + 'static,
+        Fut: Future<Output = RpcResult<Output>> + Send + 'static,
+        Output: Serialize + ToTauriSchema + Send + 'static,
+    {
+        self.meta = Some(self.meta.unwrap_or_default().with_types::<Input, Output>());
+        self.query(handler)
+    }
+
+    /// Registers this procedure as a mutation whose schema is derived from its types.
+    ///
+    /// See [`ValidatedProcedureBuilder::typed_query`].
+    pub fn typed_mutation<H, Fut, Output>(mut self, handler: H) -> RegisteredProcedure<Ctx>
+    where
+        Input: ToTauriSchema,
+        H: Fn(Context<Ctx>, Input) -> Fut + Send + Sync + Clone + 'static,
+        Fut: Future<Output = RpcResult<Output>> + Send + 'static,
+        Output: Serialize + ToTauriSchema + Send + 'static,
+    {
+        self.meta = Some(self.meta.unwrap_or_default().with_types::<Input, Output>());
+        self.mutation(handler)
+    }
+
     /// Builds the validated procedure with the given type and handler.
     fn build_validated_procedure<H, Fut, Output>(
         self,
@@ -792,6 +909,7 @@ where
             handler: boxed_handler,
             middleware: self.middleware,
             meta: self.meta,
+            timeout: self.timeout,
         }
     }
 }
@@ -816,6 +934,7 @@ where
         Option<Arc<dyn Fn(serde_json::Value) -> serde_json::Value + Send + Sync + 'static>>,
     context_transformer: ContextTransformer<OrigCtx, NewCtx>,
     meta: Option<ProcedureMeta>,
+    timeout: Option<Duration>,
     _phantom: PhantomData<(OrigCtx, NewCtx)>,
 }
 
@@ -851,6 +970,8 @@ where
         self
     }
 
+    procedure_settings_methods!();
+
     /// Sets an output transformer for this procedure.
     pub fn output<F>(mut self, transformer: F) -> Self
     where
@@ -871,6 +992,7 @@ where
             output_transformer: self.output_transformer,
             context_transformer: self.context_transformer,
             meta: self.meta,
+            timeout: self.timeout,
             _phantom: PhantomData,
         }
     }
@@ -888,6 +1010,7 @@ where
             output_transformer: self.output_transformer,
             context_transformer: self.context_transformer,
             meta: self.meta,
+            timeout: self.timeout,
             _phantom: PhantomData,
         }
     }
@@ -905,6 +1028,7 @@ where
         Option<Arc<dyn Fn(serde_json::Value) -> serde_json::Value + Send + Sync + 'static>>,
     context_transformer: ContextTransformer<OrigCtx, NewCtx>,
     meta: Option<ProcedureMeta>,
+    timeout: Option<Duration>,
     _phantom: PhantomData<(OrigCtx, NewCtx, Input)>,
 }
 
@@ -931,6 +1055,8 @@ where
         self
     }
 
+    procedure_settings_methods!();
+
     /// Sets an output transformer for this procedure.
     pub fn output<F>(mut self, transformer: F) -> Self
     where
@@ -1009,6 +1135,7 @@ where
             handler: boxed_handler,
             middleware: self.middleware,
             meta: self.meta,
+            timeout: self.timeout,
         }
     }
 }
@@ -1060,6 +1187,7 @@ where
         Option<Arc<dyn Fn(serde_json::Value) -> serde_json::Value + Send + Sync + 'static>>,
     context_transformer: ContextTransformer<OrigCtx, NewCtx>,
     meta: Option<ProcedureMeta>,
+    timeout: Option<Duration>,
     _phantom: PhantomData<(OrigCtx, NewCtx, Input)>,
 }
 
@@ -1106,6 +1234,8 @@ where
         self
     }
 
+    procedure_settings_methods!();
+
     /// Sets an output transformer for this procedure.
     ///
     /// The transformer is applied to the handler's output before returning.
@@ -1195,6 +1325,7 @@ where
             handler: boxed_handler,
             middleware: self.middleware,
             meta: self.meta,
+            timeout: self.timeout,
         }
     }
 }
@@ -4,6 +4,15 @@
 //! - Fixed window: Simple counter reset at fixed intervals
 //! - Sliding window: Weighted average of current and previous window
 //! - Token bucket: Smooth rate limiting with burst capacity
+//! - GCRA: Exact request spacing with a burst tolerance, in constant memory
+//! - Leaky bucket: Delays requests to an even pace, up to a maximum wait
+//!
+//! Limits are layered: a global ceiling, per-namespace budgets, per-procedure
+//! limits and per-client quotas are checked together, and a request is
+//! rejected if any applicable [`RateLimitLayer`] is exhausted.
+//!
+//! A call uses one unit of each layer unless its procedure declares a
+//! [`RateLimitCost`], so expensive endpoints can draw more of the quota.
 //!
 //! # Example
 //!
@@ -25,11 +34,19 @@
 //! ```
 
 use crate::middleware::{MiddlewareFn, Request, from_fn};
+use crate::pattern::PathPattern;
+use crate::types::PaginationInput;
 use crate::{Context, Next, RpcError};
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::num::NonZeroUsize;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
+use tokio::time::Instant;
 
 // =============================================================================
 // Rate Limit Strategy
@@ -49,6 +66,83 @@ pub enum RateLimitStrategy {
         /// Tokens added per second
         refill_rate: f64,
     },
+    /// Generic cell rate algorithm: requests are spaced `window / requests`
+    /// apart, and up to `burst` may arrive at once
+    Gcra {
+        /// Requests allowed back to back
+        burst: u32,
+    },
+    /// Leaky bucket queue: requests run `window / requests` apart, and early
+    /// requests are delayed instead of rejected while their wait is short
+    LeakyBucket {
+        /// Longest a request is delayed before it is rejected
+        max_wait: Duration,
+    },
+}
+
+// =============================================================================
+// Rate Limit Cost
+// =============================================================================
+
+/// Custom function computing the cost of a call from its input
+pub type RateLimitCostFn = Arc<dyn Fn(&serde_json::Value) -> u32 + Send + Sync>;
+
+/// Units of quota a call uses
+///
+/// Calls cost one unit by default. A cost of zero makes a call free; a cost
+/// above what a limit allows at once is refused with `BAD_REQUEST`, since no
+/// amount of waiting would let it through.
+#[derive(Clone)]
+pub enum RateLimitCost {
+    /// The same cost for every call
+    Fixed(u32),
+    /// Cost computed from the call input
+    FromInput(RateLimitCostFn),
+}
+
+impl RateLimitCost {
+    /// Compute the cost from the call input
+    pub fn from_input<F>(cost_fn: F) -> Self
+    where
+        F: Fn(&serde_json::Value) -> u32 + Send + Sync + 'static,
+    {
+        Self::FromInput(Arc::new(cost_fn))
+    }
+
+    /// Cost one unit per requested item of a [`PaginationInput`] page
+    ///
+    /// Reads the `limit` field with the same default and bounds as
+    /// [`PaginationInput::limit`].
+    pub fn page_limit() -> Self {
+        Self::from_input(|input| {
+            serde_json::from_value::<PaginationInput>(input.clone())
+                .map(|page| page.limit())
+                .unwrap_or(1)
+        })
+    }
+
+    /// Cost of a call with the given input
+    pub fn of(&self, input: &serde_json::Value) -> u32 {
+        match self {
+            Self::Fixed(cost) => *cost,
+            Self::FromInput(cost_fn) => cost_fn(input),
+        }
+    }
+}
+
+impl From<u32> for RateLimitCost {
+    fn from(cost: u32) -> Self {
+        Self::Fixed(cost)
+    }
+}
+
+impl fmt::Debug for RateLimitCost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fixed(cost) => f.debug_tuple("Fixed").field(cost).finish(),
+            Self::FromInput(_) => f.write_str("FromInput(..)"),
+        }
+    }
 }
 
 // =============================================================================
@@ -103,12 +197,76 @@ impl RateLimit {
         }
     }
 
+    /// Create a rate limit with GCRA strategy, allowing bursts of `burst`
+    /// requests (at least one)
+    pub fn gcra(requests: u32, window: Duration, burst: u32) -> Self {
+        Self {
+            requests,
+            window,
+            strategy: RateLimitStrategy::Gcra {
+                burst: burst.max(1),
+            },
+        }
+    }
+
+    /// Create a rate limit with leaky bucket strategy, delaying requests by
+    /// up to `max_wait`
+    pub fn leaky_bucket(requests: u32, window: Duration, max_wait: Duration) -> Self {
+        Self {
+            requests,
+            window,
+            strategy: RateLimitStrategy::LeakyBucket { max_wait },
+        }
+    }
+
     /// Set the strategy
     #[must_use = "This method returns a new RateLimit and does not modify self"]
     pub fn with_strategy(mut self, strategy: RateLimitStrategy) -> Self {
         self.strategy = strategy;
         self
     }
+
+    /// Time between two requests at the sustained rate
+    fn emission_interval(&self) -> Duration {
+        self.window / self.requests.max(1)
+    }
+
+    /// Most units a single call can draw
+    ///
+    /// The burst for GCRA and what fits in `max_wait` for a leaky bucket;
+    /// `requests` otherwise.
+    fn capacity(&self) -> u32 {
+        match &self.strategy {
+            RateLimitStrategy::Gcra { burst } => *burst,
+            RateLimitStrategy::LeakyBucket { max_wait } => {
+                let interval = self.emission_interval().as_nanos().max(1);
+                u32::try_from(max_wait.as_nanos() / interval)
+                    .unwrap_or(u32::MAX)
+                    .saturating_add(1)
+            }
+            _ => self.requests,
+        }
+    }
+
+    /// How long after its last use a key's state can still limit a request
+    fn retention(&self) -> Duration {
+        match &self.strategy {
+            RateLimitStrategy::FixedWindow => self.window,
+            // The previous window still weighs on the current one
+            RateLimitStrategy::SlidingWindow => self.window.saturating_mul(2),
+            RateLimitStrategy::TokenBucket { refill_rate } => {
+                let refill = self.requests as f64 / refill_rate;
+                Duration::try_from_secs_f64(refill)
+                    .unwrap_or(Duration::MAX)
+                    .max(self.window)
+            }
+            RateLimitStrategy::Gcra { burst } => self
+                .emission_interval()
+                .saturating_mul(*burst)
+                .max(self.window),
+            RateLimitStrategy::LeakyBucket { max_wait } => self.window.saturating_add(*max_wait),
+        }
+    }
 }
 
 impl Default for RateLimit {
@@ -125,6 +283,26 @@ pub struct RateLimitConfig {
     pub default_limit: Option<RateLimit>,
     /// Per-procedure limits (path -> limit)
     pub procedure_limits: HashMap<String, RateLimit>,
+    /// Limits for paths matching a pattern, checked in order
+    pub pattern_limits: Vec<(PathPattern, RateLimit)>,
+    /// Per-procedure call costs (path -> cost); other calls cost one unit
+    pub procedure_costs: HashMap<String, RateLimitCost>,
+    /// Ceiling shared by all procedures and clients
+    pub global_limit: Option<RateLimit>,
+    /// Budgets shared by all procedures matching a pattern, per client
+    pub namespace_limits: Vec<(PathPattern, RateLimit)>,
+    /// Quota of each client across all procedures
+    pub client_limit: Option<RateLimit>,
+    /// Client quotas by role, replacing `client_limit` (first role held wins)
+    pub role_limits: Vec<(String, RateLimit)>,
+    /// Client quotas by client id, replacing role and default quotas
+    pub client_overrides: HashMap<String, RateLimit>,
+    /// How long an unused key's state is kept before cleanup drops it
+    pub idle_ttl: Option<Duration>,
+    /// Maximum number of keys with tracked state; least recently used are dropped
+    pub max_tracked_keys: Option<usize>,
+    /// Whether the middleware adds the remaining quota to the request envelope
+    pub expose_quota: bool,
     /// Whether rate limiting is enabled
     pub enabled: bool,
 }
@@ -135,6 +313,16 @@ impl RateLimitConfig {
         Self {
             default_limit: None,
             procedure_limits: HashMap::new(),
+            pattern_limits: Vec::new(),
+            procedure_costs: HashMap::new(),
+            global_limit: None,
+            namespace_limits: Vec::new(),
+            client_limit: None,
+            role_limits: Vec::new(),
+            client_overrides: HashMap::new(),
+            idle_ttl: None,
+            max_tracked_keys: None,
+            expose_quota: false,
             enabled: true,
         }
     }
@@ -153,6 +341,123 @@ impl RateLimitConfig {
         self
     }
 
+    /// Add a rate limit for every procedure matching a pattern
+    ///
+    /// Uses the shared [pattern syntax](crate::pattern), e.g. `user.*` or
+    /// `{search,export}.**`. Each matching procedure is limited on its own.
+    /// Exact procedure limits take precedence, then the first matching pattern.
+    #[must_use = "This method returns a new RateLimitConfig and does not modify self"]
+    pub fn with_pattern_limit(mut self, pattern: impl Into<String>, limit: RateLimit) -> Self {
+        self.pattern_limits
+            .push((PathPattern::compile(&pattern.into()), limit));
+        self
+    }
+
+    /// Set the units of quota each call to a procedure uses
+    ///
+    /// The cost is drawn from every layer the call is checked against.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let config = RateLimitConfig::new()
+    ///     .with_procedure_cost("report.export", 10)
+    ///     .with_procedure_cost("user.list", RateLimitCost::page_limit())
+    ///     .with_procedure_cost(
+    ///         "user.batchGet",
+    ///         RateLimitCost::from_input(|input| input["ids"].as_array().map_or(1, |ids| ids.len() as u32)),
+    ///     );
+    /// ```
+    #[must_use = "This method returns a new RateLimitConfig and does not modify self"]
+    pub fn with_procedure_cost(
+        mut self,
+        path: impl Into<String>,
+        cost: impl Into<RateLimitCost>,
+    ) -> Self {
+        self.procedure_costs.insert(path.into(), cost.into());
+        self
+    }
+
+    /// Set a ceiling shared by all procedures and clients
+    #[must_use = "This method returns a new RateLimitConfig and does not modify self"]
+    pub fn with_global_limit(mut self, limit: RateLimit) -> Self {
+        self.global_limit = Some(limit);
+        self
+    }
+
+    /// Add a budget shared by all procedures matching a pattern
+    ///
+    /// Unlike [`with_pattern_limit`](Self::with_pattern_limit), every call to
+    /// a matching procedure draws from one budget per client. Every matching
+    /// namespace applies.
+    #[must_use = "This method returns a new RateLimitConfig and does not modify self"]
+    pub fn with_namespace_limit(mut self, pattern: impl Into<String>, limit: RateLimit) -> Self {
+        self.namespace_limits
+            .push((PathPattern::compile(&pattern.into()), limit));
+        self
+    }
+
+    /// Set the quota of each client across all procedures
+    #[must_use = "This method returns a new RateLimitConfig and does not modify self"]
+    pub fn with_client_limit(mut self, limit: RateLimit) -> Self {
+        self.client_limit = Some(limit);
+        self
+    }
+
+    /// Give clients holding a role their own quota across all procedures
+    ///
+    /// Roles come from the [`AuthResult`](crate::auth::AuthResult) attached
+    /// by the auth middleware. When a client holds several configured roles,
+    /// the role added first applies.
+    #[must_use = "This method returns a new RateLimitConfig and does not modify self"]
+    pub fn with_role_limit(mut self, role: impl Into<String>, limit: RateLimit) -> Self {
+        self.role_limits.push((role.into(), limit));
+        self
+    }
+
+    /// Give one client its own quota across all procedures
+    #[must_use = "This method returns a new RateLimitConfig and does not modify self"]
+    pub fn with_client_override(mut self, client_id: impl Into<String>, limit: RateLimit) -> Self {
+        self.client_overrides.insert(client_id.into(), limit);
+        self
+    }
+
+    /// Drop the state of keys unused for `ttl`
+    ///
+    /// Applied by [`RateLimiter::cleanup_idle`] and the task started with
+    /// [`RateLimiter::start_cleanup_task`]. A dropped key starts over with a
+    /// full quota, so keys are kept for at least their limit's window even
+    /// when `ttl` is shorter.
+    #[must_use = "This method returns a new RateLimitConfig and does not modify self"]
+    pub fn with_idle_ttl(mut self, ttl: Duration) -> Self {
+        self.idle_ttl = Some(ttl);
+        self
+    }
+
+    /// Track the state of at most `max` keys
+    ///
+    /// A key is one procedure, namespace or client quota for one client.
+    /// Past the cap, the least recently used key is dropped. The cap is
+    /// raised to the number of layers a single request can use, so checking
+    /// one request never drops the state of another of its layers.
+    #[must_use = "This method returns a new RateLimitConfig and does not modify self"]
+    pub fn with_max_tracked_keys(mut self, max: usize) -> Self {
+        self.max_tracked_keys = Some(max);
+        self
+    }
+
+    /// Add the remaining quota of allowed requests to the request envelope
+    ///
+    /// [`rate_limit_middleware`] then sets the `x-ratelimit-limit`,
+    /// `x-ratelimit-remaining` and `x-ratelimit-reset` (Unix time in
+    /// milliseconds) metadata entries for the rest of the chain, taken from
+    /// the layer with the least quota left.
+    #[must_use = "This method returns a new RateLimitConfig and does not modify self"]
+    pub fn with_quota_in_envelope(mut self, expose: bool) -> Self {
+        self.expose_quota = expose;
+        self
+    }
+
     /// Enable or disable rate limiting
     #[must_use = "This method returns a new RateLimitConfig and does not modify self"]
     pub fn with_enabled(mut self, enabled: bool) -> Self {
@@ -164,8 +469,48 @@ impl RateLimitConfig {
     pub fn get_limit(&self, path: &str) -> Option<&RateLimit> {
         self.procedure_limits
             .get(path)
+            .or_else(|| {
+                self.pattern_limits
+                    .iter()
+                    .find(|(pattern, _)| pattern.matches(path))
+                    .map(|(_, limit)| limit)
+            })
             .or(self.default_limit.as_ref())
     }
+
+    /// Get the cost of a call to a procedure
+    pub fn cost_of(&self, path: &str, input: &serde_json::Value) -> u32 {
+        self.procedure_costs
+            .get(path)
+            .map_or(1, |cost| cost.of(input))
+    }
+
+    /// Most layers a single request can be checked against
+    fn max_layers(&self) -> usize {
+        let procedure = self.default_limit.is_some()
+            || !self.procedure_limits.is_empty()
+            || !self.pattern_limits.is_empty();
+        let client = self.client_limit.is_some()
+            || !self.role_limits.is_empty()
+            || !self.client_overrides.is_empty();
+        usize::from(self.global_limit.is_some())
+            + self.namespace_limits.len()
+            + usize::from(procedure)
+            + usize::from(client)
+    }
+
+    /// Get the quota of a client across all procedures
+    pub fn get_client_limit(&self, client_id: &str, roles: &[String]) -> Option<&RateLimit> {
+        self.client_overrides
+            .get(client_id)
+            .or_else(|| {
+                self.role_limits
+                    .iter()
+                    .find(|(role, _)| roles.contains(role))
+                    .map(|(_, limit)| limit)
+            })
+            .or(self.client_limit.as_ref())
+    }
 }
 
 impl Default for RateLimitConfig {
@@ -174,6 +519,45 @@ impl Default for RateLimitConfig {
     }
 }
 
+// =============================================================================
+// Rate Limit Layers
+// =============================================================================
+
+/// A layer of rate limiting, named in the details of rate limit errors
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RateLimitLayer {
+    /// Ceiling shared by all procedures and clients
+    Global,
+    /// Budget shared by the procedures matching a pattern
+    Namespace(String),
+    /// Limit of a single procedure
+    Procedure,
+    /// Quota of a client across all procedures
+    Client,
+}
+
+impl RateLimitLayer {
+    /// Name of the layer, as reported in error details
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Global => "global",
+            Self::Namespace(_) => "namespace",
+            Self::Procedure => "procedure",
+            Self::Client => "client",
+        }
+    }
+}
+
+impl fmt::Display for RateLimitLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Namespace(pattern) => write!(f, "namespace {}", pattern),
+            layer => f.write_str(layer.name()),
+        }
+    }
+}
+
 // =============================================================================
 // Rate Limit State
 // =============================================================================
@@ -235,24 +619,70 @@ impl TokenBucketState {
     }
 }
 
+/// State for GCRA and leaky bucket rate limiting
+#[derive(Debug, Clone)]
+struct ScheduleState {
+    /// Theoretical arrival time: when the next request would be on pace
+    tat: Instant,
+}
+
+impl ScheduleState {
+    fn new() -> Self {
+        Self {
+            tat: Instant::now(),
+        }
+    }
+
+    /// How far ahead of the sustained pace admitted requests are
+    fn backlog(&self) -> Duration {
+        self.tat.saturating_duration_since(Instant::now())
+    }
+}
+
 /// Combined state for any strategy
 #[derive(Debug, Clone)]
 enum RateLimitState {
     FixedWindow(FixedWindowState),
     SlidingWindow(SlidingWindowState),
     TokenBucket(TokenBucketState),
+    Schedule(ScheduleState),
+}
+
+/// State of one key, with when it was last checked or recorded
+#[derive(Debug, Clone)]
+struct TrackedState {
+    state: RateLimitState,
+    last_used: Instant,
+    /// How long the state matters after `last_used`, see [`RateLimit::retention`]
+    retention: Duration,
 }
 
-/// Key for rate limit state (procedure path + client identifier)
+/// Rate limit state by key, least recently used first to go
+type StateMap = LruCache<StateKey, TrackedState>;
+
+/// Key for rate limit state (layer + procedure path + client identifier)
+///
+/// `path` is only set for the procedure layer and `client_id` is empty for
+/// the global layer.
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 struct StateKey {
+    layer: RateLimitLayer,
     path: String,
     client_id: String,
 }
 
 impl StateKey {
     fn new(path: impl Into<String>, client_id: impl Into<String>) -> Self {
+        Self::for_layer(RateLimitLayer::Procedure, path, client_id)
+    }
+
+    fn for_layer(
+        layer: RateLimitLayer,
+        path: impl Into<String>,
+        client_id: impl Into<String>,
+    ) -> Self {
         Self {
+            layer,
             path: path.into(),
             client_id: client_id.into(),
         }
@@ -263,115 +693,220 @@ impl StateKey {
 // Rate Limiter
 // =============================================================================
 
+/// Eviction counters of a [`RateLimiter`]
+#[derive(Debug, Default)]
+struct EvictionMetrics {
+    idle: AtomicU64,
+    capacity: AtomicU64,
+}
+
 /// Thread-safe rate limiter
+///
+/// State is kept per key in memory. Configure
+/// [`RateLimitConfig::with_idle_ttl`] and
+/// [`RateLimitConfig::with_max_tracked_keys`] to bound it in long-running
+/// apps with many clients.
 pub struct RateLimiter {
     config: RateLimitConfig,
-    state: Arc<RwLock<HashMap<StateKey, RateLimitState>>>,
+    state: Arc<RwLock<StateMap>>,
+    evictions: Arc<EvictionMetrics>,
 }
 
 impl RateLimiter {
     /// Create a new rate limiter with the given configuration
     pub fn new(config: RateLimitConfig) -> Self {
+        let state = match config.max_tracked_keys {
+            Some(max) => {
+                let max = max.max(config.max_layers()).max(1);
+                LruCache::new(NonZeroUsize::new(max).unwrap())
+            }
+            None => LruCache::unbounded(),
+        };
         Self {
             config,
-            state: Arc::new(RwLock::new(HashMap::new())),
+            state: Arc::new(RwLock::new(state)),
+            evictions: Arc::default(),
         }
     }
 
     /// Check if a request is allowed
     ///
     /// Returns `Ok(())` if allowed, or `Err(RpcError)` with RATE_LIMITED code
-    /// and retry_after in details if rate limited.
+    /// if rate limited. The details of the error carry the usage of the
+    /// exhausted layer: `limit`, `remaining`, `reset_at` (Unix time in
+    /// milliseconds), `retry_after_ms` and `retry_after_secs`. They also name
+    /// the `layer`, and the `namespace` pattern for namespaces.
+    ///
+    /// The request costs one unit; client quotas by role and call costs
+    /// apply through [`check_with_roles`](Self::check_with_roles) and
+    /// [`check_with_cost`](Self::check_with_cost). Use
+    /// [`check_delay`](Self::check_delay) to learn how long a
+    /// [`LeakyBucket`](RateLimitStrategy::LeakyBucket) layer would hold it.
     pub async fn check(&self, path: &str, client_id: &str) -> Result<(), RpcError> {
-        if !self.config.enabled {
-            return Ok(());
-        }
-
-        let limit = match self.config.get_limit(path) {
-            Some(l) => l,
-            None => return Ok(()), // No limit configured
-        };
+        self.check_with_roles(path, client_id, &[]).await
+    }
 
-        let key = StateKey::new(path, client_id);
-        let mut state_map = self.state.write().await;
+    /// Check if a request from a client holding `roles` is allowed
+    ///
+    /// Works like [`check`](Self::check), with the client quota of `roles`.
+    pub async fn check_with_roles(
+        &self,
+        path: &str,
+        client_id: &str,
+        roles: &[String],
+    ) -> Result<(), RpcError> {
+        self.check_with_cost(path, client_id, roles, 1).await
+    }
 
-        let state = state_map
-            .entry(key)
-            .or_insert_with(|| Self::create_initial_state(limit));
+    /// Check if a call using `cost` units of quota is allowed
+    ///
+    /// Works like [`check_with_roles`](Self::check_with_roles); pair it with
+    /// [`record_with_cost`](Self::record_with_cost).
+    pub async fn check_with_cost(
+        &self,
+        path: &str,
+        client_id: &str,
+        roles: &[String],
+        cost: u32,
+    ) -> Result<(), RpcError> {
+        self.check_delay(path, client_id, roles, cost)
+            .await
+            .map(|_| ())
+    }
 
-        match &limit.strategy {
-            RateLimitStrategy::FixedWindow => self.check_fixed_window(state, limit),
-            RateLimitStrategy::SlidingWindow => self.check_sliding_window(state, limit),
-            RateLimitStrategy::TokenBucket { refill_rate } => {
-                self.check_token_bucket(state, limit, *refill_rate)
-            }
+    /// Check if a call using `cost` units of quota is allowed, and how long
+    /// to wait before running it
+    ///
+    /// Works like [`check_with_cost`](Self::check_with_cost). The delay is
+    /// zero unless a [`LeakyBucket`](RateLimitStrategy::LeakyBucket) layer
+    /// queues the call; callers honour it before running the call.
+    pub async fn check_delay(
+        &self,
+        path: &str,
+        client_id: &str,
+        roles: &[String],
+        cost: u32,
+    ) -> Result<Duration, RpcError> {
+        let layers = self.layers(path, client_id, roles);
+        if layers.is_empty() {
+            return Ok(Duration::ZERO); // No limit configured
         }
+
+        let mut state_map = self.state.write().await;
+        self.check_layers(&mut state_map, &layers, cost)
     }
 
     /// Record a request (call after successful check if using two-phase)
     pub async fn record(&self, path: &str, client_id: &str) {
-        if !self.config.enabled {
-            return;
-        }
+        self.record_with_roles(path, client_id, &[]).await;
+    }
+
+    /// Record a request from a client holding `roles`
+    ///
+    /// The counterpart of [`check_with_roles`](Self::check_with_roles).
+    pub async fn record_with_roles(&self, path: &str, client_id: &str, roles: &[String]) {
+        self.record_with_cost(path, client_id, roles, 1).await;
+    }
 
-        if self.config.get_limit(path).is_none() {
+    /// Record a call using `cost` units of quota
+    ///
+    /// The counterpart of [`check_with_cost`](Self::check_with_cost).
+    pub async fn record_with_cost(&self, path: &str, client_id: &str, roles: &[String], cost: u32) {
+        let layers = self.layers(path, client_id, roles);
+        if layers.is_empty() {
             return;
         }
 
-        let key = StateKey::new(path, client_id);
         let mut state_map = self.state.write().await;
-
-        if let Some(state) = state_map.get_mut(&key) {
-            match state {
-                RateLimitState::FixedWindow(s) => s.count += 1,
-                RateLimitState::SlidingWindow(s) => s.current_count += 1,
-                RateLimitState::TokenBucket(s) => s.tokens -= 1.0,
-            }
-        }
+        self.record_layers(&mut state_map, &layers, cost);
     }
 
     /// Check and record in one operation (most common use case)
     pub async fn check_and_record(&self, path: &str, client_id: &str) -> Result<(), RpcError> {
-        self.check(path, client_id).await?;
-        self.record(path, client_id).await;
-        Ok(())
+        self.check_and_record_with_roles(path, client_id, &[])
+            .await
+            .map(|_| ())
     }
 
-    /// Get current usage for a path/client combination
-    pub async fn get_usage(&self, path: &str, client_id: &str) -> Option<RateLimitUsage> {
-        let limit = self.config.get_limit(path)?;
-        let key = StateKey::new(path, client_id);
-        let state_map = self.state.read().await;
+    /// Check and record a request from a client holding `roles`
+    ///
+    /// Roles select the client quota configured with
+    /// [`RateLimitConfig::with_role_limit`]. All layers are checked and
+    /// recorded under one lock, so a rejected request uses up no layer.
+    ///
+    /// Returns the usage of the layer with the least quota left, or `None`
+    /// when no limit applies. Requests queued by a
+    /// [`LeakyBucket`](RateLimitStrategy::LeakyBucket) layer return once
+    /// their turn comes.
+    pub async fn check_and_record_with_roles(
+        &self,
+        path: &str,
+        client_id: &str,
+        roles: &[String],
+    ) -> Result<Option<RateLimitUsage>, RpcError> {
+        self.check_and_record_with_cost(path, client_id, roles, 1)
+            .await
+    }
 
-        let state = state_map.get(&key)?;
-        let (used, remaining) = match state {
-            RateLimitState::FixedWindow(s) => {
-                let elapsed = s.window_start.elapsed();
-                if elapsed >= limit.window {
-                    (0, limit.requests)
-                } else {
-                    (s.count, limit.requests.saturating_sub(s.count))
-                }
-            }
-            RateLimitState::SlidingWindow(s) => {
-                let elapsed = s.window_start.elapsed();
-                let weight = 1.0 - (elapsed.as_secs_f64() / limit.window.as_secs_f64()).min(1.0);
-                let weighted = (s.previous_count as f64 * weight) + s.current_count as f64;
-                let used = weighted.ceil() as u32;
-                (used, limit.requests.saturating_sub(used))
-            }
-            RateLimitState::TokenBucket(s) => {
-                let remaining = s.tokens.max(0.0) as u32;
-                (limit.requests.saturating_sub(remaining), remaining)
-            }
+    /// Check and record a call using `cost` units of quota
+    ///
+    /// Works like [`check_and_record_with_roles`](Self::check_and_record_with_roles),
+    /// drawing `cost` units from every layer. Use
+    /// [`RateLimitConfig::cost_of`] to get the configured cost of a call.
+    ///
+    /// A call costing more than a layer allows at once, its `requests` or
+    /// its GCRA burst, fails with a non-retryable `BAD_REQUEST` whose
+    /// details report the `cost`, the `limit` and the `layer`.
+    pub async fn check_and_record_with_cost(
+        &self,
+        path: &str,
+        client_id: &str,
+        roles: &[String],
+        cost: u32,
+    ) -> Result<Option<RateLimitUsage>, RpcError> {
+        let layers = self.layers(path, client_id, roles);
+        if layers.is_empty() {
+            return Ok(None);
+        }
+
+        let (delay, usage) = {
+            let mut state_map = self.state.write().await;
+            let delay = self.check_layers(&mut state_map, &layers, cost)?;
+            self.record_layers(&mut state_map, &layers, cost);
+            (delay, Self::tightest_usage(&state_map, &layers))
         };
 
-        Some(RateLimitUsage {
-            limit: limit.requests,
-            used,
-            remaining,
-            reset_at: limit.window,
-        })
+        if !delay.is_zero() {
+            tracing::debug!(
+                path = %path,
+                client_id = %client_id,
+                delay_ms = %delay.as_millis(),
+                "Request delayed by rate limit"
+            );
+            tokio::time::sleep(delay).await;
+        }
+        Ok(usage)
+    }
+
+    /// Get current usage for a path/client combination
+    ///
+    /// Reports the layer with the least quota left, or `None` before any
+    /// layer has seen a request. Client quotas by role apply through
+    /// [`get_usage_with_roles`](Self::get_usage_with_roles).
+    pub async fn get_usage(&self, path: &str, client_id: &str) -> Option<RateLimitUsage> {
+        self.get_usage_with_roles(path, client_id, &[]).await
+    }
+
+    /// Get current usage for a client holding `roles`
+    pub async fn get_usage_with_roles(
+        &self,
+        path: &str,
+        client_id: &str,
+        roles: &[String],
+    ) -> Option<RateLimitUsage> {
+        let layers = self.layers(path, client_id, roles);
+        let state_map = self.state.read().await;
+        Self::tightest_usage(&state_map, &layers)
     }
 
     /// Clear all rate limit state (useful for testing)
@@ -383,56 +918,285 @@ impl RateLimiter {
     /// Clear state for a specific client
     pub async fn clear_client(&self, client_id: &str) {
         let mut state_map = self.state.write().await;
-        state_map.retain(|k, _| k.client_id != client_id);
-    }
-
-    // -------------------------------------------------------------------------
-    // Strategy Implementations
-    // -------------------------------------------------------------------------
-
-    fn create_initial_state(limit: &RateLimit) -> RateLimitState {
-        match &limit.strategy {
-            RateLimitStrategy::FixedWindow => RateLimitState::FixedWindow(FixedWindowState::new()),
-            RateLimitStrategy::SlidingWindow => {
-                RateLimitState::SlidingWindow(SlidingWindowState::new())
-            }
-            RateLimitStrategy::TokenBucket { .. } => {
-                RateLimitState::TokenBucket(TokenBucketState::new(limit.requests))
-            }
+        let keys: Vec<StateKey> = state_map
+            .iter()
+            .filter(|(k, _)| k.client_id == client_id)
+            .map(|(k, _)| k.clone())
+            .collect();
+        for key in keys {
+            state_map.pop(&key);
         }
     }
 
-    fn check_fixed_window(
-        &self,
-        state: &mut RateLimitState,
-        limit: &RateLimit,
-    ) -> Result<(), RpcError> {
-        let RateLimitState::FixedWindow(s) = state else {
-            return Ok(());
+    /// Drop the state of keys unused for the configured idle TTL
+    ///
+    /// A key is kept past the TTL while its limit's window could still
+    /// refuse a request, so cleanup never hands a client a fresh quota
+    /// early. Returns the number of keys dropped; does nothing without
+    /// [`RateLimitConfig::with_idle_ttl`].
+    pub async fn cleanup_idle(&self) -> usize {
+        let Some(idle_ttl) = self.config.idle_ttl else {
+            return 0;
         };
 
-        let elapsed = s.window_start.elapsed();
-
-        // Reset window if expired
-        if elapsed >= limit.window {
-            s.count = 0;
-            s.window_start = Instant::now();
+        let mut state_map = self.state.write().await;
+        let idle: Vec<StateKey> = state_map
+            .iter()
+            .filter(|(_, tracked)| tracked.last_used.elapsed() >= idle_ttl.max(tracked.retention))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &idle {
+            state_map.pop(key);
         }
+        let removed = idle.len();
 
-        // Check limit
-        if s.count >= limit.requests {
-            let retry_after = limit.window.saturating_sub(elapsed);
-            return Err(Self::rate_limited_error(retry_after));
+        if removed > 0 {
+            self.evictions
+                .idle
+                .fetch_add(removed as u64, Ordering::Relaxed);
+            tracing::debug!(removed = %removed, "idle rate limit state cleaned up");
         }
-
-        Ok(())
+        removed
     }
 
-    fn check_sliding_window(
-        &self,
-        state: &mut RateLimitState,
+    /// Start a background task dropping idle state every `interval`
+    ///
+    /// Works like [`Cache::start_cleanup_task`](crate::Cache::start_cleanup_task):
+    /// the task runs until the returned handle is aborted.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let limiter = RateLimiter::new(config.with_idle_ttl(Duration::from_secs(600)));
+    /// let sweeper = limiter.start_cleanup_task(Duration::from_secs(60));
+    /// ```
+    pub fn start_cleanup_task(&self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let limiter = self.clone();
+
+        tokio::spawn(async move {
+            let mut interval_timer = tokio::time::interval(interval);
+
+            loop {
+                interval_timer.tick().await;
+                limiter.cleanup_idle().await;
+            }
+        })
+    }
+
+    /// Get tracked key and eviction statistics
+    pub async fn stats(&self) -> RateLimiterStats {
+        let state_map = self.state.read().await;
+        RateLimiterStats {
+            tracked_keys: state_map.len(),
+            max_tracked_keys: self.config.max_tracked_keys.map(|_| state_map.cap().get()),
+            idle_evictions: self.evictions.idle.load(Ordering::Relaxed),
+            capacity_evictions: self.evictions.capacity.load(Ordering::Relaxed),
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // Layers
+    // -------------------------------------------------------------------------
+
+    /// Limits applying to a request, broadest first
+    fn layers(&self, path: &str, client_id: &str, roles: &[String]) -> Vec<(StateKey, &RateLimit)> {
+        let config = &self.config;
+        if !config.enabled {
+            return Vec::new();
+        }
+
+        let mut layers = Vec::new();
+        if let Some(limit) = &config.global_limit {
+            layers.push((StateKey::for_layer(RateLimitLayer::Global, "", ""), limit));
+        }
+        for (pattern, limit) in &config.namespace_limits {
+            if pattern.matches(path) {
+                let layer = RateLimitLayer::Namespace(pattern.as_str().to_string());
+                layers.push((StateKey::for_layer(layer, "", client_id), limit));
+            }
+        }
+        if let Some(limit) = config.get_limit(path) {
+            layers.push((StateKey::new(path, client_id), limit));
+        }
+        if let Some(limit) = config.get_client_limit(client_id, roles) {
+            layers.push((
+                StateKey::for_layer(RateLimitLayer::Client, "", client_id),
+                limit,
+            ));
+        }
+        layers
+    }
+
+    /// Usage of the tracked layer with the least quota left
+    fn tightest_usage(
+        state_map: &StateMap,
+        layers: &[(StateKey, &RateLimit)],
+    ) -> Option<RateLimitUsage> {
+        layers
+            .iter()
+            .filter_map(|(key, limit)| {
+                let tracked = state_map.peek(key)?;
+                Some(Self::usage_of(&tracked.state, limit))
+            })
+            .min_by_key(|usage| usage.remaining)
+    }
+
+    /// Get the state of a key, creating it and marking it as used
+    fn touch<'m>(
+        &self,
+        state_map: &'m mut StateMap,
+        key: &StateKey,
         limit: &RateLimit,
-    ) -> Result<(), RpcError> {
+    ) -> &'m mut RateLimitState {
+        if !state_map.contains(key) && state_map.len() >= state_map.cap().get() {
+            state_map.pop_lru();
+            self.evictions.capacity.fetch_add(1, Ordering::Relaxed);
+            tracing::debug!("rate limit state evicted at capacity");
+        }
+
+        let tracked = state_map.get_or_insert_mut(key.clone(), || TrackedState {
+            state: Self::create_initial_state(limit),
+            last_used: Instant::now(),
+            retention: limit.retention(),
+        });
+        tracked.last_used = Instant::now();
+        &mut tracked.state
+    }
+
+    /// Check every layer for `cost` units, returning how long the request
+    /// must be delayed
+    ///
+    /// When several layers refuse the request, the error reports the one
+    /// that frees up last, so retrying after its hint is not refused again
+    /// by another layer.
+    fn check_layers(
+        &self,
+        state_map: &mut StateMap,
+        layers: &[(StateKey, &RateLimit)],
+        cost: u32,
+    ) -> Result<Duration, RpcError> {
+        // Free calls pass every layer
+        if cost == 0 {
+            return Ok(Duration::ZERO);
+        }
+
+        // No amount of waiting lets a call through a layer it outsizes
+        if let Some((key, limit)) = layers
+            .iter()
+            .find(|(_, limit)| limit.requests > 0 && cost > limit.capacity())
+        {
+            return Err(Self::cost_too_high_error(&key.layer, limit, cost));
+        }
+
+        let mut delay = Duration::ZERO;
+        let mut rejection: Option<(Duration, &RateLimitLayer, RateLimitUsage)> = None;
+        for (key, limit) in layers {
+            let state = self.touch(state_map, key, limit);
+
+            let result = match &limit.strategy {
+                RateLimitStrategy::FixedWindow => self.check_fixed_window(state, limit, cost),
+                RateLimitStrategy::SlidingWindow => self.check_sliding_window(state, limit, cost),
+                RateLimitStrategy::TokenBucket { refill_rate } => {
+                    self.check_token_bucket(state, limit, *refill_rate, cost)
+                }
+                RateLimitStrategy::Gcra { burst } => {
+                    let tolerance = limit.emission_interval() * burst.saturating_sub(1);
+                    self.check_schedule(state, limit, tolerance, cost)
+                        .map(|_| ())
+                }
+                RateLimitStrategy::LeakyBucket { max_wait } => self
+                    .check_schedule(state, limit, *max_wait, cost)
+                    .map(|wait| delay = delay.max(wait)),
+            };
+            if let Err(retry_after) = result
+                && rejection
+                    .as_ref()
+                    .is_none_or(|(longest, ..)| retry_after > *longest)
+            {
+                rejection = Some((retry_after, &key.layer, Self::usage_of(state, limit)));
+            }
+        }
+
+        match rejection {
+            Some((retry_after, layer, usage)) => {
+                Err(Self::rate_limited_error(retry_after, layer, &usage, cost))
+            }
+            None => Ok(delay),
+        }
+    }
+
+    fn record_layers(
+        &self,
+        state_map: &mut StateMap,
+        layers: &[(StateKey, &RateLimit)],
+        cost: u32,
+    ) {
+        for (key, limit) in layers {
+            match self.touch(state_map, key, limit) {
+                RateLimitState::FixedWindow(s) => s.count = s.count.saturating_add(cost),
+                RateLimitState::SlidingWindow(s) => {
+                    s.current_count = s.current_count.saturating_add(cost)
+                }
+                RateLimitState::TokenBucket(s) => s.tokens -= cost as f64,
+                RateLimitState::Schedule(s) => {
+                    s.tat = s.tat.max(Instant::now()) + limit.emission_interval() * cost;
+                }
+            }
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // Strategy Implementations
+    // -------------------------------------------------------------------------
+
+    fn create_initial_state(limit: &RateLimit) -> RateLimitState {
+        match &limit.strategy {
+            RateLimitStrategy::FixedWindow => RateLimitState::FixedWindow(FixedWindowState::new()),
+            RateLimitStrategy::SlidingWindow => {
+                RateLimitState::SlidingWindow(SlidingWindowState::new())
+            }
+            RateLimitStrategy::TokenBucket { .. } => {
+                RateLimitState::TokenBucket(TokenBucketState::new(limit.requests))
+            }
+            RateLimitStrategy::Gcra { .. } | RateLimitStrategy::LeakyBucket { .. } => {
+                RateLimitState::Schedule(ScheduleState::new())
+            }
+        }
+    }
+
+    fn check_fixed_window(
+        &self,
+        state: &mut RateLimitState,
+        limit: &RateLimit,
+        cost: u32,
+    ) -> Result<(), Duration> {
+        let RateLimitState::FixedWindow(s) = state else {
+            return Ok(());
+        };
+
+        let elapsed = s.window_start.elapsed();
+
+        // Reset window if expired
+        if elapsed >= limit.window {
+            s.count = 0;
+            s.window_start = Instant::now();
+        }
+
+        // Check limit
+        if s.count.saturating_add(cost) > limit.requests {
+            let retry_after = limit.window.saturating_sub(elapsed);
+            return Err(retry_after);
+        }
+
+        Ok(())
+    }
+
+    fn check_sliding_window(
+        &self,
+        state: &mut RateLimitState,
+        limit: &RateLimit,
+        cost: u32,
+    ) -> Result<(), Duration> {
         let RateLimitState::SlidingWindow(s) = state else {
             return Ok(());
         };
@@ -451,12 +1215,13 @@ impl RateLimiter {
             1.0 - (s.window_start.elapsed().as_secs_f64() / limit.window.as_secs_f64()).min(1.0);
         let weighted_count = (s.previous_count as f64 * weight) + s.current_count as f64;
 
-        if weighted_count >= limit.requests as f64 {
+        // The call's last unit must start within the limit
+        if weighted_count + (cost - 1) as f64 >= limit.requests as f64 {
             // Estimate retry time based on when enough requests will "slide out"
-            let excess = weighted_count - limit.requests as f64 + 1.0;
+            let excess = weighted_count - limit.requests as f64 + cost as f64;
             let retry_secs = (excess / limit.requests as f64) * limit.window.as_secs_f64();
             let retry_after = Duration::from_secs_f64(retry_secs.max(1.0));
-            return Err(Self::rate_limited_error(retry_after));
+            return Err(retry_after);
         }
 
         Ok(())
@@ -467,7 +1232,8 @@ impl RateLimiter {
         state: &mut RateLimitState,
         limit: &RateLimit,
         refill_rate: f64,
-    ) -> Result<(), RpcError> {
+        cost: u32,
+    ) -> Result<(), Duration> {
         let RateLimitState::TokenBucket(s) = state else {
             return Ok(());
         };
@@ -479,26 +1245,154 @@ impl RateLimiter {
         s.last_refill = Instant::now();
 
         // Check if we have tokens
-        if s.tokens < 1.0 {
-            // Calculate time until next token
-            let needed = 1.0 - s.tokens;
+        if s.tokens < cost as f64 {
+            // Calculate time until enough tokens
+            let needed = cost as f64 - s.tokens;
             let retry_secs = needed / refill_rate;
             let retry_after = Duration::from_secs_f64(retry_secs.max(0.1));
-            return Err(Self::rate_limited_error(retry_after));
+            return Err(retry_after);
         }
 
         Ok(())
     }
 
-    fn rate_limited_error(retry_after: Duration) -> RpcError {
+    /// Check a GCRA or leaky bucket schedule
+    ///
+    /// A request is on time when admitted requests, and all but the last
+    /// unit of its own cost, are at most `tolerance` ahead of the sustained
+    /// pace. Returns how far ahead admitted requests are, which is how long
+    /// a leaky bucket delays the request.
+    fn check_schedule(
+        &self,
+        state: &mut RateLimitState,
+        limit: &RateLimit,
+        tolerance: Duration,
+        cost: u32,
+    ) -> Result<Duration, Duration> {
+        let RateLimitState::Schedule(s) = state else {
+            return Ok(Duration::ZERO);
+        };
+
+        if limit.requests == 0 {
+            return Err(limit.window);
+        }
+
+        let backlog = s.backlog();
+        let ahead = backlog + limit.emission_interval() * (cost - 1);
+        if ahead > tolerance {
+            return Err(ahead - tolerance);
+        }
+
+        Ok(backlog)
+    }
+
+    /// Usage of one key, as of now
+    fn usage_of(state: &RateLimitState, limit: &RateLimit) -> RateLimitUsage {
+        let (used, remaining, reset_at) = match state {
+            RateLimitState::FixedWindow(s) => {
+                let elapsed = s.window_start.elapsed();
+                if elapsed >= limit.window {
+                    (0, limit.requests, Duration::ZERO)
+                } else {
+                    (
+                        s.count,
+                        limit.requests.saturating_sub(s.count),
+                        limit.window - elapsed,
+                    )
+                }
+            }
+            RateLimitState::SlidingWindow(s) => {
+                let elapsed = s.window_start.elapsed();
+                let weight = 1.0 - (elapsed.as_secs_f64() / limit.window.as_secs_f64()).min(1.0);
+                let weighted = (s.previous_count as f64 * weight) + s.current_count as f64;
+                let used = weighted.ceil() as u32;
+                (
+                    used,
+                    limit.requests.saturating_sub(used),
+                    limit.window.saturating_sub(elapsed),
+                )
+            }
+            RateLimitState::TokenBucket(s) => {
+                let refill_rate = match limit.strategy {
+                    RateLimitStrategy::TokenBucket { refill_rate } => refill_rate,
+                    _ => 0.0,
+                };
+                let max_tokens = limit.requests as f64;
+                let tokens = (s.tokens + s.last_refill.elapsed().as_secs_f64() * refill_rate)
+                    .min(max_tokens);
+                let remaining = tokens.max(0.0) as u32;
+                let until_full = if refill_rate > 0.0 {
+                    Duration::from_secs_f64((max_tokens - tokens).max(0.0) / refill_rate)
+                } else {
+                    limit.window
+                };
+                (
+                    limit.requests.saturating_sub(remaining),
+                    remaining,
+                    until_full,
+                )
+            }
+            RateLimitState::Schedule(s) => {
+                let interval = limit.emission_interval().as_nanos().max(1);
+                let capacity = limit.capacity();
+                let backlog = s.backlog();
+                let queued = backlog.as_nanos().div_ceil(interval) as u32;
+                (queued, capacity.saturating_sub(queued), backlog)
+            }
+        };
+
+        RateLimitUsage {
+            limit: limit.requests,
+            used,
+            remaining,
+            reset_at,
+        }
+    }
+
+    fn cost_too_high_error(layer: &RateLimitLayer, limit: &RateLimit, cost: u32) -> RpcError {
+        let mut details = serde_json::json!({
+            "cost": cost,
+            "limit": limit.capacity(),
+            "layer": layer.name()
+        });
+        if let RateLimitLayer::Namespace(pattern) = layer {
+            details["namespace"] = pattern.as_str().into();
+        }
+
+        RpcError::bad_request(format!(
+            "Call cost of {} exceeds the {} limit of {}",
+            cost,
+            layer,
+            limit.capacity()
+        ))
+        .with_details(details)
+    }
+
+    fn rate_limited_error(
+        retry_after: Duration,
+        layer: &RateLimitLayer,
+        usage: &RateLimitUsage,
+        cost: u32,
+    ) -> RpcError {
+        let mut details = serde_json::json!({
+            "limit": usage.limit,
+            "remaining": usage.remaining,
+            "cost": cost,
+            "reset_at": usage.reset_at_unix_ms(),
+            "retry_after_ms": retry_after.as_millis(),
+            "retry_after_secs": retry_after.as_secs(),
+            "layer": layer.name()
+        });
+        if let RateLimitLayer::Namespace(pattern) = layer {
+            details["namespace"] = pattern.as_str().into();
+        }
+
         RpcError::rate_limited(format!(
-            "Rate limit exceeded. Retry after {} seconds.",
+            "Rate limit exceeded ({}). Retry after {} seconds.",
+            layer,
             retry_after.as_secs()
         ))
-        .with_details(serde_json::json!({
-            "retry_after_ms": retry_after.as_millis(),
-            "retry_after_secs": retry_after.as_secs()
-        }))
+        .with_details(details)
     }
 }
 
@@ -507,6 +1401,7 @@ impl Clone for RateLimiter {
         Self {
             config: self.config.clone(),
             state: self.state.clone(),
+            evictions: self.evictions.clone(),
         }
     }
 }
@@ -524,12 +1419,41 @@ pub struct RateLimitUsage {
     pub reset_at: Duration,
 }
 
+impl RateLimitUsage {
+    /// When the limit resets, in milliseconds since the Unix epoch
+    pub fn reset_at_unix_ms(&self) -> u64 {
+        (SystemTime::now() + self.reset_at)
+            .duration_since(UNIX_EPOCH)
+            .map(|since| since.as_millis() as u64)
+            .unwrap_or(0)
+    }
+}
+
+/// Rate limiter state statistics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimiterStats {
+    /// Number of keys with tracked state
+    pub tracked_keys: usize,
+    /// Maximum number of tracked keys, if any, once raised to the number
+    /// of layers of a request
+    pub max_tracked_keys: Option<usize>,
+    /// Keys dropped after going unused for the idle TTL
+    pub idle_evictions: u64,
+    /// Keys dropped to stay within `max_tracked_keys`
+    pub capacity_evictions: u64,
+}
+
 // =============================================================================
 // Middleware
 // =============================================================================
 
 /// Create a rate limiting middleware
 ///
+/// Client quotas by role use the roles of the authenticated user, so the
+/// auth middleware must run before this one for
+/// [`RateLimitConfig::with_role_limit`] to apply. Each call uses the cost
+/// set with [`RateLimitConfig::with_procedure_cost`], computed from its input.
+///
 /// # Example
 ///
 /// ```rust,ignore
@@ -549,27 +1473,46 @@ where
     Ctx: Clone + Send + Sync + 'static,
     F: Fn(&Request) -> String + Clone + Send + Sync + 'static,
 {
-    let middleware = move |ctx: Context<Ctx>, req: Request, next: Next<Ctx>| {
+    let middleware = move |mut ctx: Context<Ctx>, mut req: Request, next: Next<Ctx>| {
         let limiter = limiter.clone();
         let client_id_fn = client_id_fn.clone();
         let path = req.path.clone();
         let client_id = client_id_fn(&req);
+        let cost = limiter.config.cost_of(&path, &req.input);
+        let roles = ctx
+            .auth()
+            .map(|auth| auth.roles.clone())
+            .unwrap_or_default();
 
         async move {
             // Check rate limit
-            match limiter.check_and_record(&path, &client_id).await {
-                Ok(()) => {
-                    // Get usage info for logging
-                    if let Some(usage) = limiter.get_usage(&path, &client_id).await {
-                        tracing::trace!(
-                            path = %path,
-                            client_id = %client_id,
-                            remaining = %usage.remaining,
-                            limit = %usage.limit,
-                            "Rate limit check passed"
-                        );
+            match limiter
+                .check_and_record_with_cost(&path, &client_id, &roles, cost)
+                .await
+            {
+                Ok(Some(usage)) => {
+                    tracing::trace!(
+                        path = %path,
+                        client_id = %client_id,
+                        cost = %cost,
+                        remaining = %usage.remaining,
+                        limit = %usage.limit,
+                        "Rate limit check passed"
+                    );
+
+                    if limiter.config.expose_quota {
+                        req.envelope = req
+                            .envelope
+                            .with_metadata("x-ratelimit-limit", usage.limit.to_string())
+                            .with_metadata("x-ratelimit-remaining", usage.remaining.to_string())
+                            .with_metadata(
+                                "x-ratelimit-reset",
+                                usage.reset_at_unix_ms().to_string(),
+                            );
+                        ctx = ctx.with_envelope(req.envelope.clone());
                     }
                 }
+                Ok(None) => {}
                 Err(e) => {
                     tracing::warn!(
                         path = %path,
@@ -720,6 +1663,137 @@ mod tests {
         assert!(limiter.check_and_record("test", "client1").await.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_gcra_allows_burst_then_spaces_requests() {
+        // One request per 100ms, bursts of 3
+        let config = RateLimitConfig::new().with_default_limit(RateLimit::gcra(
+            10,
+            Duration::from_secs(1),
+            3,
+        ));
+        let limiter = RateLimiter::new(config);
+
+        for _ in 0..3 {
+            assert!(limiter.check_and_record("test", "client1").await.is_ok());
+        }
+        let err = limiter
+            .check_and_record("test", "client1")
+            .await
+            .unwrap_err();
+        let retry_after_ms = err.details.unwrap()["retry_after_ms"].as_u64().unwrap();
+        assert!(retry_after_ms > 0 && retry_after_ms <= 100);
+
+        // One emission interval frees exactly one slot
+        sleep(Duration::from_millis(110)).await;
+        assert!(limiter.check_and_record("test", "client1").await.is_ok());
+        assert!(limiter.check_and_record("test", "client1").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_gcra_usage() {
+        let config = RateLimitConfig::new().with_default_limit(RateLimit::gcra(
+            10,
+            Duration::from_secs(60),
+            4,
+        ));
+        let limiter = RateLimiter::new(config);
+
+        let usage = limiter
+            .check_and_record_with_roles("test", "client1", &[])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(usage.used, 1);
+        assert_eq!(usage.remaining, 3);
+        assert!(usage.reset_at <= Duration::from_secs(6));
+    }
+
+    #[tokio::test]
+    async fn test_leaky_bucket_delays_instead_of_rejecting() {
+        // One request per 50ms, queueing for up to 100ms
+        let config = RateLimitConfig::new().with_default_limit(RateLimit::leaky_bucket(
+            10,
+            Duration::from_millis(500),
+            Duration::from_millis(100),
+        ));
+        let limiter = RateLimiter::new(config);
+
+        let start = Instant::now();
+        for _ in 0..3 {
+            assert!(limiter.check_and_record("test", "client1").await.is_ok());
+        }
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_leaky_bucket_queues_what_gcra_bursts() {
+        // One request per 100ms, with room for two more ahead of the pace
+        let gcra = RateLimiter::new(RateLimitConfig::new().with_default_limit(RateLimit::gcra(
+            10,
+            Duration::from_secs(1),
+            3,
+        )));
+        let leaky = RateLimiter::new(RateLimitConfig::new().with_default_limit(
+            RateLimit::leaky_bucket(10, Duration::from_secs(1), Duration::from_millis(200)),
+        ));
+
+        // A request every 10ms for a second
+        let mut admitted = [0, 0];
+        let mut leaky_delays = Vec::new();
+        for _ in 0..100 {
+            if let Ok(delay) = gcra.check_delay("test", "client1", &[], 1).await {
+                assert_eq!(delay, Duration::ZERO);
+                gcra.record("test", "client1").await;
+                admitted[0] += 1;
+            }
+            if let Ok(delay) = leaky.check_delay("test", "client1", &[], 1).await {
+                leaky_delays.push(delay);
+                leaky.record("test", "client1").await;
+                admitted[1] += 1;
+            }
+            tokio::time::advance(Duration::from_millis(10)).await;
+        }
+
+        // Three at once, then one per 100ms
+        assert_eq!(admitted, [12, 12]);
+        // The leaky bucket spaces out the burst instead of letting it through
+        assert_eq!(
+            leaky_delays[..3],
+            [
+                Duration::ZERO,
+                Duration::from_millis(90),
+                Duration::from_millis(180)
+            ]
+        );
+        assert!(
+            leaky_delays[3..]
+                .iter()
+                .all(|delay| *delay == Duration::from_millis(200))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_leaky_bucket_rejects_past_max_wait() {
+        let config = RateLimitConfig::new().with_default_limit(RateLimit::leaky_bucket(
+            10,
+            Duration::from_secs(1),
+            Duration::from_millis(200),
+        ));
+        let limiter = RateLimiter::new(config);
+
+        // Queue up the immediate slot and two waiting ones without sleeping
+        for _ in 0..3 {
+            assert!(limiter.check("test", "client1").await.is_ok());
+            limiter.record("test", "client1").await;
+        }
+
+        let err = limiter.check("test", "client1").await.unwrap_err();
+        assert_eq!(err.code, crate::RpcErrorCode::RateLimited);
+        let details = err.details.unwrap();
+        assert_eq!(details["remaining"], 0);
+        assert!(details["retry_after_ms"].as_u64().unwrap() <= 100);
+    }
+
     #[tokio::test]
     async fn test_per_procedure_limits() {
         let config = RateLimitConfig::new()
@@ -756,6 +1830,208 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_pattern_limits() {
+        let config = RateLimitConfig::new()
+            .with_default_limit(RateLimit::fixed_window(100, Duration::from_secs(60)))
+            .with_procedure_limit(
+                "report.export",
+                RateLimit::fixed_window(1, Duration::from_secs(60)),
+            )
+            .with_pattern_limit(
+                "report.*.{pdf,csv}",
+                RateLimit::fixed_window(5, Duration::from_secs(60)),
+            )
+            .with_pattern_limit(
+                "report.**",
+                RateLimit::fixed_window(20, Duration::from_secs(60)),
+            );
+
+        let requests = |path: &str| config.get_limit(path).unwrap().requests;
+        assert_eq!(requests("report.export"), 1);
+        assert_eq!(requests("report.sales.pdf"), 5);
+        assert_eq!(requests("report.sales.xlsx"), 20);
+        assert_eq!(requests("user.get"), 100);
+    }
+
+    #[tokio::test]
+    async fn test_global_limit_spans_clients_and_procedures() {
+        let config = RateLimitConfig::new()
+            .with_default_limit(RateLimit::fixed_window(10, Duration::from_secs(60)))
+            .with_global_limit(RateLimit::fixed_window(3, Duration::from_secs(60)));
+        let limiter = RateLimiter::new(config);
+
+        assert!(limiter.check_and_record("a", "client1").await.is_ok());
+        assert!(limiter.check_and_record("b", "client2").await.is_ok());
+        assert!(limiter.check_and_record("c", "client3").await.is_ok());
+
+        let err = limiter.check_and_record("d", "client4").await.unwrap_err();
+        assert_eq!(err.details.unwrap()["layer"], "global");
+    }
+
+    #[tokio::test]
+    async fn test_namespace_budget_is_shared_per_client() {
+        let config = RateLimitConfig::new().with_namespace_limit(
+            "user.*",
+            RateLimit::fixed_window(2, Duration::from_secs(60)),
+        );
+        let limiter = RateLimiter::new(config);
+
+        assert!(
+            limiter
+                .check_and_record("user.get", "client1")
+                .await
+                .is_ok()
+        );
+        assert!(
+            limiter
+                .check_and_record("user.list", "client1")
+                .await
+                .is_ok()
+        );
+
+        let err = limiter
+            .check_and_record("user.search", "client1")
+            .await
+            .unwrap_err();
+        let details = err.details.unwrap();
+        assert_eq!(details["layer"], "namespace");
+        assert_eq!(details["namespace"], "user.*");
+
+        // Other namespaces and clients are unaffected
+        assert!(
+            limiter
+                .check_and_record("post.get", "client1")
+                .await
+                .is_ok()
+        );
+        assert!(
+            limiter
+                .check_and_record("user.get", "client2")
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_role_limits_override_client_quota() {
+        let config = RateLimitConfig::new()
+            .with_client_limit(RateLimit::fixed_window(1, Duration::from_secs(60)))
+            .with_role_limit(
+                "premium",
+                RateLimit::fixed_window(3, Duration::from_secs(60)),
+            )
+            .with_client_override(
+                "batch-job",
+                RateLimit::fixed_window(5, Duration::from_secs(60)),
+            );
+        let limiter = RateLimiter::new(config);
+        let premium = vec!["premium".to_string()];
+
+        assert!(limiter.check_and_record("a", "free").await.is_ok());
+        let err = limiter.check_and_record("b", "free").await.unwrap_err();
+        assert_eq!(err.details.unwrap()["layer"], "client");
+
+        for path in ["a", "b", "c"] {
+            assert!(
+                limiter
+                    .check_and_record_with_roles(path, "paid", &premium)
+                    .await
+                    .is_ok()
+            );
+        }
+        assert!(
+            limiter
+                .check_and_record_with_roles("d", "paid", &premium)
+                .await
+                .is_err()
+        );
+
+        for _ in 0..5 {
+            assert!(limiter.check_and_record("a", "batch-job").await.is_ok());
+        }
+        assert!(limiter.check_and_record("a", "batch-job").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rejection_reports_the_longest_retry_after() {
+        let config = RateLimitConfig::new()
+            .with_global_limit(RateLimit::fixed_window(1, Duration::from_secs(1)))
+            .with_client_limit(RateLimit::fixed_window(1, Duration::from_secs(60)));
+        let limiter = RateLimiter::new(config);
+
+        assert!(limiter.check_and_record("a", "client1").await.is_ok());
+        let details = limiter
+            .check_and_record("a", "client1")
+            .await
+            .unwrap_err()
+            .details
+            .unwrap();
+        assert_eq!(details["layer"], "client");
+        assert!(details["retry_after_secs"].as_u64().unwrap() >= 59);
+    }
+
+    #[tokio::test]
+    async fn test_two_phase_and_usage_cover_every_layer() {
+        let config = RateLimitConfig::new()
+            .with_default_limit(RateLimit::fixed_window(10, Duration::from_secs(60)))
+            .with_client_limit(RateLimit::fixed_window(1, Duration::from_secs(60)))
+            .with_role_limit(
+                "premium",
+                RateLimit::fixed_window(3, Duration::from_secs(60)),
+            );
+        let limiter = RateLimiter::new(config);
+        let premium = vec!["premium".to_string()];
+
+        limiter.check("a", "free").await.unwrap();
+        limiter.record("a", "free").await;
+        let usage = limiter.get_usage("a", "free").await.unwrap();
+        assert_eq!((usage.limit, usage.remaining), (1, 0));
+        let err = limiter.check("a", "free").await.unwrap_err();
+        assert_eq!(err.details.unwrap()["layer"], "client");
+
+        for _ in 0..3 {
+            limiter
+                .check_with_roles("a", "paid", &premium)
+                .await
+                .unwrap();
+            limiter.record_with_roles("a", "paid", &premium).await;
+        }
+        let usage = limiter
+            .get_usage_with_roles("a", "paid", &premium)
+            .await
+            .unwrap();
+        assert_eq!((usage.limit, usage.remaining), (3, 0));
+        assert!(
+            limiter
+                .check_with_roles("a", "paid", &premium)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejected_request_uses_no_layer() {
+        let config = RateLimitConfig::new()
+            .with_global_limit(RateLimit::fixed_window(3, Duration::from_secs(60)))
+            .with_procedure_limit("tight", RateLimit::fixed_window(1, Duration::from_secs(60)));
+        let limiter = RateLimiter::new(config);
+
+        assert!(limiter.check_and_record("tight", "client1").await.is_ok());
+        for _ in 0..5 {
+            let err = limiter
+                .check_and_record("tight", "client1")
+                .await
+                .unwrap_err();
+            assert_eq!(err.details.unwrap()["layer"], "procedure");
+        }
+
+        // Only the accepted request counted against the global ceiling
+        assert!(limiter.check_and_record("other", "client1").await.is_ok());
+        assert!(limiter.check_and_record("other", "client1").await.is_ok());
+        assert!(limiter.check_and_record("other", "client1").await.is_err());
+    }
+
     #[tokio::test]
     async fn test_per_client_isolation() {
         let config = RateLimitConfig::new()
@@ -814,6 +2090,270 @@ mod tests {
         let details = err.details.unwrap();
         assert!(details.get("retry_after_ms").is_some());
         assert!(details.get("retry_after_secs").is_some());
+        assert_eq!(details["limit"], 1);
+        assert_eq!(details["remaining"], 0);
+
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        let reset_at = details["reset_at"].as_u64().unwrap();
+        assert!(reset_at > now_ms + 59_000 && reset_at <= now_ms + 60_000);
+    }
+
+    #[tokio::test]
+    async fn test_check_and_record_reports_tightest_layer() {
+        let config = RateLimitConfig::new()
+            .with_global_limit(RateLimit::fixed_window(100, Duration::from_secs(60)))
+            .with_procedure_limit("test", RateLimit::fixed_window(3, Duration::from_secs(60)));
+        let limiter = RateLimiter::new(config);
+
+        let usage = limiter
+            .check_and_record_with_roles("test", "client1", &[])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(usage.limit, 3);
+        assert_eq!(usage.used, 1);
+        assert_eq!(usage.remaining, 2);
+        assert!(usage.reset_at > Duration::from_secs(59));
+
+        let limiter = RateLimiter::new(RateLimitConfig::new());
+        let usage = limiter
+            .check_and_record_with_roles("test", "client1", &[])
+            .await;
+        assert!(usage.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_token_bucket_usage_counts_refill() {
+        let config = RateLimitConfig::new().with_default_limit(RateLimit::token_bucket(
+            2,
+            Duration::from_secs(60),
+            20.0,
+        ));
+        let limiter = RateLimiter::new(config);
+
+        limiter.check_and_record("test", "client1").await.unwrap();
+        limiter.check_and_record("test", "client1").await.unwrap();
+        sleep(Duration::from_millis(60)).await;
+
+        let usage = limiter.get_usage("test", "client1").await.unwrap();
+        assert_eq!(usage.remaining, 1);
+        assert!(usage.reset_at <= Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn test_middleware_exposes_quota_in_envelope() {
+        use crate::{EmptyContext, Router};
+        use serde_json::json;
+
+        let config = RateLimitConfig::new()
+            .with_default_limit(RateLimit::fixed_window(5, Duration::from_secs(60)))
+            .with_quota_in_envelope(true);
+        let router = Router::new()
+            .context(EmptyContext)
+            .middleware_fn(rate_limit_middleware(RateLimiter::new(config), |_req| {
+                "client1".to_string()
+            }))
+            .query("quota", |ctx: Context<EmptyContext>, _: ()| async move {
+                let envelope = ctx.envelope();
+                Ok(json!({
+                    "limit": envelope.get("x-ratelimit-limit"),
+                    "remaining": envelope.get("x-ratelimit-remaining"),
+                    "has_reset": envelope.get("x-ratelimit-reset").is_some(),
+                }))
+            })
+            .compile();
+
+        router.call("quota", json!(null)).await.unwrap();
+        let quota = router.call("quota", json!(null)).await.unwrap();
+        assert_eq!(
+            quota,
+            json!({"limit": "5", "remaining": "3", "has_reset": true})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_procedure_cost_draws_weighted_units() {
+        let config = RateLimitConfig::new()
+            .with_client_limit(RateLimit::fixed_window(10, Duration::from_secs(60)))
+            .with_procedure_cost("report.export", 4)
+            .with_procedure_cost("health", 0);
+        let limiter = RateLimiter::new(config);
+        let cost = limiter
+            .config
+            .cost_of("report.export", &serde_json::Value::Null);
+        assert_eq!(cost, 4);
+
+        for _ in 0..2 {
+            assert!(
+                limiter
+                    .check_and_record_with_cost("report.export", "client1", &[], cost)
+                    .await
+                    .is_ok()
+            );
+        }
+        let err = limiter
+            .check_and_record_with_cost("report.export", "client1", &[], cost)
+            .await
+            .unwrap_err();
+        let details = err.details.unwrap();
+        assert_eq!(details["cost"], 4);
+        assert_eq!(details["remaining"], 2);
+
+        // Cheap lookups still fit in what is left, and free calls always do
+        assert!(
+            limiter
+                .check_and_record("user.get", "client1")
+                .await
+                .is_ok()
+        );
+        assert!(
+            limiter
+                .check_and_record("user.get", "client1")
+                .await
+                .is_ok()
+        );
+        assert!(
+            limiter
+                .check_and_record("user.get", "client1")
+                .await
+                .is_err()
+        );
+        assert!(
+            limiter
+                .check_and_record_with_cost("health", "client1", &[], 0)
+                .await
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_leaky_bucket_capacity_saturates() {
+        let limit = RateLimit::leaky_bucket(1000, Duration::from_millis(1), Duration::MAX);
+        assert_eq!(limit.capacity(), u32::MAX);
+
+        let limit = RateLimit::leaky_bucket(10, Duration::from_secs(1), Duration::from_millis(250));
+        assert_eq!(limit.capacity(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_cost_above_limit_is_a_bad_request() {
+        let config = RateLimitConfig::new()
+            .with_procedure_limit(
+                "export",
+                RateLimit::fixed_window(10, Duration::from_secs(60)),
+            )
+            .with_procedure_limit("gcra", RateLimit::gcra(60, Duration::from_secs(60), 5))
+            .with_namespace_limit(
+                "export",
+                RateLimit::fixed_window(20, Duration::from_secs(60)),
+            );
+        let limiter = RateLimiter::new(config);
+
+        let err = limiter
+            .check_and_record_with_cost("export", "client1", &[], 11)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, crate::RpcErrorCode::BadRequest);
+        let details = err.details.unwrap();
+        assert_eq!(details["cost"], 11);
+        assert_eq!(details["limit"], 10);
+        assert_eq!(details["layer"], "procedure");
+        assert!(details.get("retry_after_ms").is_none());
+
+        // GCRA calls are bounded by the burst, not the rate
+        let err = limiter
+            .check_with_cost("gcra", "client1", &[], 6)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, crate::RpcErrorCode::BadRequest);
+        assert_eq!(err.details.unwrap()["limit"], 5);
+
+        // Nothing was drawn by the refused calls
+        limiter
+            .check_with_cost("export", "client1", &[], 10)
+            .await
+            .unwrap();
+        limiter.record_with_cost("export", "client1", &[], 10).await;
+        let usage = limiter.get_usage("export", "client1").await.unwrap();
+        assert_eq!((usage.used, usage.remaining), (10, 0));
+    }
+
+    #[tokio::test]
+    async fn test_weighted_token_bucket_and_gcra() {
+        let config = RateLimitConfig::new()
+            .with_procedure_limit(
+                "bucket",
+                RateLimit::token_bucket(5, Duration::from_secs(60), 0.01),
+            )
+            .with_procedure_limit("gcra", RateLimit::gcra(60, Duration::from_secs(60), 5));
+        let limiter = RateLimiter::new(config);
+
+        for path in ["bucket", "gcra"] {
+            assert!(
+                limiter
+                    .check_and_record_with_cost(path, "client1", &[], 3)
+                    .await
+                    .is_ok()
+            );
+            assert!(
+                limiter
+                    .check_and_record_with_cost(path, "client1", &[], 3)
+                    .await
+                    .is_err(),
+                "{path} allowed more than its burst"
+            );
+            assert!(
+                limiter
+                    .check_and_record_with_cost(path, "client1", &[], 2)
+                    .await
+                    .is_ok()
+            );
+        }
+    }
+
+    #[test]
+    fn test_page_limit_cost() {
+        use serde_json::json;
+
+        let cost = RateLimitCost::page_limit();
+        assert_eq!(cost.of(&json!({"page": 2, "limit": 50})), 50);
+        assert_eq!(cost.of(&json!({"limit": 500})), 100);
+        assert_eq!(cost.of(&json!({"query": "ann"})), 10);
+        assert_eq!(cost.of(&json!(null)), 1);
+        assert_eq!(format!("{:?}", cost), "FromInput(..)");
+    }
+
+    #[tokio::test]
+    async fn test_middleware_uses_input_cost() {
+        use crate::{EmptyContext, Router, types::PaginationInput};
+        use serde_json::json;
+
+        let config = RateLimitConfig::new()
+            .with_default_limit(RateLimit::fixed_window(20, Duration::from_secs(60)))
+            .with_procedure_cost("user.list", RateLimitCost::page_limit());
+        let router = Router::new()
+            .context(EmptyContext)
+            .middleware_fn(rate_limit_middleware(RateLimiter::new(config), |_req| {
+                "client1".to_string()
+            }))
+            .query(
+                "user.list",
+                |_ctx: Context<EmptyContext>, page: PaginationInput| async move {
+                    Ok(page.limit())
+                },
+            )
+            .compile();
+
+        assert!(router.call("user.list", json!({"limit": 15})).await.is_ok());
+        let err = router
+            .call("user.list", json!({"limit": 10}))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, crate::RpcErrorCode::RateLimited);
+        assert!(router.call("user.list", json!({"limit": 5})).await.is_ok());
     }
 
     #[tokio::test]
@@ -852,6 +2392,110 @@ mod tests {
         // Should be allowed again
         assert!(limiter.check_and_record("test", "client1").await.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_cleanup_idle_drops_unused_keys() {
+        let config = RateLimitConfig::new()
+            .with_default_limit(RateLimit::fixed_window(5, Duration::from_millis(50)))
+            .with_idle_ttl(Duration::from_millis(20));
+        let limiter = RateLimiter::new(config);
+
+        limiter.check_and_record("test", "client1").await.unwrap();
+        sleep(Duration::from_millis(60)).await;
+        limiter.check_and_record("test", "client2").await.unwrap();
+        limiter.check_and_record("test", "client2").await.unwrap();
+
+        assert_eq!(limiter.cleanup_idle().await, 1);
+        assert!(limiter.get_usage("test", "client1").await.is_none());
+        assert_eq!(limiter.get_usage("test", "client2").await.unwrap().used, 2);
+
+        let stats = limiter.stats().await;
+        assert_eq!(stats.tracked_keys, 1);
+        assert_eq!(stats.idle_evictions, 1);
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_idle_keeps_keys_within_their_window() {
+        let config = RateLimitConfig::new()
+            .with_default_limit(RateLimit::fixed_window(1, Duration::from_secs(60)))
+            .with_idle_ttl(Duration::from_millis(10));
+        let limiter = RateLimiter::new(config);
+
+        limiter.check_and_record("test", "client1").await.unwrap();
+        sleep(Duration::from_millis(20)).await;
+
+        // Dropping the key would hand the client a fresh quota
+        assert_eq!(limiter.cleanup_idle().await, 0);
+        assert!(limiter.check_and_record("test", "client1").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_idle_without_ttl_keeps_keys() {
+        let config = RateLimitConfig::new()
+            .with_default_limit(RateLimit::fixed_window(5, Duration::from_secs(60)));
+        let limiter = RateLimiter::new(config);
+
+        limiter.check_and_record("test", "client1").await.unwrap();
+        assert_eq!(limiter.cleanup_idle().await, 0);
+        assert_eq!(limiter.stats().await.tracked_keys, 1);
+    }
+
+    #[tokio::test]
+    async fn test_max_tracked_keys_evicts_least_recently_used() {
+        let config = RateLimitConfig::new()
+            .with_default_limit(RateLimit::fixed_window(5, Duration::from_secs(60)))
+            .with_max_tracked_keys(2);
+        let limiter = RateLimiter::new(config);
+
+        limiter.check_and_record("test", "client1").await.unwrap();
+        limiter.check_and_record("test", "client2").await.unwrap();
+        limiter.check_and_record("test", "client1").await.unwrap();
+        limiter.check_and_record("test", "client3").await.unwrap();
+
+        assert!(limiter.get_usage("test", "client2").await.is_none());
+        assert_eq!(limiter.get_usage("test", "client1").await.unwrap().used, 2);
+
+        let stats = limiter.stats().await;
+        assert_eq!(stats.tracked_keys, 2);
+        assert_eq!(stats.max_tracked_keys, Some(2));
+        assert_eq!(stats.capacity_evictions, 1);
+    }
+
+    #[tokio::test]
+    async fn test_max_tracked_keys_covers_every_layer() {
+        let config = RateLimitConfig::new()
+            .with_global_limit(RateLimit::fixed_window(10, Duration::from_secs(60)))
+            .with_procedure_limit("test", RateLimit::fixed_window(10, Duration::from_secs(60)))
+            .with_client_limit(RateLimit::fixed_window(1, Duration::from_secs(60)))
+            .with_max_tracked_keys(1);
+        let limiter = RateLimiter::new(config);
+
+        limiter.check_and_record("test", "client1").await.unwrap();
+        let err = limiter
+            .check_and_record("test", "client1")
+            .await
+            .unwrap_err();
+        assert_eq!(err.details.unwrap()["layer"], "client");
+
+        let stats = limiter.stats().await;
+        assert_eq!(stats.max_tracked_keys, Some(3));
+        assert_eq!(stats.capacity_evictions, 0);
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_task_drops_idle_keys() {
+        let config = RateLimitConfig::new()
+            .with_default_limit(RateLimit::fixed_window(5, Duration::from_millis(20)))
+            .with_idle_ttl(Duration::from_millis(20));
+        let limiter = RateLimiter::new(config);
+        limiter.check_and_record("test", "client1").await.unwrap();
+
+        let handle = limiter.start_cleanup_task(Duration::from_millis(10));
+        sleep(Duration::from_millis(80)).await;
+        handle.abort();
+
+        assert_eq!(limiter.stats().await.tracked_keys, 0);
+    }
 }
 
 // =============================================================================
@@ -1039,6 +2683,142 @@ mod proptests {
             })?;
         }
 
+        /// Property: GCRA and leaky bucket admit a burst like the window strategies
+        /// With a burst tolerance (or queue) of `requests`, back-to-back requests
+        /// are admitted exactly as often as by fixed window, sliding window and
+        /// token bucket limits of the same size.
+        #[test]
+        fn prop_burst_admissions_match_existing_strategies(
+            requests in 1u32..20,
+            attempts in 1u32..40,
+        ) {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            rt.block_on(async {
+                let window = Duration::from_secs(60);
+                let interval = window / requests;
+                let limits = [
+                    RateLimit::fixed_window(requests, window),
+                    RateLimit::sliding_window(requests, window),
+                    RateLimit::token_bucket(requests, window, requests as f64 / 60.0),
+                    RateLimit::gcra(requests, window, requests),
+                    RateLimit::leaky_bucket(requests, window, interval * (requests - 1)),
+                ];
+
+                for limit in limits {
+                    let strategy = limit.strategy.clone();
+                    let limiter = RateLimiter::new(RateLimitConfig::new().with_default_limit(limit));
+
+                    // Two-phase so queued leaky bucket requests are not slept on
+                    let mut admitted = 0;
+                    for _ in 0..attempts {
+                        if limiter.check("test", "client").await.is_ok() {
+                            limiter.record("test", "client").await;
+                            admitted += 1;
+                        }
+                    }
+                    prop_assert_eq!(
+                        admitted,
+                        attempts.min(requests),
+                        "{:?} admitted a different count",
+                        strategy
+                    );
+                }
+
+                Ok(())
+            })?;
+        }
+
+        /// Property: GCRA admits at most `burst` back-to-back requests
+        #[test]
+        fn prop_gcra_admits_burst(
+            requests in 1u32..50,
+            burst in 1u32..20,
+            attempts in 1u32..40,
+        ) {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            rt.block_on(async {
+                let limit = RateLimit::gcra(requests, Duration::from_secs(60), burst);
+                let limiter = RateLimiter::new(RateLimitConfig::new().with_default_limit(limit));
+
+                let mut admitted = 0;
+                for _ in 0..attempts {
+                    if limiter.check_and_record("test", "client").await.is_ok() {
+                        admitted += 1;
+                    }
+                }
+                prop_assert_eq!(admitted, attempts.min(burst));
+
+                Ok(())
+            })?;
+        }
+
+        /// Property: A leaky bucket queues as many requests as fit in its max wait
+        #[test]
+        fn prop_leaky_bucket_queue_depth(
+            requests in 1u32..50,
+            queue_slots in 0u32..10,
+            attempts in 1u32..40,
+        ) {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            rt.block_on(async {
+                let window = Duration::from_secs(60);
+                let max_wait = window / requests * queue_slots;
+                let limit = RateLimit::leaky_bucket(requests, window, max_wait);
+                let limiter = RateLimiter::new(RateLimitConfig::new().with_default_limit(limit));
+
+                let mut admitted = 0;
+                for _ in 0..attempts {
+                    if limiter.check("test", "client").await.is_ok() {
+                        limiter.record("test", "client").await;
+                        admitted += 1;
+                    }
+                }
+                // One request runs right away, the rest wait their turn
+                prop_assert_eq!(admitted, attempts.min(queue_slots + 1));
+
+                Ok(())
+            })?;
+        }
+
+        /// Property: A call costing `cost` units uses as much quota as `cost` calls
+        #[test]
+        fn prop_weighted_cost_matches_unit_calls(
+            requests in 1u32..30,
+            cost in 1u32..8,
+            strategy_idx in 0u8..4,
+        ) {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            rt.block_on(async {
+                let window = Duration::from_secs(60);
+                let limit = match strategy_idx {
+                    0 => RateLimit::fixed_window(requests, window),
+                    1 => RateLimit::sliding_window(requests, window),
+                    2 => RateLimit::token_bucket(requests, window, requests as f64 / 60.0),
+                    _ => RateLimit::gcra(requests, window, requests),
+                };
+                let limiter = RateLimiter::new(RateLimitConfig::new().with_default_limit(limit));
+
+                let mut admitted = 0;
+                while limiter
+                    .check_and_record_with_cost("weighted", "client", &[], cost)
+                    .await
+                    .is_ok()
+                {
+                    admitted += 1;
+                }
+                prop_assert_eq!(admitted, requests / cost);
+
+                // The units left over still serve single calls
+                let mut leftover = 0;
+                while limiter.check_and_record("weighted", "client").await.is_ok() {
+                    leftover += 1;
+                }
+                prop_assert_eq!(leftover, requests % cost);
+
+                Ok(())
+            })?;
+        }
+
         /// Property: Token bucket refills correctly
         /// After waiting, tokens should be refilled proportionally.
         #[test]
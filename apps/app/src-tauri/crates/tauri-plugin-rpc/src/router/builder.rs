@@ -8,13 +8,16 @@ use super::{
 };
 use crate::{
     Context, RpcError, RpcResult,
-    handler::BoxedHandler,
+    cache::CachePolicy,
+    handler::{BoxedHandler, into_boxed_compensation},
     middleware::{MiddlewareFn, Next, ProcedureType, Request, Response},
+    schema::{ProcedureMeta, ToTauriSchema},
     validation::Validate,
 };
 use serde::{Serialize, de::DeserializeOwned};
 use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 
 // =============================================================================
 // Procedure Chain
@@ -66,6 +69,62 @@ impl<Ctx: Clone + Send + Sync + 'static> ProcedureChain<Ctx> {
         self
     }
 
+    /// Attach documentation metadata to this procedure.
+    ///
+    /// The metadata is reported by [`Router::schema`].
+    #[must_use = "This method returns a new ProcedureChain and does not modify self"]
+    pub fn meta(mut self, meta: ProcedureMeta) -> Self {
+        let full_path = self.router.make_path(&self.path);
+        let meta = meta.keep_cache_policy(self.router.meta.get(&full_path));
+        self.router.meta.insert(full_path, meta);
+        self
+    }
+
+    /// Set the maximum execution time for this procedure.
+    ///
+    /// When the deadline elapses the handler future is dropped and the call
+    /// fails with `RpcErrorCode::Timeout`.
+    #[must_use = "This method returns a new ProcedureChain and does not modify self"]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        let full_path = self.router.make_path(&self.path);
+        self.router.timeouts.insert(full_path, timeout);
+        self
+    }
+
+    /// Let the cache middleware store this procedure's results, even for a mutation.
+    #[must_use = "This method returns a new ProcedureChain and does not modify self"]
+    pub fn cacheable(mut self) -> Self {
+        self.router
+            .set_cache_policy(&self.path, CachePolicy::Cacheable);
+        self
+    }
+
+    /// Keep the cache middleware from ever storing this procedure's results.
+    #[must_use = "This method returns a new ProcedureChain and does not modify self"]
+    pub fn no_store(mut self) -> Self {
+        self.router
+            .set_cache_policy(&self.path, CachePolicy::NoStore);
+        self
+    }
+
+    /// Register a compensation (undo) handler for this mutation.
+    ///
+    /// See [`Router::compensation`].
+    #[must_use = "This method returns a new ProcedureChain and does not modify self"]
+    pub fn compensate<F, Fut, I, O>(mut self, handler: F) -> Self
+    where
+        I: DeserializeOwned + Send + 'static,
+        O: DeserializeOwned + Send + 'static,
+        F: Fn(Context<Ctx>, I, O) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = RpcResult<()>> + Send + 'static,
+    {
+        let full_path = self.router.make_path(&self.path);
+        self.router
+            .compensations
+            .insert(full_path, into_boxed_compensation(handler));
+        self
+    }
+
     /// Set the input type for this procedure.
     ///
     /// Returns a `TypedProcedureChain` that allows you to register the procedure
@@ -231,6 +290,47 @@ impl<Ctx: Clone + Send + Sync + 'static, Input: DeserializeOwned + Send + 'stati
         self.build_procedure(ProcedureType::Mutation, handler)
     }
 
+    /// Register this procedure as a query whose schema is derived from its types.
+    ///
+    /// The input and output reported by [`Router::schema`] come from `Input`
+    /// and the handler's return type, so the docs cannot drift from the code.
+    #[must_use = "This method returns a Router and does not modify self"]
+    pub fn typed_query<H, Fut, Output>(self, handler: H) -> Router<Ctx>
+    where
+        Input: ToTauriSchema,
+        H: Fn(Context<Ctx>, Input) -> Fut + Send + Sync + Clone + 'static,
+        Fut: Future<Output = RpcResult<Output>> + Send + 'static,
+        Output: Serialize + ToTauriSchema + Send + 'static,
+    {
+        self.with_type_schemas::<Output>().query(handler)
+    }
+
+    /// Register this procedure as a mutation whose schema is derived from its types.
+    ///
+    /// See [`TypedProcedureChain::typed_query`].
+    #[must_use = "This method returns a Router and does not modify self"]
+    pub fn typed_mutation<H, Fut, Output>(self, handler: H) -> Router<Ctx>
+    where
+        Input: ToTauriSchema,
+        H: Fn(Context<Ctx>, Input) -> Fut + Send + Sync + Clone + 'static,
+        Fut: Future<Output = RpcResult<Output>> + Send + 'static,
+        Output: Serialize + ToTauriSchema + Send + 'static,
+    {
+        self.with_type_schemas::<Output>().mutation(handler)
+    }
+
+    fn with_type_schemas<Output: ToTauriSchema>(mut self) -> Self
+    where
+        Input: ToTauriSchema,
+    {
+        let full_path = self.router.make_path(&self.path);
+        let meta = self.router.meta.remove(&full_path).unwrap_or_default();
+        self.router
+            .meta
+            .insert(full_path, meta.with_types::<Input, Output>());
+        self
+    }
+
     fn build_procedure<H, Fut, Output>(
         self,
         procedure_type: ProcedureType,
@@ -348,6 +448,47 @@ impl<Ctx: Clone + Send + Sync + 'static, Input: DeserializeOwned + Validate + Se
         self.build_validated_procedure(ProcedureType::Mutation, handler)
     }
 
+    /// Register this procedure as a query whose schema is derived from its types.
+    ///
+    /// The input and output reported by [`Router::schema`] come from `Input`
+    /// and the handler's return type, so the docs cannot drift from the code.
+    #[must_use = "This method returns a Router and does not modify self"]
+    pub fn typed_query<H, Fut, Output>(self, handler: H) -> Router<Ctx>
+    where
+        Input: ToTauriSchema,
+        H: Fn(Context<Ctx>, Input) -> Fut + Send + Sync + Clone + 'static,
+        Fut: Future<Output = RpcResult<Output>> + Send + 'static,
+        Output: Serialize + ToTauriSchema + Send + 'static,
+    {
+        self.with_type_schemas::<Output>().query(handler)
+    }
+
+    /// Register this procedure as a mutation whose schema is derived from its types.
+    ///
+    /// See [`ValidatedProcedureChain::typed_query`].
+    #[must_use = "This method returns a Router and does not modify self"]
+    pub fn typed_mutation<H, Fut, Output>(self, handler: H) -> Router<Ctx>
+    where
+        Input: ToTauriSchema,
+        H: Fn(Context<Ctx>, Input) -> Fut + Send + Sync + Clone + 'static,
+        Fut: Future<Output = RpcResult<Output>> + Send + 'static,
+        Output: Serialize + ToTauriSchema + Send + 'static,
+    {
+        self.with_type_schemas::<Output>().mutation(handler)
+    }
+
+    fn with_type_schemas<Output: ToTauriSchema>(mut self) -> Self
+    where
+        Input: ToTauriSchema,
+    {
+        let full_path = self.router.make_path(&self.path);
+        let meta = self.router.meta.remove(&full_path).unwrap_or_default();
+        self.router
+            .meta
+            .insert(full_path, meta.with_types::<Input, Output>());
+        self
+    }
+
     fn build_validated_procedure<H, Fut, Output>(
         self,
         procedure_type: ProcedureType,
@@ -446,6 +587,7 @@ pub(crate) fn wrap_with_middleware<Ctx: Clone + Send + Sync + 'static>(
                     path: String::new(),
                     input,
                     procedure_type,
+                    envelope: ctx.envelope().clone(),
                 };
                 (chain)(ctx, req).await
             })
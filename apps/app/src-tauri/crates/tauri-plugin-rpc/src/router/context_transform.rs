@@ -244,8 +244,9 @@ impl<
 
             Box::pin(async move {
                 // Transform context
+                let parent_ctx = ctx.clone();
                 let new_ctx_state = (context_transformer)(ctx).await?;
-                let new_ctx = Context::new(new_ctx_state);
+                let new_ctx = parent_ctx.rebind(new_ctx_state);
 
                 // Deserialize input
                 let input: Input = serde_json::from_value(input_value).map_err(|e| {
@@ -375,8 +376,9 @@ impl<
 
             Box::pin(async move {
                 // Transform context
+                let parent_ctx = ctx.clone();
                 let new_ctx_state = (context_transformer)(ctx).await?;
-                let new_ctx = Context::new(new_ctx_state);
+                let new_ctx = parent_ctx.rebind(new_ctx_state);
 
                 // Deserialize input
                 let input: Input = serde_json::from_value(input_value).map_err(|e| {
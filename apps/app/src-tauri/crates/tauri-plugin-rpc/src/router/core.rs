@@ -8,20 +8,150 @@ use super::{
 };
 use crate::{
     Context, EmptyContext, RpcError, RpcResult,
-    batch::{BatchConfig, BatchRequest, BatchResponse, BatchResult},
-    handler::{BoxedHandler, Handler, into_boxed},
-    middleware::{MiddlewareFn, Next, ProcedureType, Request},
+    batch::{
+        BatchConfig, BatchRequest, BatchResponse, BatchResult, execute_atomic,
+        execute_with_dependencies,
+    },
+    cache::{Cache, CacheConfig, CachePolicy, CacheResult, cache_middleware},
+    handler::{BoxedCompensation, BoxedHandler, Handler, into_boxed, into_boxed_compensation},
+    middleware::{MiddlewareFn, Next, ProcedureType, Request, RequestEnvelope},
     procedure::RegisteredProcedure,
+    schema::{ProcedureMeta, RouterSchema},
     subscription::{
-        BoxedSubscriptionHandler, Event, SubscriptionContext, SubscriptionHandler,
-        into_boxed_subscription,
+        BoxedSubscriptionHandler, CancellationSignal, Event, SubscriptionContext,
+        SubscriptionHandler, into_boxed_subscription,
     },
 };
+use futures::StreamExt;
 use serde::{Serialize, de::DeserializeOwned};
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc;
 
+/// Reserved procedure path answering with the router schema.
+///
+/// Registered by [`Router::schema_procedure`].
+pub const SCHEMA_PROCEDURE_PATH: &str = "__rpc.schema";
+
+// =============================================================================
+// Timeouts
+// =============================================================================
+
+/// Run a procedure call, failing with `TIMEOUT` once `timeout` elapses.
+///
+/// The call future is dropped on expiry, cancelling the handler at its next
+/// await point.
+pub(crate) async fn with_timeout<F>(
+    path: &str,
+    request_id: Option<&str>,
+    timeout: Option<Duration>,
+    call: F,
+) -> RpcResult<serde_json::Value>
+where
+    F: Future<Output = RpcResult<serde_json::Value>>,
+{
+    let Some(timeout) = timeout else {
+        return call.await;
+    };
+    match tokio::time::timeout(timeout, call).await {
+        Ok(result) => result,
+        Err(_) => {
+            crate::logging::log_procedure_timeout(
+                request_id.unwrap_or_default(),
+                path,
+                timeout.as_millis() as u64,
+            );
+            Err(RpcError::timeout(path, timeout))
+        }
+    }
+}
+
+// =============================================================================
+// Batches
+// =============================================================================
+
+/// Run a batch for [`Router::call_batch`] and [`CompiledRouter::call_batch`].
+///
+/// Every item and compensation is called with the batch's envelope.
+async fn call_batch_with<'a, F, Fut, C, CFut>(
+    batch: &'a BatchRequest,
+    config: &BatchConfig,
+    call: F,
+    compensate: C,
+) -> RpcResult<BatchResponse>
+where
+    F: Fn(&'a str, serde_json::Value, RequestEnvelope) -> Fut,
+    Fut: Future<Output = RpcResult<serde_json::Value>> + 'a,
+    C: Fn(&'a str, serde_json::Value, serde_json::Value, RequestEnvelope) -> CFut,
+    CFut: Future<Output = Option<RpcResult<()>>> + 'a,
+{
+    // Validate batch against configuration
+    batch.validate(config)?;
+    let envelope = &batch.envelope;
+
+    if batch.atomic || config.atomic {
+        let (results, compensations) = execute_atomic(
+            batch,
+            |req, input| call(&req.path, input, envelope.clone()),
+            |req, input, output| compensate(&req.path, input, output, envelope.clone()),
+            &|_| {},
+        )
+        .await;
+        return Ok(BatchResponse::new(results).with_compensations(compensations));
+    }
+
+    if batch.has_dependencies() {
+        let results = execute_with_dependencies(
+            batch,
+            config.concurrency(),
+            |req, input| {
+                with_timeout(
+                    &req.path,
+                    None,
+                    config.item_timeout(),
+                    call(&req.path, input, envelope.clone()),
+                )
+            },
+            &|_| {},
+        )
+        .await;
+        return Ok(BatchResponse::new(results));
+    }
+
+    // Results keep request order; at most `concurrency()` run at once
+    let results = futures::stream::iter(batch.requests.iter().map(|req| {
+        let call = call(&req.path, req.input.clone(), envelope.clone());
+        async move {
+            match with_timeout(&req.path, None, config.item_timeout(), call).await {
+                Ok(data) => BatchResult::success(&req.id, data),
+                Err(error) => BatchResult::error(&req.id, error),
+            }
+        }
+    }))
+    .buffered(config.concurrency())
+    .collect()
+    .await;
+    Ok(BatchResponse::new(results))
+}
+
+// =============================================================================
+// Merging
+// =============================================================================
+
+/// Re-key a merged router's entries under `namespace` (`path` becomes
+/// `namespace.path`; an empty namespace keeps the paths as they are).
+fn prefixed<V>(map: HashMap<String, V>, namespace: &str) -> impl Iterator<Item = (String, V)> {
+    map.into_iter().map(move |(path, value)| {
+        if namespace.is_empty() {
+            (path, value)
+        } else {
+            (format!("{}.{}", namespace, path), value)
+        }
+    })
+}
+
 // =============================================================================
 // Compiled Router
 // =============================================================================
@@ -50,6 +180,14 @@ pub struct CompiledRouter<Ctx: Clone + Send + Sync + 'static> {
     compiled_chains: HashMap<String, CompiledChain<Ctx>>,
     /// Subscription handlers (subscriptions don't use middleware chains)
     subscriptions: HashMap<String, BoxedSubscriptionHandler<Ctx>>,
+    /// Documentation metadata by path
+    meta: HashMap<String, ProcedureMeta>,
+    /// Per-procedure execution timeouts by path
+    timeouts: HashMap<String, Duration>,
+    /// Compensation (undo) handlers by mutation path
+    compensations: HashMap<String, BoxedCompensation<Ctx>>,
+    /// Configurations of the caches added with [`Router::cache`]
+    cache_configs: Vec<CacheConfig>,
 }
 
 impl<Ctx: Clone + Send + Sync + 'static> CompiledRouter<Ctx> {
@@ -75,8 +213,101 @@ impl<Ctx: Clone + Send + Sync + 'static> CompiledRouter<Ctx> {
         self.subscriptions.contains_key(path)
     }
 
+    /// Get the type of a registered procedure
+    pub fn procedure_type(&self, path: &str) -> Option<ProcedureType> {
+        if let Some(compiled) = self.compiled_chains.get(path) {
+            Some(compiled.procedure_type)
+        } else if self.subscriptions.contains_key(path) {
+            Some(ProcedureType::Subscription)
+        } else {
+            None
+        }
+    }
+
+    /// Get the execution timeout configured for a procedure, if any.
+    pub fn timeout(&self, path: &str) -> Option<Duration> {
+        self.timeouts.get(path).copied()
+    }
+
+    /// Get the cache policy declared for a procedure.
+    pub fn cache_policy(&self, path: &str) -> CachePolicy {
+        self.meta
+            .get(path)
+            .map_or(CachePolicy::Auto, |meta| meta.cache_policy)
+    }
+
+    /// Check the caches added with [`Router::cache`] against the procedures
+    ///
+    /// Runs [`CacheConfig::validate`] for each of them.
+    pub fn validate_caches(&self) -> CacheResult<()> {
+        self.cache_configs
+            .iter()
+            .try_for_each(|config| config.validate(self))
+    }
+
+    /// Run the compensation handler registered for a mutation.
+    ///
+    /// Returns `None` when no compensation is registered for `path`.
+    pub async fn call_compensation(
+        &self,
+        path: &str,
+        input: serde_json::Value,
+        output: serde_json::Value,
+        envelope: RequestEnvelope,
+    ) -> Option<RpcResult<()>> {
+        let compensation = self.compensations.get(path)?;
+        let ctx = match self.context.clone() {
+            Some(ctx) => Context::new(ctx).with_envelope(envelope),
+            None => return Some(Err(RpcError::internal("Router context not initialized"))),
+        };
+        Some(compensation(ctx, input, output).await)
+    }
+
+    /// Generate the schema for every registered procedure.
+    ///
+    /// See [`Router::schema`].
+    pub fn schema(&self) -> RouterSchema {
+        let handlers = self
+            .compiled_chains
+            .iter()
+            .map(|(path, chain)| (path, chain.procedure_type));
+        let subscriptions = self
+            .subscriptions
+            .keys()
+            .map(|path| (path, ProcedureType::Subscription));
+        RouterSchema::from_procedures(handlers.chain(subscriptions), &self.meta)
+    }
+
     /// Call a procedure by path using pre-compiled middleware chain
     pub async fn call(&self, path: &str, input: serde_json::Value) -> RpcResult<serde_json::Value> {
+        self.call_with_envelope(path, input, RequestEnvelope::default())
+            .await
+    }
+
+    /// Call a procedure by path with transport metadata attached.
+    ///
+    /// The envelope is exposed to middleware via [`Request::envelope`] and to
+    /// handlers via [`Context::envelope`].
+    pub async fn call_with_envelope(
+        &self,
+        path: &str,
+        input: serde_json::Value,
+        envelope: RequestEnvelope,
+    ) -> RpcResult<serde_json::Value> {
+        self.call_with_cancellation(path, input, envelope, Arc::default())
+            .await
+    }
+
+    /// Call a procedure with a cancellation signal the caller can fire.
+    ///
+    /// The signal is exposed to handlers via [`Context::cancellation`].
+    pub async fn call_with_cancellation(
+        &self,
+        path: &str,
+        input: serde_json::Value,
+        envelope: RequestEnvelope,
+        signal: Arc<CancellationSignal>,
+    ) -> RpcResult<serde_json::Value> {
         // Check if it's a subscription first
         if self.subscriptions.contains_key(path) {
             tracing::debug!(
@@ -109,12 +340,17 @@ impl<Ctx: Clone + Send + Sync + 'static> CompiledRouter<Ctx> {
             self.context
                 .clone()
                 .ok_or_else(|| RpcError::internal("Router context not initialized"))?,
-        );
+        )
+        .with_envelope(envelope.clone())
+        .with_cancellation(signal)
+        .with_cache_policy(self.cache_policy(path));
 
+        let request_id = envelope.request_id.clone();
         let request = Request {
             path: path.to_string(),
             procedure_type: compiled.procedure_type,
             input,
+            envelope,
         };
 
         tracing::trace!(
@@ -124,7 +360,13 @@ impl<Ctx: Clone + Send + Sync + 'static> CompiledRouter<Ctx> {
         );
 
         // Use pre-compiled chain directly - no per-request chain building
-        (compiled.chain.clone())(ctx, request).await
+        with_timeout(
+            path,
+            request_id.as_deref(),
+            self.timeout(path),
+            (compiled.chain.clone())(ctx, request),
+        )
+        .await
     }
 
     /// Subscribe to a streaming procedure
@@ -133,6 +375,18 @@ impl<Ctx: Clone + Send + Sync + 'static> CompiledRouter<Ctx> {
         path: &str,
         input: serde_json::Value,
         sub_ctx: SubscriptionContext,
+    ) -> RpcResult<mpsc::Receiver<Event<serde_json::Value>>> {
+        self.subscribe_with_envelope(path, input, sub_ctx, RequestEnvelope::default())
+            .await
+    }
+
+    /// Subscribe to a streaming procedure with transport metadata attached.
+    pub async fn subscribe_with_envelope(
+        &self,
+        path: &str,
+        input: serde_json::Value,
+        sub_ctx: SubscriptionContext,
+        envelope: RequestEnvelope,
     ) -> RpcResult<mpsc::Receiver<Event<serde_json::Value>>> {
         let handler = self.subscriptions.get(path).ok_or_else(|| {
             tracing::debug!(path = %path, "Subscription procedure not found");
@@ -166,7 +420,8 @@ impl<Ctx: Clone + Send + Sync + 'static> CompiledRouter<Ctx> {
             self.context
                 .clone()
                 .ok_or_else(|| RpcError::internal("Router context not initialized"))?,
-        );
+        )
+        .with_envelope(envelope);
 
         tracing::trace!(
             path = %path,
@@ -212,41 +467,13 @@ impl<Ctx: Clone + Send + Sync + 'static> CompiledRouter<Ctx> {
         batch: BatchRequest,
         config: &BatchConfig,
     ) -> RpcResult<BatchResponse> {
-        // Validate batch against configuration
-        batch.validate(config)?;
-
-        if config.parallel_execution {
-            // Execute all requests in parallel using futures::join_all pattern
-            let futures: Vec<_> = batch
-                .requests
-                .iter()
-                .map(|req| {
-                    let id = req.id.clone();
-                    let path = req.path.clone();
-                    let input = req.input.clone();
-                    async move {
-                        match self.call(&path, input).await {
-                            Ok(data) => BatchResult::success(id, data),
-                            Err(error) => BatchResult::error(id, error),
-                        }
-                    }
-                })
-                .collect();
-
-            let results = futures::future::join_all(futures).await;
-            Ok(BatchResponse::new(results))
-        } else {
-            // Execute requests sequentially
-            let mut results = Vec::with_capacity(batch.requests.len());
-            for req in batch.requests {
-                let result = match self.call(&req.path, req.input).await {
-                    Ok(data) => BatchResult::success(req.id, data),
-                    Err(error) => BatchResult::error(req.id, error),
-                };
-                results.push(result);
-            }
-            Ok(BatchResponse::new(results))
-        }
+        call_batch_with(
+            &batch,
+            config,
+            |path, input, envelope| self.call_with_envelope(path, input, envelope),
+            |path, input, output, envelope| self.call_compensation(path, input, output, envelope),
+        )
+        .await
     }
 }
 
@@ -270,6 +497,10 @@ pub struct Router<Ctx: Clone + Send + Sync + 'static = EmptyContext> {
     pub(crate) context: Option<Ctx>,
     pub(crate) procedures: HashMap<String, Procedure<Ctx>>,
     pub(crate) middleware: Vec<MiddlewareFn<Ctx>>,
+    pub(crate) meta: HashMap<String, ProcedureMeta>,
+    pub(crate) timeouts: HashMap<String, Duration>,
+    pub(crate) compensations: HashMap<String, BoxedCompensation<Ctx>>,
+    pub(crate) cache_configs: Vec<CacheConfig>,
     pub(crate) prefix: String,
 }
 
@@ -286,6 +517,10 @@ impl Router<EmptyContext> {
             context: None,
             procedures: HashMap::new(),
             middleware: Vec::new(),
+            meta: HashMap::new(),
+            timeouts: HashMap::new(),
+            compensations: HashMap::new(),
+            cache_configs: Vec::new(),
             prefix: String::new(),
         }
     }
@@ -301,6 +536,10 @@ impl<Ctx: Clone + Send + Sync + 'static> Router<Ctx> {
             context: Some(ctx),
             procedures: HashMap::new(),
             middleware: Vec::new(),
+            meta: HashMap::new(),
+            timeouts: HashMap::new(),
+            compensations: HashMap::new(),
+            cache_configs: Vec::new(),
             prefix: self.prefix,
         }
     }
@@ -339,6 +578,26 @@ impl<Ctx: Clone + Send + Sync + 'static> Router<Ctx> {
         self
     }
 
+    /// Cache query results in `cache`
+    ///
+    /// Adds [`cache_middleware`] like `.middleware_fn(cache_middleware(cache))`
+    /// would, and keeps the cache configuration so that
+    /// [`validate_caches`](Self::validate_caches) can check it against the
+    /// registered procedures. The plugin runs that check at startup.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let router = Router::new()
+    ///     .context(AppContext::default())
+    ///     .cache(Cache::new(CacheConfig::new()))
+    ///     .query("user.get", get_user);
+    /// ```
+    #[must_use = "This method returns a new Router and does not modify self"]
+    pub fn cache(mut self, cache: Cache) -> Self {
+        self.cache_configs.push(cache.config.clone());
+        self.middleware_fn(cache_middleware(cache))
+    }
+
     /// Add a query procedure (read-only operation)
     #[must_use = "This method returns a new Router and does not modify self"]
     pub fn query<N, Input, Output, H>(mut self, name: N, handler: H) -> Self
@@ -428,15 +687,14 @@ impl<Ctx: Clone + Send + Sync + 'static> Router<Ctx> {
     #[must_use = "This method returns a new Router and does not modify self"]
     pub fn merge<N: Into<String>>(mut self, namespace: N, other: Router<Ctx>) -> Self {
         let namespace = namespace.into();
-        for (path, procedure) in other.procedures {
-            let full_path = if namespace.is_empty() {
-                path
-            } else {
-                format!("{}.{}", namespace, path)
-            };
-            self.procedures.insert(full_path, procedure);
-        }
+        self.procedures
+            .extend(prefixed(other.procedures, &namespace));
+        self.meta.extend(prefixed(other.meta, &namespace));
+        self.timeouts.extend(prefixed(other.timeouts, &namespace));
+        self.compensations
+            .extend(prefixed(other.compensations, &namespace));
         self.middleware.extend(other.middleware);
+        self.cache_configs.extend(other.cache_configs);
         self
     }
 
@@ -497,12 +755,19 @@ impl<Ctx: Clone + Send + Sync + 'static> Router<Ctx> {
                         path: String::new(),
                         input,
                         procedure_type: ProcedureType::Query,
+                        envelope: ctx.envelope().clone(),
                     };
                     (chain)(ctx, req).await
                 })
             })
         };
 
+        if let Some(meta) = procedure.meta {
+            self.meta.insert(full_path.clone(), meta);
+        }
+        if let Some(timeout) = procedure.timeout {
+            self.timeouts.insert(full_path.clone(), timeout);
+        }
         self.procedures.insert(
             full_path,
             Procedure::Handler {
@@ -513,6 +778,100 @@ impl<Ctx: Clone + Send + Sync + 'static> Router<Ctx> {
         self
     }
 
+    /// Attach documentation metadata to a procedure path.
+    ///
+    /// Useful for procedures registered with `query`, `mutation` or
+    /// `subscription`, which have no builder to carry a [`ProcedureMeta`].
+    /// The metadata is picked up by [`Router::schema`].
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let router = Router::new()
+    ///     .context(AppContext::default())
+    ///     .subscription("events", events_handler)
+    ///     .describe("events", ProcedureMeta::new()
+    ///         .description("Stream of application events")
+    ///         .output_type::<AppEvent>());
+    /// ```
+    #[must_use = "This method returns a new Router and does not modify self"]
+    pub fn describe<N: Into<String>>(mut self, name: N, meta: ProcedureMeta) -> Self {
+        let full_path = self.make_path(&name.into());
+        let meta = meta.keep_cache_policy(self.meta.get(&full_path));
+        self.meta.insert(full_path, meta);
+        self
+    }
+
+    /// Register the reserved [`SCHEMA_PROCEDURE_PATH`] query answering with
+    /// this router's schema.
+    ///
+    /// It is an ordinary query, so router middleware (auth, logging, rate
+    /// limiting) runs for it like for any other call, including from inside
+    /// a batch. The schema is captured when this method is called, so call
+    /// it after every other procedure has been registered.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let router = Router::new()
+    ///     .context(AppContext::default())
+    ///     .middleware_fn(auth_middleware(provider))
+    ///     .query("health", health_handler)
+    ///     .merge("users", users_router())
+    ///     .schema_procedure();
+    /// ```
+    #[must_use = "This method returns a new Router and does not modify self"]
+    pub fn schema_procedure(self) -> Self {
+        let meta =
+            ProcedureMeta::new().description("Schema of all procedures exposed by this router");
+        let path = self.make_path(SCHEMA_PROCEDURE_PATH);
+        let schema = self
+            .schema()
+            .add_procedure(path, meta.clone().to_schema(ProcedureType::Query));
+        let schema = serde_json::to_value(schema).unwrap_or_default();
+        self.describe(SCHEMA_PROCEDURE_PATH, meta).query(
+            SCHEMA_PROCEDURE_PATH,
+            move |_ctx: Context<Ctx>, _input: ()| {
+                let schema = schema.clone();
+                async move { Ok(schema) }
+            },
+        )
+    }
+
+    /// Register a compensation (undo) handler for a mutation.
+    ///
+    /// When an atomic batch fails, the compensation of every mutation that
+    /// already succeeded is called with that mutation's input and output, in
+    /// reverse order.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let router = Router::new()
+    ///     .context(AppContext::default())
+    ///     .mutation("users.create", create_user)
+    ///     .compensation("users.create", |ctx: Context<AppContext>, _input: CreateUserInput, user: User| async move {
+    ///         ctx.db.delete_user(user.id).await
+    ///     });
+    /// ```
+    #[must_use = "This method returns a new Router and does not modify self"]
+    pub fn compensation<N, F, Fut, Input, Output>(mut self, name: N, handler: F) -> Self
+    where
+        N: Into<String>,
+        Input: DeserializeOwned + Send + 'static,
+        Output: DeserializeOwned + Send + 'static,
+        F: Fn(Context<Ctx>, Input, Output) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = RpcResult<()>> + Send + 'static,
+    {
+        let full_path = self.make_path(&name.into());
+        self.compensations
+            .insert(full_path, into_boxed_compensation(handler));
+        self
+    }
+
+    /// Declare the cache policy of a procedure in its metadata.
+    pub(crate) fn set_cache_policy(&mut self, name: &str, policy: CachePolicy) {
+        let full_path = self.make_path(name);
+        self.meta.entry(full_path).or_default().cache_policy = policy;
+    }
+
     pub(crate) fn make_path(&self, name: &str) -> String {
         if self.prefix.is_empty() {
             name.to_string()
@@ -533,6 +892,82 @@ impl<Ctx: Clone + Send + Sync + 'static> Router<Ctx> {
         paths
     }
 
+    /// Generate the schema for every registered procedure.
+    ///
+    /// Walks all queries, mutations and subscriptions (including those merged
+    /// under a namespace) and emits a [`RouterSchema`]. Procedures carrying a
+    /// [`ProcedureMeta`] contribute their description, tags and input/output
+    /// schemas, so the exported docs always match the real router.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let router = Router::new()
+    ///     .context(AppContext::default())
+    ///     .procedure("users.get")
+    ///         .meta(ProcedureMeta::new().description("Get a user by ID"))
+    ///         .input::<GetUserInput>()
+    ///         .typed_query(get_user); // input/output schemas from the types
+    ///
+    /// let openapi = router.schema().with_name("My API").to_openapi();
+    /// ```
+    pub fn schema(&self) -> RouterSchema {
+        let procedures = self.procedures.iter().map(|(path, procedure)| {
+            let procedure_type = match procedure {
+                Procedure::Handler { procedure_type, .. } => *procedure_type,
+                Procedure::Subscription { .. } => ProcedureType::Subscription,
+            };
+            (path, procedure_type)
+        });
+        RouterSchema::from_procedures(procedures, &self.meta)
+    }
+
+    /// Get the execution timeout configured for a procedure, if any.
+    pub fn timeout(&self, path: &str) -> Option<Duration> {
+        self.timeouts.get(path).copied()
+    }
+
+    /// Get the cache policy declared for a procedure.
+    pub fn cache_policy(&self, path: &str) -> CachePolicy {
+        self.meta
+            .get(path)
+            .map_or(CachePolicy::Auto, |meta| meta.cache_policy)
+    }
+
+    /// Get the type of a registered procedure
+    pub fn procedure_type(&self, path: &str) -> Option<ProcedureType> {
+        match self.procedures.get(path)? {
+            Procedure::Handler { procedure_type, .. } => Some(*procedure_type),
+            Procedure::Subscription { .. } => Some(ProcedureType::Subscription),
+        }
+    }
+
+    /// Check the caches added with [`Router::cache`] against the procedures
+    ///
+    /// Runs [`CacheConfig::validate`] for each of them.
+    pub fn validate_caches(&self) -> CacheResult<()> {
+        self.cache_configs
+            .iter()
+            .try_for_each(|config| config.validate(self))
+    }
+
+    /// Run the compensation handler registered for a mutation.
+    ///
+    /// Returns `None` when no compensation is registered for `path`.
+    pub async fn call_compensation(
+        &self,
+        path: &str,
+        input: serde_json::Value,
+        output: serde_json::Value,
+        envelope: RequestEnvelope,
+    ) -> Option<RpcResult<()>> {
+        let compensation = self.compensations.get(path)?;
+        let ctx = match self.context.clone() {
+            Some(ctx) => Context::new(ctx).with_envelope(envelope),
+            None => return Some(Err(RpcError::internal("Router context not initialized"))),
+        };
+        Some(compensation(ctx, input, output).await)
+    }
+
     /// Check if a path is a subscription
     pub fn is_subscription(&self, path: &str) -> bool {
         matches!(
@@ -618,11 +1053,40 @@ impl<Ctx: Clone + Send + Sync + 'static> Router<Ctx> {
             context: self.context,
             compiled_chains,
             subscriptions,
+            meta: self.meta,
+            timeouts: self.timeouts,
+            compensations: self.compensations,
+            cache_configs: self.cache_configs,
         }
     }
 
     /// Call a procedure by path
     pub async fn call(&self, path: &str, input: serde_json::Value) -> RpcResult<serde_json::Value> {
+        self.call_with_envelope(path, input, RequestEnvelope::default())
+            .await
+    }
+
+    /// Call a procedure by path with transport metadata attached.
+    pub async fn call_with_envelope(
+        &self,
+        path: &str,
+        input: serde_json::Value,
+        envelope: RequestEnvelope,
+    ) -> RpcResult<serde_json::Value> {
+        self.call_with_cancellation(path, input, envelope, Arc::default())
+            .await
+    }
+
+    /// Call a procedure with a cancellation signal the caller can fire.
+    ///
+    /// The signal is exposed to handlers via [`Context::cancellation`].
+    pub async fn call_with_cancellation(
+        &self,
+        path: &str,
+        input: serde_json::Value,
+        envelope: RequestEnvelope,
+        signal: Arc<CancellationSignal>,
+    ) -> RpcResult<serde_json::Value> {
         let procedure = self.procedures.get(path).ok_or_else(|| {
             // Provide helpful error with available procedures
             let available: Vec<String> = self
@@ -658,12 +1122,17 @@ impl<Ctx: Clone + Send + Sync + 'static> Router<Ctx> {
                     self.context
                         .clone()
                         .ok_or_else(|| RpcError::internal("Router context not initialized"))?,
-                );
+                )
+                .with_envelope(envelope.clone())
+                .with_cancellation(signal)
+                .with_cache_policy(self.cache_policy(path));
 
+                let request_id = envelope.request_id.clone();
                 let request = Request {
                     path: path.to_string(),
                     procedure_type: *procedure_type,
-                    input: input.clone(),
+                    input,
+                    envelope,
                 };
 
                 // Build the handler as the final step
@@ -676,7 +1145,13 @@ impl<Ctx: Clone + Send + Sync + 'static> Router<Ctx> {
                 // Use the shared middleware chain builder
                 let chain = build_middleware_chain(self.middleware.clone(), final_handler);
 
-                chain(ctx, request).await
+                with_timeout(
+                    path,
+                    request_id.as_deref(),
+                    self.timeout(path),
+                    chain(ctx, request),
+                )
+                .await
             }
             Procedure::Subscription { .. } => Err(RpcError::bad_request(
                 "Cannot call subscription procedure with 'call'. Use 'subscribe' instead.",
@@ -690,6 +1165,18 @@ impl<Ctx: Clone + Send + Sync + 'static> Router<Ctx> {
         path: &str,
         input: serde_json::Value,
         sub_ctx: SubscriptionContext,
+    ) -> RpcResult<mpsc::Receiver<Event<serde_json::Value>>> {
+        self.subscribe_with_envelope(path, input, sub_ctx, RequestEnvelope::default())
+            .await
+    }
+
+    /// Subscribe to a streaming procedure with transport metadata attached.
+    pub async fn subscribe_with_envelope(
+        &self,
+        path: &str,
+        input: serde_json::Value,
+        sub_ctx: SubscriptionContext,
+        envelope: RequestEnvelope,
     ) -> RpcResult<mpsc::Receiver<Event<serde_json::Value>>> {
         let procedure = self
             .procedures
@@ -702,7 +1189,8 @@ impl<Ctx: Clone + Send + Sync + 'static> Router<Ctx> {
                     self.context
                         .clone()
                         .ok_or_else(|| RpcError::internal("Router context not initialized"))?,
-                );
+                )
+                .with_envelope(envelope);
 
                 (handler)(ctx, sub_ctx, input).await
             }
@@ -747,41 +1235,13 @@ impl<Ctx: Clone + Send + Sync + 'static> Router<Ctx> {
         batch: BatchRequest,
         config: &BatchConfig,
     ) -> RpcResult<BatchResponse> {
-        // Validate batch against configuration
-        batch.validate(config)?;
-
-        if config.parallel_execution {
-            // Execute all requests in parallel using futures::join_all pattern
-            let futures: Vec<_> = batch
-                .requests
-                .iter()
-                .map(|req| {
-                    let id = req.id.clone();
-                    let path = req.path.clone();
-                    let input = req.input.clone();
-                    async move {
-                        match self.call(&path, input).await {
-                            Ok(data) => BatchResult::success(id, data),
-                            Err(error) => BatchResult::error(id, error),
-                        }
-                    }
-                })
-                .collect();
-
-            let results = futures::future::join_all(futures).await;
-            Ok(BatchResponse::new(results))
-        } else {
-            // Execute requests sequentially
-            let mut results = Vec::with_capacity(batch.requests.len());
-            for req in batch.requests {
-                let result = match self.call(&req.path, req.input).await {
-                    Ok(data) => BatchResult::success(req.id, data),
-                    Err(error) => BatchResult::error(req.id, error),
-                };
-                results.push(result);
-            }
-            Ok(BatchResponse::new(results))
-        }
+        call_batch_with(
+            &batch,
+            config,
+            |path, input, envelope| self.call_with_envelope(path, input, envelope),
+            |path, input, output, envelope| self.call_compensation(path, input, output, envelope),
+        )
+        .await
     }
 
     /// Start building a procedure with the oRPC-style fluent API.
@@ -5,11 +5,16 @@
 use super::core::{CompiledRouter, Router};
 use crate::{
     RpcResult,
-    plugin::DynRouter,
-    subscription::{Event, SubscriptionContext},
+    cache::CachePolicy,
+    middleware::{ProcedureType, RequestEnvelope},
+    plugin::{CompensationFuture, DynRouter},
+    schema::RouterSchema,
+    subscription::{CancellationSignal, Event, SubscriptionContext},
 };
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc;
 
 // =============================================================================
@@ -21,14 +26,61 @@ impl<Ctx: Clone + Send + Sync + 'static> DynRouter for CompiledRouter<Ctx> {
         &'a self,
         path: &'a str,
         input: serde_json::Value,
+        envelope: RequestEnvelope,
     ) -> Pin<Box<dyn Future<Output = RpcResult<serde_json::Value>> + Send + 'a>> {
-        Box::pin(async move { CompiledRouter::call(self, path, input).await })
+        Box::pin(
+            async move { CompiledRouter::call_with_envelope(self, path, input, envelope).await },
+        )
+    }
+
+    fn call_with_cancellation<'a>(
+        &'a self,
+        path: &'a str,
+        input: serde_json::Value,
+        envelope: RequestEnvelope,
+        signal: Arc<CancellationSignal>,
+    ) -> Pin<Box<dyn Future<Output = RpcResult<serde_json::Value>> + Send + 'a>> {
+        Box::pin(async move {
+            CompiledRouter::call_with_cancellation(self, path, input, envelope, signal).await
+        })
     }
 
     fn procedures(&self) -> Vec<String> {
         CompiledRouter::procedures(self)
     }
 
+    fn schema(&self) -> RouterSchema {
+        CompiledRouter::schema(self)
+    }
+
+    fn timeout(&self, path: &str) -> Option<Duration> {
+        CompiledRouter::timeout(self, path)
+    }
+
+    fn procedure_type(&self, path: &str) -> Option<ProcedureType> {
+        CompiledRouter::procedure_type(self, path)
+    }
+
+    fn cache_policy(&self, path: &str) -> CachePolicy {
+        CompiledRouter::cache_policy(self, path)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        CompiledRouter::validate_caches(self).map_err(|e| e.to_string())
+    }
+
+    fn compensate<'a>(
+        &'a self,
+        path: &'a str,
+        input: serde_json::Value,
+        output: serde_json::Value,
+        envelope: RequestEnvelope,
+    ) -> CompensationFuture<'a> {
+        Box::pin(CompiledRouter::call_compensation(
+            self, path, input, output, envelope,
+        ))
+    }
+
     fn is_subscription(&self, path: &str) -> bool {
         CompiledRouter::is_subscription(self, path)
     }
@@ -38,10 +90,13 @@ impl<Ctx: Clone + Send + Sync + 'static> DynRouter for CompiledRouter<Ctx> {
         path: &'a str,
         input: serde_json::Value,
         ctx: SubscriptionContext,
+        envelope: RequestEnvelope,
     ) -> Pin<
         Box<dyn Future<Output = RpcResult<mpsc::Receiver<Event<serde_json::Value>>>> + Send + 'a>,
     > {
-        Box::pin(async move { CompiledRouter::subscribe(self, path, input, ctx).await })
+        Box::pin(async move {
+            CompiledRouter::subscribe_with_envelope(self, path, input, ctx, envelope).await
+        })
     }
 }
 
@@ -54,14 +109,59 @@ impl<Ctx: Clone + Send + Sync + 'static> DynRouter for Router<Ctx> {
         &'a self,
         path: &'a str,
         input: serde_json::Value,
+        envelope: RequestEnvelope,
     ) -> Pin<Box<dyn Future<Output = RpcResult<serde_json::Value>> + Send + 'a>> {
-        Box::pin(async move { Router::call(self, path, input).await })
+        Box::pin(async move { Router::call_with_envelope(self, path, input, envelope).await })
+    }
+
+    fn call_with_cancellation<'a>(
+        &'a self,
+        path: &'a str,
+        input: serde_json::Value,
+        envelope: RequestEnvelope,
+        signal: Arc<CancellationSignal>,
+    ) -> Pin<Box<dyn Future<Output = RpcResult<serde_json::Value>> + Send + 'a>> {
+        Box::pin(async move {
+            Router::call_with_cancellation(self, path, input, envelope, signal).await
+        })
     }
 
     fn procedures(&self) -> Vec<String> {
         Router::procedures(self)
     }
 
+    fn schema(&self) -> RouterSchema {
+        Router::schema(self)
+    }
+
+    fn timeout(&self, path: &str) -> Option<Duration> {
+        Router::timeout(self, path)
+    }
+
+    fn procedure_type(&self, path: &str) -> Option<ProcedureType> {
+        Router::procedure_type(self, path)
+    }
+
+    fn cache_policy(&self, path: &str) -> CachePolicy {
+        Router::cache_policy(self, path)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        Router::validate_caches(self).map_err(|e| e.to_string())
+    }
+
+    fn compensate<'a>(
+        &'a self,
+        path: &'a str,
+        input: serde_json::Value,
+        output: serde_json::Value,
+        envelope: RequestEnvelope,
+    ) -> CompensationFuture<'a> {
+        Box::pin(Router::call_compensation(
+            self, path, input, output, envelope,
+        ))
+    }
+
     fn is_subscription(&self, path: &str) -> bool {
         Router::is_subscription(self, path)
     }
@@ -71,9 +171,12 @@ impl<Ctx: Clone + Send + Sync + 'static> DynRouter for Router<Ctx> {
         path: &'a str,
         input: serde_json::Value,
         ctx: SubscriptionContext,
+        envelope: RequestEnvelope,
     ) -> Pin<
         Box<dyn Future<Output = RpcResult<mpsc::Receiver<Event<serde_json::Value>>>> + Send + 'a>,
     > {
-        Box::pin(async move { Router::subscribe(self, path, input, ctx).await })
+        Box::pin(
+            async move { Router::subscribe_with_envelope(self, path, input, ctx, envelope).await },
+        )
     }
 }
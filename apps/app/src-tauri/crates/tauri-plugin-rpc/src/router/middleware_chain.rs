@@ -106,6 +106,7 @@ mod tests {
             path: "test".to_string(),
             procedure_type: ProcedureType::Query,
             input: serde_json::json!(null),
+            envelope: Default::default(),
         };
 
         let result = chain(ctx, req).await;
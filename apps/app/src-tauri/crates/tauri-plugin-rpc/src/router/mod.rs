@@ -113,7 +113,9 @@ pub use builder::{ProcedureChain, TypedProcedureChain, ValidatedProcedureChain};
 pub use context_transform::{
     ContextTransformedChain, ContextTransformedTypedChain, ContextTransformedValidatedChain,
 };
-pub use core::{CompiledRouter, Router};
+pub use core::{CompiledRouter, Router, SCHEMA_PROCEDURE_PATH};
+
+pub(crate) use core::with_timeout;
 
 #[allow(unused)]
 pub(crate) use middleware_chain::build_middleware_chain;
@@ -23,6 +23,41 @@ struct TestOutput {
     message: String,
 }
 
+impl zod_rs::ZodSchema for TestInput {
+    fn zod_schema() -> &'static str {
+        "z.object({ name: z.string() })"
+    }
+
+    fn ts_type_name() -> &'static str {
+        "TestInput"
+    }
+
+    fn schema_name() -> &'static str {
+        "TestInputSchema"
+    }
+}
+
+impl zod_rs::ZodSchema for TestOutput {
+    fn zod_schema() -> &'static str {
+        "z.object({ message: z.string() })"
+    }
+
+    fn ts_type_name() -> &'static str {
+        "TestOutput"
+    }
+
+    fn schema_name() -> &'static str {
+        "TestOutputSchema"
+    }
+
+    fn metadata() -> zod_rs::SchemaMetadata {
+        zod_rs::SchemaMetadata {
+            description: Some("Greeting".to_string()),
+            ..Default::default()
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct ValidatedInput {
     name: String,
@@ -194,3 +229,284 @@ async fn test_procedure_chain_multiple_procedures() {
         .await;
     assert!(result2.is_ok());
 }
+
+// =============================================================================
+// Schema Generation
+// =============================================================================
+
+fn schema_test_router() -> Router<TestContext> {
+    use crate::schema::ProcedureMeta;
+    use crate::subscription::{EventStream, SubscriptionContext};
+
+    let users = Router::new()
+        .context(TestContext::default())
+        .procedure("get")
+        .meta(ProcedureMeta::new().description("Get a user").tag("users"))
+        .input::<TestInput>()
+        .typed_query(test_handler)
+        .mutation(
+            "delete",
+            |_ctx: Context<TestContext>, _input: TestInput| async move { Ok(()) },
+        );
+
+    Router::new()
+        .context(TestContext::default())
+        .query(
+            "health",
+            |_ctx: Context<TestContext>, _input: ()| async move { Ok("ok") },
+        )
+        .subscription(
+            "events",
+            |_ctx: Context<TestContext>, _sub_ctx: SubscriptionContext, _input: ()| async move {
+                let (_tx, rx) = crate::subscription::event_channel::<String>(1);
+                Ok::<EventStream<String>, crate::RpcError>(rx)
+            },
+        )
+        .describe(
+            "events",
+            ProcedureMeta::new().deprecated().output_type::<String>(),
+        )
+        .merge("users", users)
+}
+
+#[test]
+fn test_router_schema_covers_all_procedures() {
+    use crate::schema::ProcedureTypeSchema;
+
+    let schema = schema_test_router().schema();
+    let mut paths: Vec<_> = schema.procedures.keys().cloned().collect();
+    paths.sort();
+    assert_eq!(paths, vec!["events", "health", "users.delete", "users.get"]);
+
+    let get = &schema.procedures["users.get"];
+    assert_eq!(get.procedure_type, ProcedureTypeSchema::Query);
+    assert_eq!(get.description.as_deref(), Some("Get a user"));
+    assert_eq!(get.tags, vec!["users"]);
+    assert_eq!(get.input.as_ref().unwrap().type_name, "object");
+
+    let delete = &schema.procedures["users.delete"];
+    assert_eq!(delete.procedure_type, ProcedureTypeSchema::Mutation);
+    assert!(delete.description.is_none());
+
+    let events = &schema.procedures["events"];
+    assert_eq!(events.procedure_type, ProcedureTypeSchema::Subscription);
+    assert!(events.deprecated);
+    assert_eq!(events.output.as_ref().unwrap().type_name, "string");
+}
+
+#[test]
+fn test_typed_query_schema_matches_registered_types() {
+    use crate::schema::TypeSchema;
+
+    let schema = schema_test_router().schema();
+    let get = &schema.procedures["users.get"];
+    let to_json = |schema: &TypeSchema| serde_json::to_value(schema).unwrap();
+
+    assert_eq!(
+        to_json(get.input.as_ref().unwrap()),
+        to_json(&TypeSchema::of::<TestInput>())
+    );
+    assert_eq!(
+        to_json(get.output.as_ref().unwrap()),
+        to_json(&TypeSchema::of::<TestOutput>())
+    );
+}
+
+#[test]
+fn test_compiled_router_schema_matches_router() {
+    let router = schema_test_router();
+    let expected = router.schema().to_json();
+    let expected: serde_json::Value = serde_json::from_str(&expected).unwrap();
+
+    let compiled = router.compile().schema().to_json();
+    let compiled: serde_json::Value = serde_json::from_str(&compiled).unwrap();
+    assert_eq!(compiled, expected);
+}
+
+#[test]
+fn test_registered_procedure_meta_in_schema() {
+    use crate::procedure::ProcedureBuilder;
+    use crate::schema::ProcedureMeta;
+
+    let procedure = ProcedureBuilder::<TestContext>::new("users.get")
+        .meta(
+            ProcedureMeta::new()
+                .summary("Get")
+                .description("Get a user"),
+        )
+        .input::<TestInput>()
+        .query(test_handler);
+
+    let schema = Router::new()
+        .context(TestContext::default())
+        .register(procedure)
+        .schema();
+    assert_eq!(
+        schema.procedures["users.get"].description.as_deref(),
+        Some("Get a user")
+    );
+}
+
+// =============================================================================
+// Timeouts
+// =============================================================================
+
+/// Sets the flag when dropped, proving the handler future was cancelled.
+struct DropFlag(std::sync::Arc<std::sync::atomic::AtomicBool>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.store(true, std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+#[tokio::test]
+async fn test_procedure_chain_timeout_drops_handler() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+
+    let dropped = Arc::new(AtomicBool::new(false));
+    let flag = dropped.clone();
+
+    let router = Router::new()
+        .context(TestContext::default())
+        .procedure("slow")
+        .timeout(Duration::from_millis(20))
+        .input::<TestInput>()
+        .query(move |_ctx: Context<TestContext>, _input: TestInput| {
+            let guard = DropFlag(flag.clone());
+            async move {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                drop(guard);
+                Ok(TestOutput {
+                    message: "too late".to_string(),
+                })
+            }
+        });
+
+    let err = router
+        .call("slow", serde_json::json!({"name": "x"}))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code, crate::RpcErrorCode::Timeout);
+    assert_eq!(err.details.as_ref().unwrap()["timeout_ms"], 20);
+    assert!(dropped.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn test_procedure_chain_timeout_not_reached() {
+    use std::time::Duration;
+
+    let router = Router::new()
+        .context(TestContext::default())
+        .procedure("fast")
+        .timeout(Duration::from_secs(5))
+        .input::<TestInput>()
+        .query(test_handler)
+        .compile();
+
+    let result = router
+        .call("fast", serde_json::json!({"name": "World"}))
+        .await
+        .unwrap();
+    assert_eq!(result["message"], "Hello, World!");
+}
+
+#[tokio::test]
+async fn test_registered_procedure_timeout_survives_merge_and_compile() {
+    use crate::procedure::ProcedureBuilder;
+    use std::time::Duration;
+
+    let procedure = ProcedureBuilder::<TestContext>::new("get")
+        .timeout(Duration::from_millis(20))
+        .input::<TestInput>()
+        .query(|_ctx: Context<TestContext>, _input: TestInput| async move {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(TestOutput {
+                message: "too late".to_string(),
+            })
+        });
+
+    let users = Router::new()
+        .context(TestContext::default())
+        .register(procedure);
+    let router = Router::new()
+        .context(TestContext::default())
+        .merge("users", users)
+        .compile();
+
+    assert_eq!(router.timeout("users.get"), Some(Duration::from_millis(20)));
+    let err = router
+        .call("users.get", serde_json::json!({"name": "x"}))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code, crate::RpcErrorCode::Timeout);
+}
+
+#[tokio::test]
+async fn test_schema_procedure_returns_router_schema() {
+    use crate::router::SCHEMA_PROCEDURE_PATH;
+
+    let router = schema_test_router().schema_procedure().compile();
+    assert!(
+        router
+            .procedures()
+            .contains(&SCHEMA_PROCEDURE_PATH.to_string())
+    );
+
+    let schema = router
+        .call(SCHEMA_PROCEDURE_PATH, serde_json::json!(null))
+        .await
+        .unwrap();
+    assert_eq!(schema["procedures"]["users.get"]["procedure_type"], "query");
+    assert_eq!(schema["procedures"]["events"]["deprecated"], true);
+    assert_eq!(
+        schema["procedures"][SCHEMA_PROCEDURE_PATH]["procedure_type"],
+        "query"
+    );
+    assert_eq!(
+        serde_json::to_value(router.schema()).unwrap(),
+        schema,
+        "the procedure answers with the router's own schema"
+    );
+}
+
+#[tokio::test]
+async fn test_schema_procedure_runs_router_middleware() {
+    use crate::middleware::{Next, Request};
+    use crate::router::SCHEMA_PROCEDURE_PATH;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let seen = Arc::new(AtomicUsize::new(0));
+    let counter = seen.clone();
+    let router = schema_test_router()
+        .middleware(
+            move |ctx: Context<TestContext>, req: Request, next: Next<TestContext>| {
+                let counter = counter.clone();
+                async move {
+                    if req.path == SCHEMA_PROCEDURE_PATH {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                        return Err(crate::RpcError::unauthorized("denied"));
+                    }
+                    next(ctx, req).await
+                }
+            },
+        )
+        .schema_procedure();
+
+    let err = router
+        .call(SCHEMA_PROCEDURE_PATH, serde_json::json!(null))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code, crate::RpcErrorCode::Unauthorized);
+
+    let err = router
+        .compile()
+        .call(SCHEMA_PROCEDURE_PATH, serde_json::json!(null))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code, crate::RpcErrorCode::Unauthorized);
+    assert_eq!(seen.load(Ordering::SeqCst), 2);
+}
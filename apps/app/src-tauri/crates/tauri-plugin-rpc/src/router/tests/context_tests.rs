@@ -2,6 +2,7 @@
 
 use crate::{
     Context, RpcError, RpcResult,
+    middleware::RequestEnvelope,
     router::Router,
     validation::{FieldError, Validate, ValidationResult},
 };
@@ -246,3 +247,74 @@ async fn test_context_transformation_with_middleware() {
         "Hello, World! User: user_with_mw, Value: 42"
     );
 }
+
+#[tokio::test]
+async fn test_context_transformation_preserves_envelope() {
+    let router = Router::new()
+        .context(TestContext { value: 1 })
+        .procedure("users.profile")
+        .context(|ctx: Context<TestContext>| async move {
+            let user_id = ctx
+                .envelope()
+                .bearer_token()
+                .ok_or_else(|| RpcError::unauthorized("missing token"))?
+                .to_string();
+            Ok(AuthContext {
+                user_id,
+                original_value: ctx.inner().value,
+            })
+        })
+        .input::<TestInput>()
+        .query(|ctx: Context<AuthContext>, _input: TestInput| async move {
+            Ok(ctx.envelope().request_id.clone())
+        });
+
+    let envelope = RequestEnvelope::new()
+        .with_request_id("req-7")
+        .with_metadata("authorization", "Bearer user123");
+    let result = router
+        .call_with_envelope(
+            "users.profile",
+            serde_json::json!({"name": "World"}),
+            envelope,
+        )
+        .await;
+    assert_eq!(result.unwrap(), serde_json::json!("req-7"));
+}
+
+#[tokio::test]
+async fn test_context_transformation_preserves_cancellation() {
+    use crate::CancellationSignal;
+    use std::sync::Arc;
+
+    let router = Router::new()
+        .context(TestContext { value: 1 })
+        .procedure("users.profile")
+        .context(|ctx: Context<TestContext>| async move {
+            Ok(AuthContext {
+                user_id: "user123".to_string(),
+                original_value: ctx.inner().value,
+            })
+        })
+        .input::<TestInput>()
+        .query(
+            |ctx: Context<AuthContext>, _input: TestInput| async move { Ok(ctx.is_cancelled()) },
+        );
+
+    let signal = Arc::new(CancellationSignal::new());
+    signal.cancel();
+    let result = router
+        .call_with_cancellation(
+            "users.profile",
+            serde_json::json!({"name": "World"}),
+            RequestEnvelope::new(),
+            signal,
+        )
+        .await;
+    assert_eq!(result.unwrap(), serde_json::json!(true));
+
+    let result = router
+        .call("users.profile", serde_json::json!({"name": "World"}))
+        .await;
+    assert_eq!(result.unwrap(), serde_json::json!(false));
+}
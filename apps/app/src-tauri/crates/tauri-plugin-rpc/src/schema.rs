@@ -14,7 +14,7 @@
 //!     .mutation("user.create", create_user);
 //!
 //! // Export schema
-//! let schema = router.export_schema();
+//! let schema = router.schema();
 //! let json = schema.to_json_pretty();
 //! println!("{}", json);
 //!
@@ -22,9 +22,13 @@
 //! let openapi = schema.to_openapi();
 //! ```
 
+use crate::cache::CachePolicy;
 use crate::middleware::ProcedureType;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use zod_rs::integrations::tauri::TauriTypeSchema;
+
+pub use zod_rs::integrations::tauri::ToTauriSchema;
 
 // =============================================================================
 // Procedure Meta (for builder pattern)
@@ -84,6 +88,14 @@ pub struct ProcedureMeta {
     /// Example output value
     #[serde(skip_serializing_if = "Option::is_none")]
     pub example_output: Option<serde_json::Value>,
+    /// Whether the cache middleware may store results, declared with
+    /// `.cacheable()` or `.no_store()` on the procedure builders
+    #[serde(default, skip_serializing_if = "is_auto")]
+    pub cache_policy: CachePolicy,
+}
+
+fn is_auto(policy: &CachePolicy) -> bool {
+    *policy == CachePolicy::Auto
 }
 
 impl ProcedureMeta {
@@ -116,6 +128,24 @@ impl ProcedureMeta {
         self
     }
 
+    /// Set the input type schema from a type implementing [`ToTauriSchema`].
+    ///
+    /// Any `#[derive(ZodSchema)]` type qualifies, so the documented input
+    /// stays in sync with the Rust type the handler deserializes.
+    pub fn input_type<T: ToTauriSchema>(self) -> Self {
+        self.input(TypeSchema::of::<T>())
+    }
+
+    /// Set the output type schema from a type implementing [`ToTauriSchema`].
+    pub fn output_type<T: ToTauriSchema>(self) -> Self {
+        self.output(TypeSchema::of::<T>())
+    }
+
+    /// Set both type schemas from a procedure's registered input and output types.
+    pub(crate) fn with_types<I: ToTauriSchema, O: ToTauriSchema>(self) -> Self {
+        self.input_type::<I>().output_type::<O>()
+    }
+
     /// Mark as deprecated.
     pub fn deprecated(mut self) -> Self {
         self.deprecated = true;
@@ -152,6 +182,24 @@ impl ProcedureMeta {
         self
     }
 
+    /// Set whether the cache middleware may store results.
+    pub fn cache_policy(mut self, policy: CachePolicy) -> Self {
+        self.cache_policy = policy;
+        self
+    }
+
+    /// Keep the cache policy of `previous` unless this metadata declares one.
+    ///
+    /// Lets `.meta()` follow `.cacheable()` or `.no_store()` on a builder.
+    pub(crate) fn keep_cache_policy(mut self, previous: Option<&ProcedureMeta>) -> Self {
+        if let Some(previous) = previous
+            && self.cache_policy == CachePolicy::Auto
+        {
+            self.cache_policy = previous.cache_policy;
+        }
+        self
+    }
+
     /// Convert to a ProcedureSchema with the given procedure type.
     pub fn to_schema(self, procedure_type: ProcedureType) -> ProcedureSchema {
         ProcedureSchema {
@@ -230,6 +278,31 @@ impl RouterSchema {
         self
     }
 
+    /// Build a schema from registered procedure paths and types.
+    ///
+    /// Procedures with attached [`ProcedureMeta`] contribute their description,
+    /// tags and input/output schemas; the rest are emitted with their type only.
+    pub(crate) fn from_procedures<'a>(
+        procedures: impl IntoIterator<Item = (&'a String, ProcedureType)>,
+        meta: &HashMap<String, ProcedureMeta>,
+    ) -> Self {
+        let procedures = procedures
+            .into_iter()
+            .map(|(path, procedure_type)| {
+                let schema = match meta.get(path) {
+                    Some(meta) => meta.clone().to_schema(procedure_type),
+                    None => ProcedureSchema::from_procedure_type(procedure_type),
+                };
+                (path.clone(), schema)
+            })
+            .collect();
+
+        Self {
+            procedures,
+            ..Self::new()
+        }
+    }
+
     /// Convert to JSON string
     pub fn to_json(&self) -> String {
         serde_json::to_string(self).unwrap_or_default()
@@ -619,6 +692,37 @@ impl TypeSchema {
         self.nullable = true;
         self
     }
+
+    /// Create a type schema from a type implementing [`ToTauriSchema`].
+    pub fn of<T: ToTauriSchema>() -> Self {
+        T::to_tauri_schema().into()
+    }
+}
+
+impl From<TauriTypeSchema> for TypeSchema {
+    fn from(schema: TauriTypeSchema) -> Self {
+        Self {
+            type_name: schema.type_name,
+            properties: schema.properties.map(|props| {
+                props
+                    .into_iter()
+                    .map(|(name, prop)| (name, prop.into()))
+                    .collect()
+            }),
+            required: schema.required,
+            items: schema.items.map(|items| Box::new((*items).into())),
+            description: schema.description,
+            example: schema.example,
+            enum_values: schema.enum_values,
+            format: schema.format,
+            minimum: schema.minimum,
+            maximum: schema.maximum,
+            min_length: schema.min_length,
+            max_length: schema.max_length,
+            pattern: schema.pattern,
+            nullable: schema.nullable,
+        }
+    }
 }
 
 // =============================================================================
@@ -1101,6 +1205,36 @@ mod tests {
         assert_eq!(schema.example.unwrap(), json!("hello@example.com"));
     }
 
+    #[test]
+    fn test_type_schema_of_zod_type() {
+        assert_eq!(TypeSchema::of::<String>().type_name, "string");
+        assert_eq!(TypeSchema::of::<i32>().type_name, "integer");
+        assert_eq!(TypeSchema::of::<bool>().type_name, "boolean");
+    }
+
+    #[test]
+    fn test_type_schema_from_tauri_schema_nested() {
+        let tauri = TauriTypeSchema::object()
+            .with_property("tags", TauriTypeSchema::array(TauriTypeSchema::string()))
+            .with_required("tags");
+        let schema = TypeSchema::from(tauri);
+
+        assert_eq!(schema.type_name, "object");
+        assert_eq!(schema.required, vec!["tags"]);
+        let tags = &schema.properties.as_ref().unwrap()["tags"];
+        assert_eq!(tags.type_name, "array");
+        assert_eq!(tags.items.as_ref().unwrap().type_name, "string");
+    }
+
+    #[test]
+    fn test_procedure_meta_typed_schemas() {
+        let meta = ProcedureMeta::new()
+            .input_type::<String>()
+            .output_type::<bool>();
+        assert_eq!(meta.input.unwrap().type_name, "string");
+        assert_eq!(meta.output.unwrap().type_name, "boolean");
+    }
+
     #[test]
     fn test_openapi_from_router_schema() {
         let schema = RouterSchema::new()
@@ -86,7 +86,7 @@ impl SubscriptionContext {
     }
 }
 
-/// Cancellation signal for subscriptions
+/// Cancellation signal for subscriptions and in-flight calls
 #[derive(Debug)]
 pub struct CancellationSignal {
     cancelled: std::sync::atomic::AtomicBool,
@@ -115,10 +115,12 @@ impl CancellationSignal {
 
     /// Wait until cancelled
     pub async fn cancelled(&self) {
+        // Register interest before checking so a concurrent cancel is not missed
+        let notified = self.notify.notified();
         if self.is_cancelled() {
             return;
         }
-        self.notify.notified().await;
+        notified.await;
     }
 }
 
@@ -222,6 +222,532 @@ async fn test_batch_mixed_procedures() {
     assert!(response.results[2].is_error());
 }
 
+#[tokio::test]
+async fn test_batch_item_timeout_isolated() {
+    let router = Router::new()
+        .context(EmptyContext)
+        .query("sleep", |_ctx: Context<EmptyContext>, ms: u64| async move {
+            tokio::time::sleep(std::time::Duration::from_millis(ms)).await;
+            Ok(ms)
+        })
+        .compile();
+    let config = BatchConfig::default()
+        .with_max_concurrency(1)
+        .with_item_timeout(std::time::Duration::from_millis(30));
+    let batch =
+        BatchRequest::new()
+            .add("slow", "sleep", json!(5000))
+            .add("fast", "sleep", json!(0));
+
+    let response = router.call_batch(batch, &config).await.unwrap();
+
+    assert_eq!(
+        response.results[0].get_error().unwrap().code,
+        crate::RpcErrorCode::Timeout
+    );
+    assert_eq!(response.results[1].data().unwrap(), &json!(0));
+}
+
+// =============================================================================
+// Dependent Requests
+// =============================================================================
+
+#[tokio::test]
+async fn test_batch_result_reference() {
+    let router = create_test_router().compile();
+    let config = BatchConfig::default();
+
+    // Listed before its dependency to show scheduling follows `depends_on`
+    let batch = BatchRequest::new()
+        .add_dependent(
+            "profile",
+            "echo",
+            json!({"userId": {"$ref": "createUser#/id"}, "user": {"$ref": "createUser"}}),
+            ["createUser"],
+        )
+        .add("createUser", "item.get", json!({"id": 7}));
+
+    let response = router.call_batch(batch, &config).await.unwrap();
+
+    assert!(response.all_success());
+    assert_eq!(response.results[0].id, "profile");
+    assert_eq!(
+        response.results[0].data().unwrap(),
+        &json!({"userId": 7, "user": {"id": 7, "name": "Item 7"}})
+    );
+}
+
+#[tokio::test]
+async fn test_batch_reference_requires_declared_dependency() {
+    let router = create_test_router().compile();
+    let config = BatchConfig::default();
+
+    // User data that happens to look like a reference stays untouched unless
+    // the request lists the referenced id in `depends_on`.
+    let batch = BatchRequest::new()
+        .add("user", "item.get", json!({"id": 5}))
+        .add("plain", "echo", json!({"schema": {"$ref": "user#/id"}}))
+        .add_dependent(
+            "mixed",
+            "echo",
+            json!({"id": {"$ref": "user#/id"}, "other": {"$ref": "plain"}}),
+            ["user"],
+        );
+
+    let response = router.call_batch(batch, &config).await.unwrap();
+
+    assert!(response.all_success());
+    assert_eq!(
+        response.results[1].data().unwrap(),
+        &json!({"schema": {"$ref": "user#/id"}})
+    );
+    assert_eq!(
+        response.results[2].data().unwrap(),
+        &json!({"id": 5, "other": {"$ref": "plain"}})
+    );
+}
+
+#[tokio::test]
+async fn test_batch_depends_on_orders_execution() {
+    use std::sync::{Arc, Mutex};
+
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let log = order.clone();
+    let router = Router::new()
+        .context(EmptyContext)
+        .query(
+            "record",
+            move |_ctx: Context<EmptyContext>, input: String| {
+                let log = log.clone();
+                async move {
+                    // Give later-listed, independent-looking requests a head start
+                    if input == "first" {
+                        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                    }
+                    log.lock().unwrap().push(input.clone());
+                    Ok(input)
+                }
+            },
+        )
+        .compile();
+
+    for parallel in [true, false] {
+        order.lock().unwrap().clear();
+        let config = BatchConfig::default().with_parallel_execution(parallel);
+        let batch = BatchRequest::new()
+            .add("a", "record", json!("first"))
+            .add_dependent("b", "record", json!("second"), ["a"]);
+
+        let response = router.call_batch(batch, &config).await.unwrap();
+
+        assert!(response.all_success());
+        assert_eq!(*order.lock().unwrap(), vec!["first", "second"]);
+    }
+}
+
+#[tokio::test]
+async fn test_batch_failed_dependency() {
+    let router = create_test_router().compile();
+    let config = BatchConfig::default();
+
+    let batch = BatchRequest::new()
+        .add("1", "fail", json!(null))
+        .add_dependent("2", "echo", json!({"from": {"$ref": "1#/id"}}), ["1"])
+        .add_dependent("3", "echo", json!(null), ["2"])
+        .add("4", "item.get", json!({"id": 4}));
+
+    let response = router.call_batch(batch, &config).await.unwrap();
+
+    let error = response.results[1].get_error().unwrap();
+    assert_eq!(error.code, crate::RpcErrorCode::BadRequest);
+    assert_eq!(
+        error.details.as_ref().unwrap(),
+        &json!({"dependency": "1", "dependency_error": "INTERNAL_ERROR"})
+    );
+    let error = response.results[2].get_error().unwrap();
+    assert_eq!(error.details.as_ref().unwrap()["dependency"], "2");
+    assert!(response.results[3].is_success());
+}
+
+#[tokio::test]
+async fn test_batch_dependency_cycle() {
+    let router = create_test_router().compile();
+    let config = BatchConfig::default();
+
+    let batch = BatchRequest::new()
+        .add_dependent("a", "echo", json!(null), ["b"])
+        .add_dependent("b", "echo", json!({"$ref": "a"}), ["a"])
+        .add_dependent("c", "echo", json!(null), ["a"])
+        .add_dependent("d", "echo", json!(null), ["d"])
+        .add("e", "echo", json!({"ok": true}));
+
+    let response = router.call_batch(batch, &config).await.unwrap();
+
+    for (i, cycle) in [
+        (0, json!(["a", "b"])),
+        (1, json!(["a", "b"])),
+        (3, json!(["d"])),
+    ] {
+        let error = response.results[i].get_error().unwrap();
+        assert!(error.message.contains("dependency cycle"));
+        assert_eq!(error.details.as_ref().unwrap()["cycle"], cycle);
+    }
+    let error = response.results[2].get_error().unwrap();
+    assert_eq!(error.details.as_ref().unwrap()["dependency"], "a");
+    assert!(response.results[4].is_success());
+}
+
+#[tokio::test]
+async fn test_batch_unknown_dependency_and_unresolved_reference() {
+    let router = create_test_router().compile();
+    let config = BatchConfig::default();
+
+    let batch = BatchRequest::new()
+        .add_dependent("1", "echo", json!(null), ["missing"])
+        .add("2", "item.get", json!({"id": 2}))
+        .add_dependent("3", "echo", json!({"$ref": "2#/email"}), ["2"]);
+
+    let response = router.call_batch(batch, &config).await.unwrap();
+
+    let error = response.results[0].get_error().unwrap();
+    assert_eq!(error.details.as_ref().unwrap()["dependency"], "missing");
+    assert!(response.results[1].is_success());
+    let error = response.results[2].get_error().unwrap();
+    assert_eq!(error.details.as_ref().unwrap()["reference"], "2#/email");
+}
+
+#[tokio::test]
+async fn test_execute_batch_with_dependencies() {
+    use crate::RpcConfig;
+    use crate::batch::execute_batch;
+    use std::sync::Arc;
+
+    let router = create_test_router().compile();
+    let batch: BatchRequest = serde_json::from_value(json!({
+        "requests": [
+            {"id": "user", "path": "item.get", "input": {"id": 3}},
+            {"id": "audit", "path": "echo", "dependsOn": ["user"]},
+            {"id": "name", "path": "echo", "input": {"$ref": "user#/name"}, "dependsOn": ["user"]}
+        ]
+    }))
+    .unwrap();
+    assert_eq!(batch.requests[1].depends_on, vec!["user".to_string()]);
+
+    let (response, metrics) = execute_batch(batch, Arc::new(router), &RpcConfig::default())
+        .await
+        .unwrap();
+
+    assert_eq!(metrics.success_count, 3);
+    assert_eq!(response.results[2].data().unwrap(), &json!("Item 3"));
+}
+
+// =============================================================================
+// Atomic Batches
+// =============================================================================
+
+/// Router whose `counter.add` mutation records applied and undone steps.
+fn create_compensating_router(
+    log: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+) -> Router<EmptyContext> {
+    let undo_log = log.clone();
+    Router::new()
+        .context(EmptyContext)
+        .mutation(
+            "counter.add",
+            move |_ctx: Context<EmptyContext>, step: String| {
+                let log = log.clone();
+                async move {
+                    log.lock().unwrap().push(format!("do {}", step));
+                    Ok(step)
+                }
+            },
+        )
+        .compensation(
+            "counter.add",
+            move |_ctx: Context<EmptyContext>, step: String, output: String| {
+                let log = undo_log.clone();
+                async move {
+                    assert_eq!(step, output);
+                    log.lock().unwrap().push(format!("undo {}", step));
+                    Ok(())
+                }
+            },
+        )
+        .query("fail", fail_handler)
+}
+
+#[tokio::test]
+async fn test_atomic_batch_compensates_in_reverse_order() {
+    use std::sync::{Arc, Mutex};
+
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let router = create_compensating_router(log.clone()).compile();
+    let config = BatchConfig::default();
+    let batch = BatchRequest::new()
+        .add("1", "counter.add", json!("a"))
+        .add("2", "counter.add", json!("b"))
+        .add("3", "fail", json!(null))
+        .add("4", "counter.add", json!("c"))
+        .with_atomic(true);
+
+    let response = router.call_batch(batch, &config).await.unwrap();
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec!["do a", "do b", "undo b", "undo a"]
+    );
+    assert!(response.results[0].is_success());
+    assert!(response.results[1].is_success());
+    assert_eq!(
+        response.results[2].get_error().unwrap().code,
+        crate::RpcErrorCode::InternalError
+    );
+    let skipped = response.results[3].get_error().unwrap();
+    assert_eq!(skipped.code, crate::RpcErrorCode::BadRequest);
+    assert_eq!(skipped.details, Some(json!({"aborted_by": "3"})));
+
+    let compensated: Vec<_> = response
+        .compensations
+        .iter()
+        .map(|c| c.id.as_str())
+        .collect();
+    assert_eq!(compensated, vec!["2", "1"]);
+    assert!(response.compensations.iter().all(|c| c.is_success()));
+}
+
+#[tokio::test]
+async fn test_batch_passes_envelope_to_items_and_compensations() {
+    use crate::middleware::RequestEnvelope;
+    use std::sync::{Arc, Mutex};
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let (call_seen, undo_seen) = (seen.clone(), seen.clone());
+    let router = Router::new()
+        .context(EmptyContext)
+        .mutation(
+            "step",
+            move |ctx: Context<EmptyContext>, _input: serde_json::Value| {
+                let seen = call_seen.clone();
+                async move {
+                    let token = ctx.envelope().bearer_token().map(String::from);
+                    seen.lock().unwrap().push(("call", token));
+                    Ok(())
+                }
+            },
+        )
+        .compensation(
+            "step",
+            move |ctx: Context<EmptyContext>, _input: serde_json::Value, _output: ()| {
+                let seen = undo_seen.clone();
+                async move {
+                    let token = ctx.envelope().bearer_token().map(String::from);
+                    seen.lock().unwrap().push(("undo", token));
+                    Ok(())
+                }
+            },
+        )
+        .query("fail", fail_handler);
+    let envelope = RequestEnvelope::new().with_metadata("authorization", "Bearer abc123");
+    let token = Some("abc123".to_string());
+    let batch = || {
+        BatchRequest::new()
+            .add("1", "step", json!(null))
+            .add("2", "fail", json!(null))
+            .with_envelope(envelope.clone())
+    };
+    let config = BatchConfig::default();
+
+    router.call_batch(batch(), &config).await.unwrap();
+    assert_eq!(*seen.lock().unwrap(), vec![("call", token.clone())]);
+
+    seen.lock().unwrap().clear();
+    let router = router.compile();
+    router
+        .call_batch(batch().with_atomic(true), &config)
+        .await
+        .unwrap();
+    assert_eq!(
+        *seen.lock().unwrap(),
+        vec![("call", token.clone()), ("undo", token)]
+    );
+}
+
+#[tokio::test]
+async fn test_atomic_batch_success_runs_no_compensations() {
+    use std::sync::{Arc, Mutex};
+
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let router = create_compensating_router(log.clone()).compile();
+    let config = BatchConfig::default().with_atomic(true);
+    let batch =
+        BatchRequest::new()
+            .add("1", "counter.add", json!("a"))
+            .add("2", "counter.add", json!("b"));
+
+    let response = router.call_batch(batch, &config).await.unwrap();
+
+    assert!(response.all_success());
+    assert!(response.compensations.is_empty());
+    assert_eq!(*log.lock().unwrap(), vec!["do a", "do b"]);
+
+    let json = serde_json::to_value(&response).unwrap();
+    assert!(json.get("compensations").is_none());
+}
+
+#[tokio::test]
+async fn test_atomic_batch_reports_failed_compensation() {
+    let router =
+        Router::new()
+            .context(EmptyContext)
+            .procedure("step")
+            .compensate(
+                |_ctx: Context<EmptyContext>,
+                 _input: serde_json::Value,
+                 _output: serde_json::Value| async {
+                    Err(RpcError::conflict("Already archived"))
+                },
+            )
+            .input::<serde_json::Value>()
+            .mutation(echo_handler)
+            .query("echo", echo_handler)
+            .query("fail", fail_handler)
+            .compile();
+    let batch = BatchRequest::new()
+        .add("step", "step", json!(1))
+        .add("plain", "echo", json!(2))
+        .add("boom", "fail", json!(null))
+        .with_atomic(true);
+
+    let response = router
+        .call_batch(batch, &BatchConfig::default())
+        .await
+        .unwrap();
+
+    // Procedures without a compensation are not reported
+    assert_eq!(response.compensations.len(), 1);
+    let compensation = &response.compensations[0];
+    assert_eq!(compensation.id, "step");
+    assert!(!compensation.is_success());
+    assert_eq!(
+        compensation.error.as_ref().unwrap().code,
+        crate::RpcErrorCode::Conflict
+    );
+}
+
+#[tokio::test]
+async fn test_execute_batch_atomic_compensates() {
+    use crate::RpcConfig;
+    use crate::batch::execute_batch;
+    use std::sync::{Arc, Mutex};
+
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let router = create_compensating_router(log.clone()).compile();
+    let batch: BatchRequest = serde_json::from_value(json!({
+        "atomic": true,
+        "requests": [
+            {"id": "first", "path": "counter.add", "input": "a"},
+            {"id": "second", "path": "fail"}
+        ]
+    }))
+    .unwrap();
+
+    let (response, metrics) = execute_batch(batch, Arc::new(router), &RpcConfig::default())
+        .await
+        .unwrap();
+
+    assert_eq!(metrics.error_count, 1);
+    assert_eq!(response.compensations.len(), 1);
+    assert_eq!(*log.lock().unwrap(), vec!["do a", "undo a"]);
+}
+
+// =============================================================================
+// Streaming
+// =============================================================================
+
+#[tokio::test]
+async fn test_streaming_reports_results_in_completion_order() {
+    use crate::RpcConfig;
+    use crate::batch::execute_batch_streaming;
+    use std::sync::{Arc, Mutex};
+
+    let router = Router::new()
+        .context(EmptyContext)
+        .query("sleep", |_ctx: Context<EmptyContext>, ms: u64| async move {
+            tokio::time::sleep(std::time::Duration::from_millis(ms)).await;
+            Ok(ms)
+        })
+        .compile();
+    let batch = BatchRequest::new()
+        .add("slow", "sleep", json!(60))
+        .add("fast", "sleep", json!(0));
+
+    let seen = Mutex::new(Vec::new());
+    let (response, metrics) =
+        execute_batch_streaming(batch, Arc::new(router), &RpcConfig::default(), &|result| {
+            seen.lock().unwrap().push(result.id.clone())
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(*seen.lock().unwrap(), vec!["fast", "slow"]);
+    // The response keeps request order
+    assert_eq!(response.results[0].id, "slow");
+    assert_eq!(metrics.success_count, 2);
+}
+
+#[tokio::test]
+async fn test_streaming_reports_dependency_failures() {
+    use crate::RpcConfig;
+    use crate::batch::execute_batch_streaming;
+    use std::sync::{Arc, Mutex};
+
+    let batch = BatchRequest::new()
+        .add("boom", "fail", json!(null))
+        .add_dependent("after", "echo", json!(1), ["boom"])
+        .add("ok", "echo", json!(2));
+
+    let seen = Mutex::new(Vec::new());
+    execute_batch_streaming(
+        batch,
+        Arc::new(create_test_router().compile()),
+        &RpcConfig::default(),
+        &|result| seen.lock().unwrap().push(result.id.clone()),
+    )
+    .await
+    .unwrap();
+
+    let mut seen = seen.into_inner().unwrap();
+    seen.sort();
+    assert_eq!(seen, vec!["after", "boom", "ok"]);
+}
+
+#[test]
+fn test_batch_stream_event_serialization() {
+    use crate::batch::{BatchMetrics, BatchResult, BatchStreamEvent, batch_event_name};
+
+    assert_eq!(
+        batch_event_name("rpc:subscription:", "req-1"),
+        "rpc:subscription:batch_req-1"
+    );
+
+    let event = BatchStreamEvent::Result {
+        result: BatchResult::success("1", json!(42)),
+    };
+    assert_eq!(
+        serde_json::to_value(&event).unwrap(),
+        json!({"type": "result", "result": {"id": "1", "data": 42}})
+    );
+
+    let event = BatchStreamEvent::Complete {
+        metrics: BatchMetrics::new(1),
+        compensations: Vec::new(),
+    };
+    let json = serde_json::to_value(&event).unwrap();
+    assert_eq!(json["type"], "complete");
+    assert_eq!(json["metrics"]["total_requests"], 1);
+    assert!(json.get("compensations").is_none());
+}
+
 // =============================================================================
 // Property-Based Tests
 // =============================================================================
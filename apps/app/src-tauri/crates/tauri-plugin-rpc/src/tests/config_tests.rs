@@ -160,6 +160,7 @@ fn test_default_config_values() {
     assert_eq!(config.backpressure_strategy, BackpressureStrategy::Block);
     assert!(!config.debug_logging);
     assert_eq!(config.cleanup_interval_secs, 60);
+    assert!(config.default_timeout_ms.is_none());
 }
 
 #[test]
@@ -193,6 +194,13 @@ fn test_config_validation_errors() {
         config.validate(),
         Err(ConfigValidationError::InvalidCleanupInterval)
     );
+
+    // Zero default timeout
+    let config = RpcConfig::default().with_default_timeout(std::time::Duration::ZERO);
+    assert_eq!(
+        config.validate(),
+        Err(ConfigValidationError::InvalidDefaultTimeout)
+    );
 }
 
 #[test]
@@ -202,7 +210,8 @@ fn test_config_builder_chain() {
         .with_channel_buffer(64)
         .with_backpressure_strategy(BackpressureStrategy::DropOldest)
         .with_debug_logging(true)
-        .with_cleanup_interval(30);
+        .with_cleanup_interval(30)
+        .with_default_timeout(std::time::Duration::from_secs(10));
 
     assert_eq!(config.max_input_size, 512 * 1024);
     assert_eq!(config.default_channel_buffer, 64);
@@ -212,9 +221,21 @@ fn test_config_builder_chain() {
     );
     assert!(config.debug_logging);
     assert_eq!(config.cleanup_interval_secs, 30);
+    assert_eq!(config.default_timeout_ms, Some(10_000));
+    assert_eq!(
+        config.default_timeout(),
+        Some(std::time::Duration::from_secs(10))
+    );
     assert!(config.validate().is_ok());
 }
 
+#[test]
+fn test_default_timeout_serializes_as_millis() {
+    let config = RpcConfig::new().with_default_timeout(std::time::Duration::from_millis(1500));
+    let json = serde_json::to_value(&config).unwrap();
+    assert_eq!(json["default_timeout_ms"], 1500);
+}
+
 #[test]
 fn test_validation_error_display() {
     assert_eq!(
@@ -229,4 +250,8 @@ fn test_validation_error_display() {
         ConfigValidationError::InvalidCleanupInterval.to_string(),
         "cleanup_interval_secs must be greater than 0"
     );
+    assert_eq!(
+        ConfigValidationError::InvalidDefaultTimeout.to_string(),
+        "default_timeout_ms must be greater than 0"
+    );
 }
@@ -19,6 +19,8 @@ fn arb_error_code() -> impl Strategy<Value = RpcErrorCode> {
         Just(RpcErrorCode::InternalError),
         Just(RpcErrorCode::NotImplemented),
         Just(RpcErrorCode::ServiceUnavailable),
+        Just(RpcErrorCode::Timeout),
+        Just(RpcErrorCode::Cancelled),
         Just(RpcErrorCode::ProcedureNotFound),
         Just(RpcErrorCode::SubscriptionError),
         Just(RpcErrorCode::MiddlewareError),
@@ -160,10 +162,12 @@ mod unit_tests {
     fn test_error_code_classification() {
         assert!(RpcErrorCode::BadRequest.is_client_error());
         assert!(RpcErrorCode::NotFound.is_client_error());
+        assert!(RpcErrorCode::Cancelled.is_client_error());
         assert!(!RpcErrorCode::BadRequest.is_server_error());
 
         assert!(RpcErrorCode::InternalError.is_server_error());
         assert!(RpcErrorCode::ServiceUnavailable.is_server_error());
+        assert!(RpcErrorCode::Timeout.is_server_error());
         assert!(!RpcErrorCode::InternalError.is_client_error());
     }
 }
@@ -8,8 +8,8 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use crate::{
-    Context, Router, RpcError, RpcResult,
-    middleware::{Next, Request, Response},
+    Context, Router, RpcError, RpcErrorCode, RpcResult,
+    middleware::{Next, Request, RequestEnvelope, Response},
     router::build_middleware_chain,
 };
 
@@ -507,6 +507,88 @@ async fn test_multiple_async_closures_as_middleware() {
     assert_eq!(log.as_slice(), expected.as_slice());
 }
 
+// =============================================================================
+// Unit Tests for Request Envelope
+// =============================================================================
+
+#[tokio::test]
+async fn test_envelope_visible_to_router_middleware() {
+    let test_ctx = TestContext::default();
+
+    let router = Router::new()
+        .context(test_ctx.clone())
+        .middleware(
+            |ctx: Context<TestContext>, req: Request, next: Next<TestContext>| async move {
+                if req.metadata("Authorization") != Some("Bearer secret") {
+                    return Err(RpcError::unauthorized("missing token"));
+                }
+                assert_eq!(req.request_id(), Some("req-1"));
+                assert_eq!(req.envelope.window_label.as_deref(), Some("main"));
+                next(ctx, req).await
+            },
+        )
+        .query("test", test_handler)
+        .compile();
+
+    let envelope = RequestEnvelope::new()
+        .with_request_id("req-1")
+        .with_metadata("authorization", "Bearer secret")
+        .with_window_label("main");
+
+    let result = router
+        .call_with_envelope("test", serde_json::json!(null), envelope)
+        .await;
+    assert!(result.is_ok());
+
+    // Plain `call` sends an empty envelope
+    let result = router.call("test", serde_json::json!(null)).await;
+    assert_eq!(result.unwrap_err().code, RpcErrorCode::Unauthorized);
+}
+
+#[tokio::test]
+async fn test_envelope_visible_to_procedure_middleware_and_handler() {
+    let router = Router::new()
+        .context(TestContext::default())
+        .procedure("whoami")
+        .use_middleware(
+            |ctx: Context<TestContext>, req: Request, next: Next<TestContext>| async move {
+                if req.metadata("x-client-id").is_none() {
+                    return Err(RpcError::bad_request("missing client id"));
+                }
+                next(ctx, req).await
+            },
+        )
+        .query(|ctx: Context<TestContext>, _: ()| async move {
+            Ok(ctx.envelope().get("x-client-id").map(str::to_string))
+        })
+        .compile();
+
+    let envelope = RequestEnvelope::new().with_metadata("X-Client-Id", "client-42");
+    let result = router
+        .call_with_envelope("whoami", serde_json::json!(null), envelope)
+        .await
+        .unwrap();
+    assert_eq!(result, serde_json::json!("client-42"));
+
+    let result = router.call("whoami", serde_json::json!(null)).await;
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_envelope_deserialization_and_lookup() {
+    let envelope: RequestEnvelope = serde_json::from_value(serde_json::json!({
+        "requestId": "abc",
+        "metadata": { "Authorization": "Bearer token-1" }
+    }))
+    .unwrap();
+
+    assert_eq!(envelope.request_id.as_deref(), Some("abc"));
+    assert_eq!(envelope.get("authorization"), Some("Bearer token-1"));
+    assert_eq!(envelope.bearer_token(), Some("token-1"));
+    assert!(envelope.window_label.is_none());
+    assert!(envelope.get("missing").is_none());
+}
+
 // =============================================================================
 // Property Tests for build_middleware_chain Helper
 // =============================================================================
@@ -588,6 +670,7 @@ proptest! {
                 path: "test".to_string(),
                 procedure_type: ProcedureType::Query,
                 input: serde_json::json!(null),
+                envelope: Default::default(),
             };
 
             let result = chain(ctx, request).await;
@@ -652,6 +735,7 @@ proptest! {
                 path: "test".to_string(),
                 procedure_type: ProcedureType::Query,
                 input: serde_json::json!(null),
+                envelope: Default::default(),
             };
 
             let result = chain(ctx, request).await;
@@ -6,6 +6,7 @@
 //! - generate_request_id: UUID v7 format validation
 //! - validate_input_size: Heuristic-based validation
 //! - validate_path: Iterator-based validation
+//! - InFlightRequests / call_cancellable: `rpc_cancel` support
 
 use crate::validation::{validate_input_size, validate_path};
 use crate::{RpcConfig, RpcError, RpcErrorCode};
@@ -453,3 +454,228 @@ mod integration_tests {
         RpcConfig::default().with_max_input_size(max_size)
     }
 }
+
+// =============================================================================
+// Cancellation Tests
+// =============================================================================
+
+#[cfg(test)]
+mod cancellation_tests {
+    use super::*;
+    use crate::middleware::RequestEnvelope;
+    use crate::plugin::{InFlightRequests, call_cancellable};
+    use crate::{Context, Router};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn test_in_flight_cancel_fires_signal() {
+        let requests = InFlightRequests::default();
+        let guard = requests.register("main", "req-1");
+
+        assert!(requests.cancel("main", "req-1"));
+        assert!(guard.signal().is_cancelled());
+        assert!(!requests.cancel("main", "req-2"));
+    }
+
+    #[test]
+    fn test_in_flight_guard_unregisters_on_drop() {
+        let requests = InFlightRequests::default();
+        drop(requests.register("main", "req-1"));
+        assert!(!requests.cancel("main", "req-1"));
+    }
+
+    #[test]
+    fn test_in_flight_reused_id_keeps_newer_registration() {
+        let requests = InFlightRequests::default();
+        let first = requests.register("main", "req-1");
+        let second = requests.register("main", "req-1");
+
+        drop(first);
+        assert!(requests.cancel("main", "req-1"));
+        assert!(second.signal().is_cancelled());
+    }
+
+    #[test]
+    fn test_in_flight_cancel_is_scoped_to_the_calling_window() {
+        let requests = InFlightRequests::default();
+        let guard = requests.register("main", "req-1");
+
+        assert!(!requests.cancel("other", "req-1"));
+        assert!(!guard.signal().is_cancelled());
+
+        // Nor does it leave a cancel behind for the window's later calls
+        drop(guard);
+        assert!(!requests.register("main", "req-1").signal().is_cancelled());
+        assert!(requests.register("other", "req-1").signal().is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn test_call_cancellable_returns_cancelled() {
+        let router = Router::new()
+            .context(())
+            .query("scan", |_ctx: Context<()>, _input: ()| async move {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok("done")
+            })
+            .compile();
+
+        let requests = Arc::new(InFlightRequests::default());
+        let guard = requests.register("main", "req-1");
+        let canceller = requests.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            canceller.cancel("main", "req-1");
+        });
+
+        let err = call_cancellable(
+            &router,
+            "scan",
+            json!(null),
+            RequestEnvelope::new().with_request_id("req-1"),
+            None,
+            guard.signal(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.code, RpcErrorCode::Cancelled);
+    }
+
+    #[test]
+    fn test_in_flight_cancel_before_register_is_applied() {
+        let requests = InFlightRequests::default();
+
+        assert!(!requests.cancel("main", "req-1"));
+        let guard = requests.register("main", "req-1");
+        assert!(guard.signal().is_cancelled());
+
+        // The early cancel is consumed by the first registration
+        drop(guard);
+        assert!(!requests.register("main", "req-1").signal().is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn test_handler_observes_cancellation() {
+        let observed = Arc::new(AtomicBool::new(false));
+        let flag = observed.clone();
+
+        let router = Router::new()
+            .context(())
+            .query("export", move |ctx: Context<()>, _input: ()| {
+                let flag = flag.clone();
+                async move {
+                    ctx.cancellation().cancelled().await;
+                    flag.store(ctx.is_cancelled(), Ordering::SeqCst);
+                    Err::<(), _>(RpcError::cancelled("export"))
+                }
+            })
+            .compile();
+
+        let signal = Arc::new(crate::CancellationSignal::new());
+        let canceller = signal.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            canceller.cancel();
+        });
+
+        let err = call_cancellable(
+            &router,
+            "export",
+            json!(null),
+            RequestEnvelope::new(),
+            None,
+            signal,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.code, RpcErrorCode::Cancelled);
+        assert!(observed.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_errors_after_cancel_keep_their_code() {
+        let router = Router::new()
+            .context(())
+            .query("export", |ctx: Context<()>, _input: ()| async move {
+                ctx.cancellation().cancelled().await;
+                Err::<(), _>(RpcError::validation("bad range"))
+            })
+            .compile();
+
+        let signal = Arc::new(crate::CancellationSignal::new());
+        let canceller = signal.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            canceller.cancel();
+        });
+
+        let err = call_cancellable(
+            &router,
+            "export",
+            json!(null),
+            RequestEnvelope::new(),
+            None,
+            signal,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.code, RpcErrorCode::ValidationError);
+    }
+
+    #[tokio::test]
+    async fn test_call_cancelled_before_start_skips_handler() {
+        let ran = Arc::new(AtomicBool::new(false));
+        let flag = ran.clone();
+        let router = Router::new()
+            .context(())
+            .query("export", move |_ctx: Context<()>, _input: ()| {
+                let flag = flag.clone();
+                async move {
+                    flag.store(true, Ordering::SeqCst);
+                    Ok("done")
+                }
+            })
+            .compile();
+
+        let requests = InFlightRequests::default();
+        requests.cancel("main", "req-1");
+        let guard = requests.register("main", "req-1");
+
+        let err = call_cancellable(
+            &router,
+            "export",
+            json!(null),
+            RequestEnvelope::new().with_request_id("req-1"),
+            None,
+            guard.signal(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.code, RpcErrorCode::Cancelled);
+        assert!(!ran.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_call_cancellable_passes_through_results() {
+        let router = Router::new()
+            .context(())
+            .query(
+                "health",
+                |_ctx: Context<()>, _input: ()| async move { Ok("ok") },
+            )
+            .compile();
+
+        let result = call_cancellable(
+            &router,
+            "health",
+            json!(null),
+            RequestEnvelope::new(),
+            None,
+            Arc::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result, json!("ok"));
+    }
+}
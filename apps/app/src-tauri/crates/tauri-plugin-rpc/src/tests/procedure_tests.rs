@@ -317,6 +317,35 @@ fn test_context_transformed_validated_procedure_with_meta() {
     );
 }
 
+#[test]
+fn test_procedure_builder_with_timeout() {
+    let procedure = ProcedureBuilder::<TestContext>::new("users.get")
+        .timeout(std::time::Duration::from_secs(2))
+        .input::<TestInput>()
+        .query(test_handler);
+
+    assert_eq!(procedure.timeout, Some(std::time::Duration::from_secs(2)));
+
+    let procedure = ProcedureBuilder::<TestContext>::new("users.get")
+        .input::<TestInput>()
+        .query(test_handler);
+    assert!(procedure.timeout.is_none());
+}
+
+#[test]
+fn test_context_transformed_validated_procedure_with_timeout() {
+    let procedure = ProcedureBuilder::<TestContext>::new("users.update")
+        .context(|ctx: Context<TestContext>| async move { Ok(ctx.inner().clone()) })
+        .input_validated::<ValidatedInput>()
+        .timeout(std::time::Duration::from_millis(250))
+        .mutation(validated_handler);
+
+    assert_eq!(
+        procedure.timeout,
+        Some(std::time::Duration::from_millis(250))
+    );
+}
+
 #[tokio::test]
 async fn test_procedure_handler_execution() {
     let procedure = ProcedureBuilder::<TestContext>::new("test")
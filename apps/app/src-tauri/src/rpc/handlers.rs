@@ -53,6 +53,8 @@ pub fn create_router() -> Router<AppContext> {
         .merge("user", user_router())
         // Subscription examples
         .merge("stream", stream_router())
+        // Live schema for the docs page (registered last to include everything)
+        .schema_procedure()
 }
 
 /// User sub-router
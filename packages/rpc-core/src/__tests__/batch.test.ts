@@ -202,6 +202,7 @@ describe("TypedBatchBuilder", () => {
             { id: "g", path: "greet", input: { name: "World" } },
           ],
         },
+        meta: {},
       });
 
       expect(response).toBeInstanceOf(TypedBatchResponseWrapper);
@@ -471,6 +472,7 @@ describe("Client batch() Method", () => {
           { id: "g", path: "greet", input: { name: "Test" } },
         ],
       },
+      meta: {},
     });
 
     expect(response.successCount).toBe(2);
@@ -501,6 +503,7 @@ describe("Client batch() Method", () => {
           { id: "list", path: "user.list", input: null },
         ],
       },
+      meta: {},
     });
 
     const u1Result = response.getResult("u1");
@@ -12,6 +12,7 @@ import {
   configureRpc,
   getConfig,
   call,
+  executeBatch,
   isRpcError,
   hasErrorCode,
   createError,
@@ -28,6 +29,11 @@ vi.mock("@tauri-apps/api/core", () => ({
 
 const mockInvoke = invoke as ReturnType<typeof vi.fn>;
 
+// Every call carries its tracing IDs in the request envelope
+const tracedEnvelope = {
+  metadata: { traceId: expect.any(String), spanId: expect.any(String) },
+};
+
 // =============================================================================
 // Test Contract
 // =============================================================================
@@ -259,6 +265,7 @@ describe("call()", () => {
     expect(mockInvoke).toHaveBeenCalledWith("plugin:rpc|rpc_call", {
       path: "user.get",
       input: { id: 1 },
+      meta: tracedEnvelope,
     });
     expect(result).toEqual({ id: 1, name: "John" });
   });
@@ -271,10 +278,53 @@ describe("call()", () => {
     expect(mockInvoke).toHaveBeenCalledWith("plugin:rpc|rpc_call", {
       path: "health",
       input: null,
+      meta: tracedEnvelope,
     });
     expect(result).toEqual({ status: "ok" });
   });
 
+  it("should send call metadata and middleware headers in the envelope", async () => {
+    const auth: Middleware = async (ctx, next) => {
+      ctx.meta!.authorization = "Bearer abc123";
+      return next();
+    };
+    configureRpc({ middleware: [auth] });
+    mockInvoke.mockResolvedValueOnce({ id: 1, name: "John" });
+
+    await call(
+      "user.get",
+      { id: 1 },
+      { meta: { "x-client-id": "c1", n: 2 } },
+    );
+
+    expect(mockInvoke).toHaveBeenCalledWith("plugin:rpc|rpc_call", {
+      path: "user.get",
+      input: { id: 1 },
+      meta: {
+        metadata: {
+          "x-client-id": "c1",
+          n: "2",
+          authorization: "Bearer abc123",
+          traceId: expect.any(String),
+          spanId: expect.any(String),
+        },
+      },
+    });
+  });
+
+  it("should send batch metadata in the envelope", async () => {
+    mockInvoke.mockResolvedValueOnce({ results: [] });
+
+    await executeBatch([{ id: "h", path: "health", input: undefined }], {
+      meta: { authorization: "Bearer abc123" },
+    });
+
+    expect(mockInvoke).toHaveBeenCalledWith("plugin:rpc|rpc_call_batch", {
+      batch: { requests: [{ id: "h", path: "health", input: null }] },
+      meta: { metadata: { authorization: "Bearer abc123" } },
+    });
+  });
+
   it("should throw RPC error on failure", async () => {
     const errorResponse = JSON.stringify({
       code: "NOT_FOUND",
@@ -532,6 +582,7 @@ describe("Client Proxy", () => {
     expect(mockInvoke).toHaveBeenCalledWith("plugin:rpc|rpc_call", {
       path: "user.get",
       input: { id: 1 },
+      meta: tracedEnvelope,
     });
   });
 
@@ -546,6 +597,7 @@ describe("Client Proxy", () => {
     expect(mockInvoke).toHaveBeenCalledWith("plugin:rpc|rpc_call", {
       path: "health",
       input: null,
+      meta: tracedEnvelope,
     });
   });
 
@@ -558,6 +610,7 @@ describe("Client Proxy", () => {
     expect(mockInvoke).toHaveBeenCalledWith("plugin:rpc|rpc_call", {
       path: "greet",
       input: { name: "World" },
+      meta: tracedEnvelope,
     });
   });
 
@@ -581,6 +634,7 @@ describe("Client Proxy", () => {
     expect(mockInvoke).toHaveBeenCalledWith("plugin:rpc|rpc_call", {
       path: "a.b.c.method",
       input: null,
+      meta: tracedEnvelope,
     });
   });
 });
@@ -8,8 +8,11 @@ import * as fc from "fast-check";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import {
+  configureRpc,
   createEventIterator,
   consumeEventIterator,
+  executeBatchStream,
+  onCacheInvalidation,
 } from "@tauri-nexus/rpc-core";
 import type { RpcError } from "@tauri-nexus/rpc-core";
 
@@ -99,6 +102,26 @@ describe("createEventIterator()", () => {
     await iterator.return();
   });
 
+  it("should listen with the configured event prefix", async () => {
+    createMockEventEmitter();
+    configureRpc({ eventPrefix: "custom:events:" });
+
+    try {
+      const iterator = await createEventIterator<number>("stream.counter", {
+        start: 0,
+      });
+
+      expect(mockListen).toHaveBeenCalledWith(
+        expect.stringMatching(/^custom:events:sub_/),
+        expect.any(Function),
+      );
+
+      await iterator.return();
+    } finally {
+      configureRpc({ eventPrefix: undefined });
+    }
+  });
+
   it("should invoke backend subscribe command", async () => {
     createMockEventEmitter();
 
@@ -111,6 +134,7 @@ describe("createEventIterator()", () => {
         path: "stream.counter",
         input: { start: 0 },
       }),
+      meta: {},
     });
 
     await iterator.return();
@@ -129,6 +153,7 @@ describe("createEventIterator()", () => {
       request: expect.objectContaining({
         lastEventId: "event-123",
       }),
+      meta: {},
     });
 
     await iterator.return();
@@ -794,3 +819,69 @@ describe("Property-Based Tests", () => {
     );
   });
 });
+
+// =============================================================================
+// Streaming Batch Tests
+// =============================================================================
+
+describe("executeBatchStream()", () => {
+  it("should listen for the batch's events with the configured prefix", async () => {
+    const emitter = createMockEventEmitter();
+    const metrics = {
+      total_requests: 1,
+      success_count: 1,
+      error_count: 0,
+      duration_ms: 1,
+    };
+    mockInvoke.mockImplementation(async () => {
+      emitter.emit({ type: "result", result: { id: "h", data: "ok" } });
+      return metrics;
+    });
+    configureRpc({ eventPrefix: "custom:events:" });
+
+    try {
+      const results: unknown[] = [];
+      const result = await executeBatchStream(
+        [{ id: "h", path: "health", input: null }],
+        (r) => results.push(r),
+      );
+
+      const requestId = mockInvoke.mock.calls[0][1].meta.requestId;
+      expect(mockListen).toHaveBeenCalledWith(
+        `custom:events:batch_${requestId}`,
+        expect.any(Function),
+      );
+      expect(results).toEqual([{ id: "h", data: "ok" }]);
+      expect(result).toEqual(metrics);
+      expect(emitter.unlisten).toHaveBeenCalled();
+    } finally {
+      configureRpc({ eventPrefix: undefined });
+    }
+  });
+});
+
+// =============================================================================
+// Cache Invalidation Tests
+// =============================================================================
+
+describe("onCacheInvalidation()", () => {
+  it("should listen with the configured event prefix", async () => {
+    const emitter = createMockEventEmitter();
+    configureRpc({ eventPrefix: "custom:events:" });
+
+    try {
+      const received: unknown[] = [];
+      await onCacheInvalidation((invalidation) => received.push(invalidation));
+      const invalidation = { cause: "all", entries: [] };
+      emitter.emit(invalidation);
+
+      expect(mockListen).toHaveBeenCalledWith(
+        "custom:events:cache_invalidation",
+        expect.any(Function),
+      );
+      expect(received).toEqual([invalidation]);
+    } finally {
+      configureRpc({ eventPrefix: undefined });
+    }
+  });
+});
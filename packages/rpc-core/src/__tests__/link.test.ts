@@ -34,6 +34,11 @@ import { invoke } from "@tauri-apps/api/core";
 const mockInvoke = invoke as ReturnType<typeof vi.fn>;
 const mockCreateEventIterator = createEventIterator as ReturnType<typeof vi.fn>;
 
+// Every call carries its tracing IDs in the request envelope
+const tracedEnvelope = {
+  metadata: { traceId: expect.any(String), spanId: expect.any(String) },
+};
+
 describe("TauriLink", () => {
   beforeEach(() => {
     vi.clearAllMocks();
@@ -50,6 +55,7 @@ describe("TauriLink", () => {
       expect(mockInvoke).toHaveBeenCalledWith("plugin:rpc|rpc_call", {
         path: "health",
         input: null,
+        meta: tracedEnvelope,
       });
     });
 
@@ -63,6 +69,7 @@ describe("TauriLink", () => {
       expect(mockInvoke).toHaveBeenCalledWith("plugin:rpc|rpc_call", {
         path: "user.get",
         input: { id: 1 },
+        meta: tracedEnvelope,
       });
     });
 
@@ -326,6 +333,7 @@ describe("createClientFromLink", () => {
     expect(mockInvoke).toHaveBeenCalledWith("plugin:rpc|rpc_call", {
       path: "user.get",
       input: { id: 1 },
+      meta: tracedEnvelope,
     });
   });
 
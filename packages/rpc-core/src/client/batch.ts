@@ -7,6 +7,7 @@ import { Effect, pipe } from "effect";
 import { invoke } from "@tauri-apps/api/core";
 import type {
   SingleRequest,
+  BatchEntryOptions,
   BatchCallOptions,
   BatchResponse,
   BatchRequest,
@@ -22,7 +23,7 @@ import {
   createCallError,
   type RpcEffectError,
 } from "@tauri-nexus/rpc-effect";
-import { toRpcError, parseEffectError } from "../internal";
+import { toRpcError, parseEffectError, toRequestEnvelope } from "../internal";
 
 // =============================================================================
 // Types
@@ -32,6 +33,7 @@ interface BatchEntry {
   id: string;
   path: string;
   input: unknown;
+  dependsOn?: readonly string[];
 }
 
 type OutputTypeMap = Record<string, unknown>;
@@ -54,13 +56,17 @@ export const executeBatchEffect = <T = unknown>(
       input: req.input === undefined ? null : req.input,
     }));
 
-    const batchRequest: BatchRequest = { requests: normalizedRequests };
+    const batchRequest: BatchRequest = {
+      requests: normalizedRequests,
+      atomic: options?.atomic,
+    };
     const timeoutMs = options?.timeout;
 
     const executeInvoke = Effect.tryPromise({
       try: () =>
         invoke<BatchResponse<T>>("plugin:rpc|rpc_call_batch", {
           batch: batchRequest,
+          meta: toRequestEnvelope(options?.meta),
         }),
       catch: (error) => parseEffectError(error, "batch", timeoutMs),
     });
@@ -95,11 +101,12 @@ export class EffectBatchBuilder<
     id: TId,
     path: TPath,
     input: GetInputAtPath<TContract, TPath>,
+    options?: BatchEntryOptions,
   ): EffectBatchBuilder<
     TContract,
     TOutputMap & Record<TId, GetOutputAtPath<TContract, TPath>>
   > {
-    this.entries.push({ id, path, input });
+    this.entries.push({ id, path, input, dependsOn: options?.dependsOn });
     return this as unknown as EffectBatchBuilder<
       TContract,
       TOutputMap & Record<TId, GetOutputAtPath<TContract, TPath>>
@@ -111,6 +118,7 @@ export class EffectBatchBuilder<
       id: e.id,
       path: e.path,
       input: e.input,
+      ...(e.dependsOn && { dependsOn: e.dependsOn }),
     }));
   }
 
@@ -17,6 +17,11 @@ export interface RpcClientConfig {
   subscriptionPaths?: string[];
   /** Global request timeout in milliseconds */
   timeout?: number;
+  /**
+   * Prefix of the plugin's Tauri events, matching the backend's
+   * `PluginConfig::with_event_prefix` (default `"rpc:subscription:"`)
+   */
+  eventPrefix?: string;
   /** Called before each request */
   onRequest?: (ctx: RequestContext) => void;
   /** Called after successful response */
@@ -29,6 +34,9 @@ export interface RpcClientConfig {
 // Global Configuration
 // =============================================================================
 
+/** Default prefix of the plugin's Tauri events */
+export const DEFAULT_EVENT_PREFIX = "rpc:subscription:";
+
 /** Global configuration store */
 let globalConfig: RpcClientConfig = {};
 
@@ -46,6 +54,13 @@ export function getConfig(): RpcClientConfig {
   return globalConfig;
 }
 
+/**
+ * Get the prefix of the plugin's Tauri events.
+ */
+export function getEventPrefix(): string {
+  return globalConfig.eventPrefix ?? DEFAULT_EVENT_PREFIX;
+}
+
 /**
  * Check if path is a subscription.
  */
@@ -7,6 +7,7 @@
 export {
   configureRpc,
   getConfig,
+  getEventPrefix,
   isSubscriptionPath,
   type RpcClientConfig,
 } from "./config";
@@ -17,6 +17,12 @@ export type {
   Event,
   EventMeta,
   SingleRequest,
+  BatchEntryOptions,
+  CompensationResult,
+  BatchMetrics,
+  BatchStreamEvent,
+  CacheInvalidation,
+  InvalidatedEntry,
   BatchRequest,
   BatchResult,
   BatchResponse,
@@ -24,6 +30,7 @@ export type {
   SubscriptionOptions,
   BatchCallOptions,
   SubscribeRequest,
+  RequestEnvelope,
   RequestContext,
   ResponseContext,
   Middleware,
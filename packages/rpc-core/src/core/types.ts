@@ -96,6 +96,18 @@ export interface SingleRequest {
   readonly id: string;
   readonly path: string;
   readonly input: unknown;
+  /**
+   * Ids of requests in the same batch that must succeed first. Inputs may
+   * reference their results with `{ $ref: "id#/json/pointer" }`; references
+   * to ids not listed here are left as plain data.
+   */
+  readonly dependsOn?: readonly string[];
+}
+
+/** Options for a single batch entry */
+export interface BatchEntryOptions {
+  /** Ids of requests in the same batch that must succeed first */
+  readonly dependsOn?: readonly string[];
 }
 
 /**
@@ -103,6 +115,11 @@ export interface SingleRequest {
  */
 export interface BatchRequest {
   readonly requests: readonly SingleRequest[];
+  /**
+   * Stop at the first error and undo already-succeeded mutations with their
+   * registered compensation handlers, in reverse order.
+   */
+  readonly atomic?: boolean;
 }
 
 /**
@@ -119,8 +136,66 @@ export interface BatchResult<T = unknown> {
  */
 export interface BatchResponse<T = unknown> {
   readonly results: readonly BatchResult<T>[];
+  /** Compensations run after an atomic batch failed, in execution order */
+  readonly compensations?: readonly CompensationResult[];
+}
+
+/**
+ * Metrics collected during batch execution.
+ */
+export interface BatchMetrics {
+  readonly total_requests: number;
+  readonly success_count: number;
+  readonly error_count: number;
+  readonly duration_ms: number;
+}
+
+/**
+ * Event emitted while a streaming batch runs: one `result` per request, in
+ * completion order, then a single `complete`.
+ */
+export type BatchStreamEvent<T = unknown> =
+  | { readonly type: "result"; readonly result: BatchResult<T> }
+  | {
+      readonly type: "complete";
+      readonly metrics: BatchMetrics;
+      readonly compensations?: readonly CompensationResult[];
+    };
+
+/**
+ * Outcome of a compensation handler run after an atomic batch failed.
+ */
+export interface CompensationResult {
+  readonly id: string;
+  readonly path: string;
+  readonly error?: RpcError;
 }
 
+// =============================================================================
+// Cache Invalidation
+// =============================================================================
+
+/** A server cache entry, identified by the call that produced it */
+export interface InvalidatedEntry {
+  readonly path: string;
+  readonly input: unknown;
+  /** Set for procedures cached per user */
+  readonly user?: string;
+}
+
+/**
+ * Emitted whenever the server cache invalidates entries.
+ *
+ * `entries` lists the requested entries for `entries`, the removed entries
+ * for `pattern` and `tags`, and is empty for `all`.
+ */
+export type CacheInvalidation = { readonly entries: readonly InvalidatedEntry[] } & (
+  | { readonly cause: "entries" }
+  | { readonly cause: "pattern"; readonly pattern: string }
+  | { readonly cause: "tags"; readonly tags: readonly string[] }
+  | { readonly cause: "all" }
+);
+
 // =============================================================================
 // Call Options
 // =============================================================================
@@ -145,6 +220,8 @@ export interface BatchCallOptions {
   readonly signal?: AbortSignal;
   readonly timeout?: number;
   readonly meta?: Record<string, unknown>;
+  /** Run the batch all-or-nothing (see `BatchRequest.atomic`) */
+  readonly atomic?: boolean;
 }
 
 // =============================================================================
@@ -159,6 +236,18 @@ export interface SubscribeRequest {
   readonly lastEventId?: string;
 }
 
+// =============================================================================
+// Request Envelope
+// =============================================================================
+
+/** Transport metadata sent as `meta` with every backend command */
+export interface RequestEnvelope {
+  /** Identifies the call, e.g. for `rpc_cancel` or batch stream events */
+  readonly requestId?: string;
+  /** Headers-like metadata read by backend middleware (e.g. `authorization`) */
+  readonly metadata?: Record<string, string>;
+}
+
 // =============================================================================
 // Middleware Types
 // =============================================================================
@@ -0,0 +1,30 @@
+// =============================================================================
+// @tauri-nexus/rpc-core - Request Envelope
+// =============================================================================
+// Builds the `meta` argument every backend command takes.
+
+import type { RequestEnvelope } from "../core/types";
+
+/**
+ * Build the request envelope from call metadata.
+ *
+ * The backend takes string metadata values, so numbers and booleans are
+ * stringified and any other value is left out.
+ */
+export const toRequestEnvelope = (
+  meta?: Readonly<Record<string, unknown>>,
+  requestId?: string,
+): RequestEnvelope => {
+  const metadata: Record<string, string> = {};
+  for (const [key, value] of Object.entries(meta ?? {})) {
+    if (typeof value === "string") {
+      metadata[key] = value;
+    } else if (typeof value === "number" || typeof value === "boolean") {
+      metadata[key] = String(value);
+    }
+  }
+  return {
+    ...(requestId ? { requestId } : {}),
+    ...(Object.keys(metadata).length > 0 ? { metadata } : {}),
+  };
+};
@@ -15,19 +15,58 @@ import {
   fromTransportError,
   type RpcConfig,
   type RpcTransport,
+  type CallTransportOptions,
+  type BatchTransportOptions,
+  type SubscribeTransportOptions,
 } from "@tauri-nexus/rpc-effect";
 import { createEventIterator } from "../subscription";
+import { toRequestEnvelope } from "./envelope";
+
+export { toRequestEnvelope } from "./envelope";
 
 // =============================================================================
 // Tauri Transport
 // =============================================================================
 
+export const generateRequestId = (): string =>
+  typeof crypto !== "undefined" && crypto.randomUUID
+    ? crypto.randomUUID()
+    : `${Date.now().toString(36)}-${Math.random().toString(36).slice(2)}`;
+
 const tauriTransport: RpcTransport = {
-  call: async <T>(path: string, input: unknown): Promise<T> => {
-    return invoke<T>("plugin:rpc|rpc_call", { path, input });
+  call: async <T>(
+    path: string,
+    input: unknown,
+    options?: CallTransportOptions,
+  ): Promise<T> => {
+    const signal = options?.signal;
+    if (!signal) {
+      return invoke<T>("plugin:rpc|rpc_call", {
+        path,
+        input,
+        meta: toRequestEnvelope(options?.meta),
+      });
+    }
+
+    // Tag the call so the backend can cancel it when the signal aborts.
+    const requestId = generateRequestId();
+    const onAbort = () => {
+      void invoke("plugin:rpc|rpc_cancel", { requestId }).catch(() => {});
+    };
+    signal.addEventListener("abort", onAbort, { once: true });
+    try {
+      return await invoke<T>("plugin:rpc|rpc_call", {
+        path,
+        input,
+        meta: toRequestEnvelope(options?.meta, requestId),
+      });
+    } finally {
+      signal.removeEventListener("abort", onAbort);
+    }
   },
   callBatch: async <T>(
     requests: readonly { id: string; path: string; input: unknown }[],
+    options?: BatchTransportOptions,
   ) => {
     const normalizedRequests = requests.map((req) => ({
       ...req,
@@ -41,12 +80,13 @@ const tauriTransport: RpcTransport = {
       }[];
     }>("plugin:rpc|rpc_call_batch", {
       batch: { requests: normalizedRequests },
+      meta: toRequestEnvelope(options?.meta),
     });
   },
   subscribe: async <T>(
     path: string,
     input: unknown,
-    options?: { lastEventId?: string; signal?: AbortSignal },
+    options?: SubscribeTransportOptions,
   ) => {
     return createEventIterator<T>(path, input, options);
   },
@@ -151,6 +191,8 @@ export {
   // Rate limit helpers
   isRateLimitError,
   getRateLimitRetryAfter,
+  getRateLimitInfo,
+  type RateLimitInfo,
   // Validation
   validatePath,
   validatePaths,
@@ -6,6 +6,7 @@
 import { Effect } from "effect";
 import type {
   SingleRequest,
+  BatchEntryOptions,
   BatchCallOptions,
   BatchResponse,
 } from "../core/types";
@@ -54,11 +55,17 @@ export class TypedBatchBuilder<
     id: TId,
     path: TPath,
     input: GetInputAtPath<TContract, TPath>,
+    options?: BatchEntryOptions,
   ): TypedBatchBuilder<
     TContract,
     TOutputMap & Record<TId, GetOutputAtPath<TContract, TPath>>
   > {
-    this.requests.push({ id: id as string, path: path as string, input });
+    this.requests.push({
+      id: id as string,
+      path: path as string,
+      input,
+      ...(options?.dependsOn && { dependsOn: options.dependsOn }),
+    });
     return this as any;
   }
 
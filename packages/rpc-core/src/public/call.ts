@@ -5,6 +5,7 @@
 
 import { Effect, pipe, Layer } from "effect";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import type {
   CallOptions,
   SubscriptionOptions,
@@ -13,9 +14,13 @@ import type {
   SingleRequest,
   BatchCallOptions,
   BatchRequest,
+  BatchResult,
+  BatchMetrics,
+  BatchStreamEvent,
+  CacheInvalidation,
   EventIterator,
 } from "../core/types";
-import { getConfig } from "../client/config";
+import { getConfig, getEventPrefix } from "../client/config";
 import {
   callEffect,
   subscribeEffect,
@@ -26,6 +31,8 @@ import {
   RpcInterceptorService,
   RpcLoggerService,
   TauriTransportLayer,
+  generateRequestId,
+  toRequestEnvelope,
   type RpcServices,
   type RpcEffectError,
   type RpcInterceptor,
@@ -223,13 +230,17 @@ const executeBatchEffect = <T = unknown>(
       input: req.input === undefined ? null : req.input,
     }));
 
-    const batchRequest: BatchRequest = { requests: normalizedRequests };
+    const batchRequest: BatchRequest = {
+      requests: normalizedRequests,
+      atomic: options?.atomic,
+    };
     const timeoutMs = options?.timeout;
 
     const executeInvoke = Effect.tryPromise({
       try: () =>
         invoke<BatchResponse<T>>("plugin:rpc|rpc_call_batch", {
           batch: batchRequest,
+          meta: toRequestEnvelope(options?.meta),
         }),
       catch: (error) => parseEffectError(error, "batch", timeoutMs),
     });
@@ -271,3 +282,62 @@ export async function executeBatch<T = unknown>(
     throw rpcError;
   }
 }
+
+/**
+ * Execute batch requests, receiving each result as soon as it finishes.
+ *
+ * Resolves with the batch metrics once every request has completed.
+ */
+export async function executeBatchStream<T = unknown>(
+  requests: SingleRequest[],
+  onResult: (result: BatchResult<T>) => void,
+  options?: BatchCallOptions,
+): Promise<BatchMetrics> {
+  for (const req of requests) {
+    await Effect.runPromise(validatePathEffect(req.path));
+  }
+
+  const batch: BatchRequest = {
+    requests: requests.map((req) => ({
+      ...req,
+      input: req.input === undefined ? null : req.input,
+    })),
+    atomic: options?.atomic,
+  };
+  const requestId = generateRequestId();
+  const unlisten = await listen<BatchStreamEvent<T>>(
+    `${getEventPrefix()}batch_${requestId}`,
+    (event) => {
+      if (event.payload.type === "result") {
+        onResult(event.payload.result);
+      }
+    },
+  );
+
+  try {
+    return await invoke<BatchMetrics>("plugin:rpc|rpc_call_batch_stream", {
+      batch,
+      meta: toRequestEnvelope(options?.meta, requestId),
+    });
+  } catch (error) {
+    throw toRpcError(parseEffectError(error, "batch", options?.timeout));
+  } finally {
+    unlisten();
+  }
+}
+
+/**
+ * Listen for server cache invalidations.
+ *
+ * Requires the app to call `emit_cache_invalidations` for its cache. Events
+ * use the configured `eventPrefix`. Resolves with a function that stops
+ * listening.
+ */
+export async function onCacheInvalidation(
+  handler: (invalidation: CacheInvalidation) => void,
+): Promise<() => void> {
+  return listen<CacheInvalidation>(
+    `${getEventPrefix()}cache_invalidation`,
+    (event) => handler(event.payload),
+  );
+}
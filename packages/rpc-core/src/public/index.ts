@@ -21,6 +21,12 @@ export type {
   Event,
   EventMeta,
   SingleRequest,
+  BatchEntryOptions,
+  CompensationResult,
+  BatchMetrics,
+  BatchStreamEvent,
+  CacheInvalidation,
+  InvalidatedEntry,
   BatchRequest,
   BatchResult,
   BatchResponse,
@@ -28,6 +34,7 @@ export type {
   SubscriptionOptions,
   BatchCallOptions,
   SubscribeRequest,
+  RequestEnvelope,
   RequestContext,
   ResponseContext,
   Middleware,
@@ -84,6 +91,8 @@ export {
   // Rate limit
   isRateLimitError,
   getRateLimitRetryAfter,
+  getRateLimitInfo,
+  type RateLimitInfo,
 } from "@tauri-nexus/rpc-effect";
 
 // rpc-core specific error utilities
@@ -115,7 +124,13 @@ export {
 // Core Call Functions
 // =============================================================================
 
-export { call, subscribe, executeBatch } from "./call";
+export {
+  call,
+  subscribe,
+  executeBatch,
+  executeBatchStream,
+  onCacheInvalidation,
+} from "./call";
 
 // =============================================================================
 // Batch Operations
@@ -35,6 +35,8 @@ import {
   createAsyncIterator,
   type RpcEffectError,
 } from "@tauri-nexus/rpc-effect";
+import { toRequestEnvelope } from "../internal/envelope";
+import { getEventPrefix } from "../client/config";
 
 // =============================================================================
 // Internal State Type (extends base with Tauri-specific fields)
@@ -53,11 +55,16 @@ interface TauriSubscriptionState extends SubscriptionState {
  */
 const subscribeToBackend = (
   request: SubscribeRequest,
+  meta: Record<string, unknown> | undefined,
   path: string,
   cleanup: () => void,
 ): Effect.Effect<void, RpcEffectError> =>
   Effect.tryPromise({
-    try: () => invoke("plugin:rpc|rpc_subscribe", { request }),
+    try: () =>
+      invoke("plugin:rpc|rpc_subscribe", {
+        request,
+        meta: toRequestEnvelope(meta),
+      }),
     catch: (error) => {
       cleanup();
       return createNetworkError(path, error);
@@ -83,11 +90,12 @@ const createConnectEffect = <T>(
   stateRef: Ref.Ref<TauriSubscriptionState>,
   path: string,
   input: unknown,
+  meta: Record<string, unknown> | undefined,
   eventQueue: Queue.Queue<QueueItem<T>>,
 ): Effect.Effect<void, RpcEffectError> =>
   Effect.gen(function* () {
     const state = yield* Ref.get(stateRef);
-    const eventName = `rpc:subscription:sub_${state.id}`;
+    const eventName = `${getEventPrefix()}sub_${state.id}`;
 
     const unlisten = yield* Effect.tryPromise({
       try: () =>
@@ -106,7 +114,7 @@ const createConnectEffect = <T>(
       lastEventId: state.lastEventId,
     };
 
-    yield* subscribeToBackend(request, path, unlisten);
+    yield* subscribeToBackend(request, meta, path, unlisten);
   });
 
 /**
@@ -140,6 +148,7 @@ const createReconnectEffect =
     stateRef: Ref.Ref<TauriSubscriptionState>,
     path: string,
     input: unknown,
+    meta: Record<string, unknown> | undefined,
     eventQueue: Queue.Queue<QueueItem<T>>,
   ) =>
   (newId: string): Effect.Effect<void, RpcEffectError> =>
@@ -147,7 +156,7 @@ const createReconnectEffect =
       yield* resetForReconnect(stateRef, newId);
       yield* Ref.update(stateRef, (s) => ({ ...s, unlisten: null }));
 
-      yield* createConnectEffect(stateRef, path, input, eventQueue);
+      yield* createConnectEffect(stateRef, path, input, meta, eventQueue);
       yield* resetReconnectAttempts(stateRef);
     });
 
@@ -178,7 +187,13 @@ const createEventIteratorEffect = <T>(
     const eventQueue = yield* createEventQueue<T>();
 
     // Initial connection
-    yield* createConnectEffect(stateRef, path, input, eventQueue);
+    yield* createConnectEffect(
+      stateRef,
+      path,
+      input,
+      options.meta,
+      eventQueue,
+    );
 
     // Setup abort signal handler
     if (options.signal) {
@@ -194,7 +209,13 @@ const createEventIteratorEffect = <T>(
     };
 
     const disconnect = createDisconnectEffect(stateRef, eventQueue);
-    const reconnect = createReconnectEffect(stateRef, path, input, eventQueue);
+    const reconnect = createReconnectEffect(
+      stateRef,
+      path,
+      input,
+      options.meta,
+      eventQueue,
+    );
 
     // Create async iterator config
     const iteratorConfig: AsyncIteratorConfig<T, TauriSubscriptionState> = {
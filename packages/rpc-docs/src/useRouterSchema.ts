@@ -12,79 +12,25 @@ import type { RouterSchema, ProcedureSchema } from "./types";
  */
 export const ROUTER_SCHEMA_KEY = ["rpc", "schema"] as const;
 
-/**
- * Infer procedure type from path naming conventions.
- * - Paths containing 'stream' or ending with subscription-like names → subscription
- * - Paths containing 'create', 'update', 'delete', 'set', 'add', 'remove' → mutation
- * - Everything else → query
- */
-function inferProcedureType(
-  path: string,
-): "query" | "mutation" | "subscription" {
-  const lowerPath = path.toLowerCase();
-
-  // Check for subscription patterns
-  if (
-    lowerPath.includes("stream") ||
-    lowerPath.includes("subscribe") ||
-    lowerPath.includes("watch")
-  ) {
-    return "subscription";
-  }
-
-  // Check for mutation patterns
-  const mutationPatterns = [
-    "create",
-    "update",
-    "delete",
-    "set",
-    "add",
-    "remove",
-    "send",
-    "post",
-    "put",
-  ];
-  for (const pattern of mutationPatterns) {
-    if (lowerPath.includes(pattern)) {
-      return "mutation";
-    }
-  }
-
-  return "query";
-}
-
 /**
  * Fetch router schema from the backend.
- * The backend returns procedure names, which we convert to a RouterSchema.
+ * The backend generates the schema from the live router, including procedure
+ * types, input/output schemas, tags and deprecation flags.
  */
 async function fetchRouterSchema(): Promise<RouterSchema> {
-  // Call the plugin command to get procedure names
-  const procedureNames = await invoke<string[]>("plugin:rpc|rpc_procedures");
+  const schema = await invoke<RouterSchema>("plugin:rpc|rpc_schema");
 
-  // Build schema from procedure names
+  // Normalize fields the backend omits when empty
   const procedures: Record<string, ProcedureSchema> = {};
-
-  for (const path of procedureNames) {
-    const procedureType = inferProcedureType(path);
-
+  for (const [path, procedure] of Object.entries(schema.procedures)) {
     procedures[path] = {
-      procedure_type: procedureType,
-      description: undefined,
-      input: undefined,
-      output: undefined,
-      deprecated: false,
-      tags: [],
-      metadata: undefined,
+      ...procedure,
+      deprecated: procedure.deprecated ?? false,
+      tags: procedure.tags ?? [],
     };
   }
 
-  return {
-    version: "1.0.0",
-    name: "RPC API",
-    description: "Available RPC procedures",
-    procedures,
-    metadata: undefined,
-  };
+  return { ...schema, procedures };
 }
 
 /**
@@ -12,6 +12,7 @@ import {
   createRpcError,
   isRateLimitError,
   getRateLimitRetryAfter,
+  getRateLimitInfo,
   isRpcErrorShape,
   parseJsonError,
   createCallErrorFromShape,
@@ -226,6 +227,34 @@ describe("TC008: Error Serialization and Parsing", () => {
       };
       expect(getRateLimitRetryAfter(error)).toBeUndefined();
     });
+
+    it("should extract quota details from rate limit error", () => {
+      const error: RpcError = {
+        code: "RATE_LIMITED",
+        message: "Too many requests",
+        details: {
+          limit: 10,
+          remaining: 0,
+          cost: 1,
+          reset_at: 1_700_000_000_000,
+          retry_after_ms: 5000,
+          retry_after_secs: 5,
+          layer: "client",
+        },
+      };
+
+      expect(getRateLimitInfo(error)).toEqual({
+        limit: 10,
+        remaining: 0,
+        cost: 1,
+        resetAt: 1_700_000_000_000,
+        retryAfterMs: 5000,
+        layer: "client",
+      });
+      expect(
+        getRateLimitInfo({ code: "NOT_FOUND", message: "Not found" }),
+      ).toBeUndefined();
+    });
   });
 
   describe("isRpcErrorShape", () => {
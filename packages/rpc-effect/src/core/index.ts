@@ -10,6 +10,8 @@ export type {
   ValidationIssue,
   RpcConfig,
   RpcTransport,
+  CallTransportOptions,
+  BatchTransportOptions,
   SubscribeTransportOptions,
   RpcInterceptorChain,
   RpcInterceptor,
@@ -40,9 +40,14 @@ export interface RpcConfig {
 
 /** Transport layer abstraction for making actual RPC calls */
 export interface RpcTransport {
-  readonly call: <T>(path: string, input: unknown) => Promise<T>;
+  readonly call: <T>(
+    path: string,
+    input: unknown,
+    options?: CallTransportOptions,
+  ) => Promise<T>;
   readonly callBatch: <T>(
     requests: readonly { id: string; path: string; input: unknown }[],
+    options?: BatchTransportOptions,
   ) => Promise<{
     results: readonly {
       id: string;
@@ -62,9 +67,23 @@ export interface RpcTransport {
   ) => import("./errors").RpcEffectError;
 }
 
+export interface CallTransportOptions {
+  /** Aborting asks the backend to cancel the in-flight call */
+  readonly signal?: AbortSignal;
+  /** Metadata sent to the backend with the call (e.g. `authorization`) */
+  readonly meta?: Readonly<Record<string, unknown>>;
+}
+
+export interface BatchTransportOptions {
+  /** Metadata sent to the backend with the batch */
+  readonly meta?: Readonly<Record<string, unknown>>;
+}
+
 export interface SubscribeTransportOptions {
   readonly lastEventId?: string;
   readonly signal?: AbortSignal;
+  /** Metadata sent to the backend with the subscription */
+  readonly meta?: Readonly<Record<string, unknown>>;
 }
 
 /** Interceptor chain for middleware-like functionality */
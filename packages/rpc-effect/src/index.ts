@@ -16,6 +16,8 @@ export type {
   ValidationIssue,
   RpcConfig,
   RpcTransport,
+  CallTransportOptions,
+  BatchTransportOptions,
   SubscribeTransportOptions,
   RpcInterceptorChain,
   RpcInterceptor,
@@ -91,6 +93,8 @@ export {
   createRpcError,
   isRateLimitError,
   getRateLimitRetryAfter,
+  getRateLimitInfo,
+  type RateLimitInfo,
   type ErrorParserOptions,
   isRpcErrorShape,
   parseJsonError,
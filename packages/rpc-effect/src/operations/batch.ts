@@ -4,6 +4,7 @@
 
 import { Effect, Either } from "effect";
 import type { RpcEffectError } from "../core/errors";
+import type { BatchTransportOptions } from "../core/types";
 import {
   RpcTransportService,
   RpcLoggerService,
@@ -58,6 +59,7 @@ export const validateBatchRequests = (
  */
 export const batchCall = <T = unknown>(
   requests: readonly BatchRequestItem[],
+  options?: BatchTransportOptions,
 ): Effect.Effect<BatchResponse<T>, RpcEffectError, RpcServices> =>
   Effect.gen(function* () {
     const transport = yield* RpcTransportService;
@@ -72,7 +74,7 @@ export const batchCall = <T = unknown>(
     const parseError = transport.parseError ?? defaultParseError;
 
     const response = yield* Effect.tryPromise({
-      try: () => transport.callBatch<T>(requests),
+      try: () => transport.callBatch<T>(requests, options),
       catch: (error) => parseError(error, "batch"),
     });
 
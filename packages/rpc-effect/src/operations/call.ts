@@ -217,7 +217,10 @@ const coreCall = <T>(
           const controller = new AbortController();
           const timeoutId = setTimeout(() => controller.abort(), timeoutMs);
           try {
-            const res = await transport.call<T>(path, input);
+            const res = await transport.call<T>(path, input, {
+              signal: options.signal,
+              meta: ctx.meta,
+            });
             clearTimeout(timeoutId);
             return res;
           } catch (error) {
@@ -225,7 +228,10 @@ const coreCall = <T>(
             throw error;
           }
         }
-        return transport.call<T>(path, input);
+        return transport.call<T>(path, input, {
+          signal: options.signal,
+          meta: ctx.meta,
+        });
       },
       getParseError(transport),
     );
@@ -509,6 +515,7 @@ export function subscribe<TInput = unknown, TEvent = unknown>(
         transport.subscribe<unknown>(path, validatedInput, {
           lastEventId: baseOptions.lastEventId,
           signal: baseOptions.signal,
+          meta: baseOptions.meta,
         }),
       catch: (error) => getParseError(transport)(error, path),
     });
@@ -123,3 +123,41 @@ export const getRateLimitRetryAfter = (error: RpcError): number | undefined => {
     ? details.retry_after_ms
     : undefined;
 };
+
+/**
+ * Quota details attached to a rate limit error.
+ */
+export interface RateLimitInfo {
+  /** Requests allowed by the exhausted limit */
+  readonly limit?: number;
+  /** Requests left in the current window */
+  readonly remaining?: number;
+  /** Units of quota the refused call needed */
+  readonly cost?: number;
+  /** When the quota resets, in Unix milliseconds */
+  readonly resetAt?: number;
+  /** How long to wait before retrying, in milliseconds */
+  readonly retryAfterMs?: number;
+  /** Limit layer that rejected the call: global, namespace, procedure or client */
+  readonly layer?: string;
+}
+
+/**
+ * Extract quota details from rate limit error.
+ */
+export const getRateLimitInfo = (
+  error: RpcError,
+): RateLimitInfo | undefined => {
+  if (error.code !== "RATE_LIMITED") return undefined;
+  const details = (error.details ?? {}) as Record<string, unknown>;
+  const number = (value: unknown) =>
+    typeof value === "number" ? value : undefined;
+  return {
+    limit: number(details.limit),
+    remaining: number(details.remaining),
+    cost: number(details.cost),
+    resetAt: number(details.reset_at),
+    retryAfterMs: number(details.retry_after_ms),
+    layer: typeof details.layer === "string" ? details.layer : undefined,
+  };
+};
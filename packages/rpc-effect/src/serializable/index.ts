@@ -3,6 +3,7 @@
 // =============================================================================
 
 export type { RpcError, RpcErrorCode, RpcErrorShape } from "./types";
+export type { RateLimitInfo } from "./conversion";
 
 export {
   toRpcError,
@@ -12,6 +13,7 @@ export {
   createRpcError,
   isRateLimitError,
   getRateLimitRetryAfter,
+  getRateLimitInfo,
 } from "./conversion";
 
 export {
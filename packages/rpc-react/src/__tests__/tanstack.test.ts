@@ -3,7 +3,11 @@
 // =============================================================================
 
 import { describe, it, expect, vi } from "vitest";
-import { createTanstackQueryUtils } from "@tauri-nexus/rpc-react";
+import {
+  createTanstackQueryUtils,
+  invalidationQueryKeys,
+  rpcRetryDelay,
+} from "@tauri-nexus/rpc-react";
 
 // =============================================================================
 // Mock Client (Plain Object)
@@ -617,3 +621,69 @@ describe("createTanstackQueryUtils with proxy client", () => {
     });
   });
 });
+
+describe("invalidationQueryKeys", () => {
+  it("maps entries to query keys", () => {
+    expect(
+      invalidationQueryKeys({
+        cause: "tags",
+        tags: ["user:1"],
+        entries: [
+          { path: "user.get", input: { id: 1 } },
+          { path: "user.list", input: null },
+        ],
+      }),
+    ).toEqual([["user", "get", { id: 1 }], ["user", "list"]]);
+  });
+
+  it("maps brace patterns to the removed entries", () => {
+    expect(
+      invalidationQueryKeys({
+        cause: "pattern",
+        pattern: "user.{get,list}",
+        entries: [
+          { path: "user.get", input: { id: 1 } },
+          { path: "user.list", input: null },
+        ],
+      }),
+    ).toEqual([["user", "get", { id: 1 }], ["user", "list"]]);
+  });
+
+  it("maps negated patterns to the removed entries", () => {
+    expect(
+      invalidationQueryKeys({
+        cause: "pattern",
+        pattern: "!admin.*",
+        entries: [{ path: "user.get", input: { id: 2 } }],
+      }),
+    ).toEqual([["user", "get", { id: 2 }]]);
+  });
+
+  it("maps patterns that removed nothing to no keys", () => {
+    expect(
+      invalidationQueryKeys({ cause: "pattern", pattern: "user.*", entries: [] }),
+    ).toEqual([]);
+  });
+
+  it("maps all to the empty key", () => {
+    expect(invalidationQueryKeys({ cause: "all", entries: [] })).toEqual([[]]);
+  });
+});
+
+describe("rpcRetryDelay", () => {
+  it("waits for the server's retry hint on rate limit errors", () => {
+    const error = {
+      code: "RATE_LIMITED",
+      message: "Too many requests",
+      details: { retry_after_ms: 4200 },
+    };
+    expect(rpcRetryDelay(0, error)).toBe(4200);
+  });
+
+  it("backs off exponentially otherwise", () => {
+    const error = { code: "INTERNAL_ERROR", message: "boom" };
+    expect(rpcRetryDelay(0, error)).toBe(1000);
+    expect(rpcRetryDelay(2, error)).toBe(4000);
+    expect(rpcRetryDelay(10, error)).toBe(30_000);
+  });
+});
@@ -32,6 +32,8 @@ export {
 
 export {
   createTanstackQueryUtils,
+  invalidationQueryKeys,
+  rpcRetryDelay,
   type TanstackQueryUtils,
   type CreateTanstackQueryUtilsOptions,
   type QueryOptionsResult,
@@ -5,6 +5,11 @@
 // TanStack Query options automatically, similar to oRPC's approach.
 
 import type { QueryKey } from "@tanstack/react-query";
+import {
+  getRateLimitRetryAfter,
+  isRateLimitError,
+  type CacheInvalidation,
+} from "@tauri-nexus/rpc-core";
 
 // =============================================================================
 // Types
@@ -308,3 +313,63 @@ export function createTanstackQueryUtils<
     InferContract<TClient>
   >;
 }
+
+// =============================================================================
+// Server Cache Invalidation
+// =============================================================================
+
+/**
+ * Query keys affected by a server cache invalidation.
+ *
+ * Keys follow `createTanstackQueryUtils`: `[...path, input]`. Tag and pattern
+ * invalidations map the entries the server removed, so brace and negated
+ * patterns stay exact; `all` maps to the empty key, which matches every query.
+ *
+ * @example
+ * ```ts
+ * onCacheInvalidation((invalidation) => {
+ *   for (const queryKey of invalidationQueryKeys(invalidation)) {
+ *     queryClient.invalidateQueries({ queryKey });
+ *   }
+ * });
+ * ```
+ */
+export function invalidationQueryKeys(
+  invalidation: CacheInvalidation,
+): QueryKey[] {
+  switch (invalidation.cause) {
+    case "all":
+      return [[]];
+    default:
+      return invalidation.entries.map(({ path, input }) =>
+        input === null || input === undefined
+          ? path.split(".")
+          : [...path.split("."), input],
+      );
+  }
+}
+
+// =============================================================================
+// Retry Delay
+// =============================================================================
+
+/**
+ * Retry delay honouring server rate limit hints.
+ *
+ * Rate limited calls wait for the `retry_after_ms` the server reported;
+ * other failures back off exponentially, capped at 30 seconds.
+ *
+ * @example
+ * ```ts
+ * const queryClient = new QueryClient({
+ *   defaultOptions: { queries: { retryDelay: rpcRetryDelay } },
+ * });
+ * ```
+ */
+export function rpcRetryDelay(failureCount: number, error: unknown): number {
+  if (isRateLimitError(error)) {
+    const retryAfter = getRateLimitRetryAfter(error);
+    if (retryAfter !== undefined) return retryAfter;
+  }
+  return Math.min(1000 * 2 ** failureCount, 30_000);
+}